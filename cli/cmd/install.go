@@ -44,7 +44,9 @@ You can use the --ignore-cluster flag if you just want to generate the installat
 
 var (
 	templatesCrdFiles = []string{
+		"templates/gateway.networking.k8s.io/grpcroute.yaml",
 		"templates/gateway.networking.k8s.io/httproute.yaml",
+		"templates/gateway.networking.k8s.io/referencegrant.yaml",
 		"templates/policy/authorization-policy.yaml",
 		"templates/policy/httproute.yaml",
 		"templates/policy/meshtls-authentication.yaml",
@@ -52,6 +54,7 @@ var (
 		"templates/policy/server-authorization.yaml",
 		"templates/policy/server.yaml",
 		"templates/serviceprofile.yaml",
+		"templates/workload/external-workload.yaml",
 	}
 
 	templatesControlPlane = []string{
@@ -229,6 +229,7 @@ func TestRender(t *testing.T) {
 		{defaultValues, "install_custom_domain.golden", values.Options{}},
 		{defaultValues, "install_values_file.golden", values.Options{ValueFiles: []string{filepath.Join("testdata", "install_config.yaml")}}},
 		{defaultValues, "install_default_token.golden", values.Options{Values: []string{"identity.serviceAccountTokenProjection=false"}}},
+		{defaultValues, "install_discover_cluster_networks.golden", values.Options{Values: []string{"policyController.discoverClusterNetworks=true", "policyController.serviceNetworks=10.96.0.0/12"}}},
 	}
 
 	for i, tc := range testCases {
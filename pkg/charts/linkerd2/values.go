@@ -138,10 +138,12 @@ type (
 
 	// PolicyController contains the fields to configure the policy controller container
 	PolicyController struct {
-		Image              *Image     `json:"image"`
-		Resources          *Resources `json:"resources"`
-		LogLevel           string     `json:"logLevel"`
-		DefaultAllowPolicy string     `json:"defaultAllowPolicy"`
+		Image                   *Image     `json:"image"`
+		Resources               *Resources `json:"resources"`
+		LogLevel                string     `json:"logLevel"`
+		DefaultAllowPolicy      string     `json:"defaultAllowPolicy"`
+		DiscoverClusterNetworks bool       `json:"discoverClusterNetworks"`
+		ServiceNetworks         string     `json:"serviceNetworks"`
 	}
 
 	// Image contains the details to define a container image
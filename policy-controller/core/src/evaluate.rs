@@ -0,0 +1,1030 @@
+//! A reference implementation of inbound policy enforcement.
+//!
+//! Proxies enforce the policies that the controller serves. This module
+//! evaluates an `InboundServer` for a client connection and, optionally, an
+//! HTTP request so that tools (e.g. simulators) may explain how a request is
+//! handled without running a proxy.
+
+use crate::{
+    grpc_route::{GrpcRouteMatch, InboundGrpcRoute},
+    http_route::{
+        Fragment, HeaderMatch, HostMatch, HttpRouteMatch, InboundHttpRoute, PathMatch,
+        QueryParamMatch,
+    },
+    AuthorizationAction, AuthorizationRef, ClientAuthentication, ClientAuthorization,
+    InboundServer, ProxyProtocol,
+};
+use ahash::AHashMap as HashMap;
+use ipnet::IpNet;
+use regex::Regex;
+use std::net::IpAddr;
+
+/// Describes a client connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Client {
+    pub addr: IpAddr,
+
+    /// Whether the connection is meshed, i.e. uses mutual TLS.
+    pub tls: bool,
+
+    /// The client's mesh identity, if it is authenticated.
+    pub identity: Option<String>,
+}
+
+/// The result of evaluating a server's policy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decision<'s> {
+    /// The route and rule that matched the request. Unset when no request is
+    /// evaluated, when the server is not an HTTP server, or when the server
+    /// has no routes (in which case the server's authorizations apply).
+    pub route: Option<RouteMatch<'s>>,
+
+    pub outcome: Outcome<'s>,
+
+    /// The authorizations that did not match the client, with the reason each
+    /// did not match.
+    pub unmatched: Vec<(&'s AuthorizationRef, Mismatch)>,
+}
+
+/// Identifies the route and rule that match a request.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RouteMatch<'s> {
+    Http { name: &'s str, rule: usize },
+    Grpc { name: &'s str, rule: usize },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome<'s> {
+    /// The client is permitted by an `Allow` authorization or, if no allow
+    /// authorization matches, by an `Audit` authorization. The action is
+    /// `Audit` if the client is flagged by either an `Audit` or an
+    /// `AuditDeny` authorization.
+    Permitted {
+        authorization: &'s AuthorizationRef,
+        action: AuthorizationAction,
+    },
+
+    /// The client is refused by a `Deny` authorization.
+    Denied { authorization: &'s AuthorizationRef },
+
+    /// No authorization matches the client.
+    Unauthorized,
+
+    /// The server has routes but none match the request.
+    NoRoute,
+}
+
+/// Describes why an authorization does not match a client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The client's address is not in any of the authorization's networks.
+    Network,
+
+    /// The authorization requires a meshed client.
+    Tls,
+
+    /// The authorization requires an authenticated client with one of the
+    /// given identities.
+    Identity,
+}
+
+/// Evaluates the server's policy for a client and, optionally, a request.
+///
+/// Authorizations that do not specify networks apply to `cluster_networks`.
+/// Deny authorizations take precedence over allow authorizations, which take
+/// precedence over audit authorizations. Permitted clients that match an
+/// audited deny authorization are flagged by it.
+pub fn evaluate<'s>(
+    server: &'s InboundServer,
+    cluster_networks: &[IpNet],
+    client: &Client,
+    request: Option<&http::Request<()>>,
+) -> Decision<'s> {
+    let (route, authorizations) = match (request, &server.protocol) {
+        (Some(_), ProxyProtocol::Opaque | ProxyProtocol::Tls) | (None, _) => {
+            (None, &server.authorizations)
+        }
+        (Some(req), ProxyProtocol::Grpc) if !server.grpc_routes.is_empty() => {
+            match select_grpc_route(&server.grpc_routes, req) {
+                Some((name, rule, route)) => {
+                    (Some(RouteMatch::Grpc { name, rule }), &route.authorizations)
+                }
+                None => return Decision::no_route(),
+            }
+        }
+        (Some(req), ProxyProtocol::Detect { .. } | ProxyProtocol::Http1 | ProxyProtocol::Http2)
+            if !server.http_routes.is_empty() =>
+        {
+            match select_http_route(&server.http_routes, req) {
+                Some((name, rule, route)) => {
+                    (Some(RouteMatch::Http { name, rule }), &route.authorizations)
+                }
+                None => return Decision::no_route(),
+            }
+        }
+        // Servers without routes apply their authorizations to all requests.
+        (Some(_), _) => (None, &server.authorizations),
+    };
+
+    let (outcome, unmatched) = authorize(authorizations, cluster_networks, client);
+    Decision {
+        route,
+        outcome,
+        unmatched,
+    }
+}
+
+/// Determines whether a client matches an authorization.
+pub fn authorization_matches(
+    authz: &ClientAuthorization,
+    cluster_networks: &[IpNet],
+    client: &Client,
+) -> Result<(), Mismatch> {
+    let in_network = if authz.networks.is_empty() {
+        cluster_networks
+            .iter()
+            .any(|net| net.contains(&client.addr))
+    } else {
+        authz.networks.iter().any(|net| net.contains(&client.addr))
+    };
+    if !in_network {
+        return Err(Mismatch::Network);
+    }
+
+    match &authz.authentication {
+        ClientAuthentication::Unauthenticated => {}
+        ClientAuthentication::TlsUnauthenticated => {
+            if !client.tls {
+                return Err(Mismatch::Tls);
+            }
+        }
+        ClientAuthentication::TlsAuthenticated(identities) => {
+            if !client.tls {
+                return Err(Mismatch::Tls);
+            }
+            let id = client.identity.as_deref().ok_or(Mismatch::Identity)?;
+            if !identities.iter().any(|m| m.matches(id)) {
+                return Err(Mismatch::Identity);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn authorize<'s>(
+    authorizations: &'s HashMap<AuthorizationRef, ClientAuthorization>,
+    cluster_networks: &[IpNet],
+    client: &Client,
+) -> (Outcome<'s>, Vec<(&'s AuthorizationRef, Mismatch)>) {
+    // Authorizations are considered in a stable order so that results are
+    // deterministic.
+    let mut authzs = authorizations.iter().collect::<Vec<_>>();
+    authzs.sort_by_key(|(reference, _)| *reference);
+
+    let mut unmatched = Vec::new();
+    let mut permitted = None;
+    let mut audit_denied = None;
+    for (reference, authz) in authzs {
+        match authorization_matches(authz, cluster_networks, client) {
+            Err(mismatch) => unmatched.push((reference, mismatch)),
+            Ok(()) => match authz.action {
+                AuthorizationAction::Deny => {
+                    return (
+                        Outcome::Denied {
+                            authorization: reference,
+                        },
+                        unmatched,
+                    );
+                }
+                AuthorizationAction::Allow => {
+                    if !matches!(permitted, Some((_, AuthorizationAction::Allow))) {
+                        permitted = Some((reference, AuthorizationAction::Allow));
+                    }
+                }
+                AuthorizationAction::Audit => {
+                    if permitted.is_none() {
+                        permitted = Some((reference, AuthorizationAction::Audit));
+                    }
+                }
+                AuthorizationAction::AuditDeny => {
+                    audit_denied = audit_denied.or(Some(reference));
+                }
+            },
+        }
+    }
+
+    let outcome = match (permitted, audit_denied) {
+        // Permitted clients that an audited deny policy would refuse are
+        // flagged by that policy.
+        (Some(_), Some(authorization)) => Outcome::Permitted {
+            authorization,
+            action: AuthorizationAction::Audit,
+        },
+        (Some((authorization, action)), None) => Outcome::Permitted {
+            authorization,
+            action,
+        },
+        (None, _) => Outcome::Unauthorized,
+    };
+    (outcome, unmatched)
+}
+
+/// Selects the route with the most specific match for the request, as
+/// ordered by `http_route::fragments_by_precedence`.
+fn select_http_route<'s>(
+    routes: &'s HashMap<String, InboundHttpRoute>,
+    req: &http::Request<()>,
+) -> Option<(&'s str, usize, &'s InboundHttpRoute)> {
+    let host = request_host(req);
+    InboundHttpRoute::fragments(routes.iter())
+        .into_iter()
+        .find_map(|fragment| {
+            let Fragment {
+                name,
+                route,
+                hostname,
+                rules,
+            } = fragment;
+            if !hostname.map_or(true, |h| host_matches(&route.hostnames[h], host.as_deref())) {
+                return None;
+            }
+            rules
+                .into_iter()
+                .find(|(rule, matches)| {
+                    matches.is_empty()
+                        || matches
+                            .iter()
+                            .any(|m| http_matches(&route.rules[*rule].matches[*m], req))
+                })
+                .map(|(rule, _)| (name.as_str(), rule, route))
+        })
+}
+
+/// Selects the route with the most specific match for the request, as
+/// ordered by `grpc_route::fragments_by_precedence`.
+fn select_grpc_route<'s>(
+    routes: &'s HashMap<String, InboundGrpcRoute>,
+    req: &http::Request<()>,
+) -> Option<(&'s str, usize, &'s InboundGrpcRoute)> {
+    let host = request_host(req);
+    InboundGrpcRoute::fragments(routes.iter())
+        .into_iter()
+        .find_map(|fragment| {
+            let Fragment {
+                name,
+                route,
+                hostname,
+                rules,
+            } = fragment;
+            if !hostname.map_or(true, |h| host_matches(&route.hostnames[h], host.as_deref())) {
+                return None;
+            }
+            rules
+                .into_iter()
+                .find(|(rule, matches)| {
+                    matches.is_empty()
+                        || matches
+                            .iter()
+                            .any(|m| grpc_matches(&route.rules[*rule].matches[*m], req))
+                })
+                .map(|(rule, _)| (name.as_str(), rule, route))
+        })
+}
+
+/// Returns the lowercased host of the request's URI or `Host` header,
+/// without a port. IPv6 addresses are returned without brackets.
+fn request_host(req: &http::Request<()>) -> Option<String> {
+    let host = match req.uri().authority() {
+        Some(authority) => authority.host().to_string(),
+        None => {
+            let host = req.headers().get(http::header::HOST)?.to_str().ok()?;
+            host.parse::<http::uri::Authority>()
+                .ok()?
+                .host()
+                .to_string()
+        }
+    };
+    let host = host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(&host);
+    Some(host.to_ascii_lowercase())
+}
+
+fn host_matches(hostname: &HostMatch, host: Option<&str>) -> bool {
+    let host = match host {
+        Some(host) => host,
+        None => return false,
+    };
+    match hostname {
+        HostMatch::Exact(h) => h == host,
+        HostMatch::Suffix { reverse_labels } => {
+            let mut labels = host.split('.').rev();
+            // A wildcard matches at least one label.
+            reverse_labels
+                .iter()
+                .all(|l| labels.next() == Some(l.as_str()))
+                && labels.next().is_some()
+        }
+    }
+}
+
+fn http_matches(m: &HttpRouteMatch, req: &http::Request<()>) -> bool {
+    let path = req.uri().path();
+    let path_matches = match &m.path {
+        None => true,
+        Some(PathMatch::Exact(p)) => p == path,
+        // Prefixes match whole path segments, so `/foo` matches `/foo/bar`
+        // but not `/foobar`.
+        Some(PathMatch::Prefix(p)) => match path.strip_prefix(p.trim_end_matches('/')) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/'),
+            None => false,
+        },
+        Some(PathMatch::Regex(r)) => is_full_match(r, path),
+    };
+    if !path_matches {
+        return false;
+    }
+
+    if let Some(method) = &m.method {
+        if req.method() != method {
+            return false;
+        }
+    }
+
+    if !m.headers.iter().all(|h| header_matches(h, req)) {
+        return false;
+    }
+
+    let params = form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes())
+        .collect::<Vec<_>>();
+    m.query_params.iter().all(|q| match q {
+        QueryParamMatch::Exact(name, value) => params.iter().any(|(n, v)| n == name && v == value),
+        QueryParamMatch::Regex(name, r) => {
+            params.iter().any(|(n, v)| n == name && is_full_match(r, v))
+        }
+    })
+}
+
+fn grpc_matches(m: &GrpcRouteMatch, req: &http::Request<()>) -> bool {
+    if let Some(rpc) = &m.method {
+        // gRPC requests are made to `/<service>/<method>`.
+        let (service, method) = match req.uri().path().trim_start_matches('/').split_once('/') {
+            Some(parts) => parts,
+            None => return false,
+        };
+        if rpc.service.as_deref().map_or(false, |s| s != service)
+            || rpc.method.as_deref().map_or(false, |m| m != method)
+        {
+            return false;
+        }
+    }
+
+    m.headers.iter().all(|h| header_matches(h, req))
+}
+
+fn header_matches(h: &HeaderMatch, req: &http::Request<()>) -> bool {
+    match h {
+        HeaderMatch::Exact(name, value) => req.headers().get_all(name).iter().any(|v| v == value),
+        HeaderMatch::Regex(name, r) => req
+            .headers()
+            .get_all(name)
+            .iter()
+            .any(|v| v.to_str().map_or(false, |v| is_full_match(r, v))),
+    }
+}
+
+/// Indicates whether `r` matches all of `s`.
+///
+/// The leftmost match found by `Regex::find` may be shorter than a match of
+/// the whole string (e.g. `a|ab` in `ab`), so the pattern is anchored instead.
+fn is_full_match(r: &Regex, s: &str) -> bool {
+    Regex::new(&format!("^(?:{})$", r.as_str())).map_or(false, |r| r.is_match(s))
+}
+
+// === impl Decision ===
+
+impl<'s> Decision<'s> {
+    fn no_route() -> Self {
+        Self {
+            route: None,
+            outcome: Outcome::NoRoute,
+            unmatched: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        grpc_route::{GrpcMethodMatch, InboundGrpcRouteRule},
+        http_route::InboundHttpRouteRule,
+        IdentityMatch, NetworkMatch, ServerRef,
+    };
+    use std::time::Duration;
+
+    const CLUSTER_NET: &str = "10.0.0.0/8";
+    const SA_ID: &str = "web.emojivoto.serviceaccount.identity.linkerd.cluster.local";
+
+    #[test]
+    fn permits_cluster_networks() {
+        let authz = authz_ref("cluster-unauthenticated");
+        let server = mk_server(vec![(
+            authz.clone(),
+            mk_authz(
+                AuthorizationAction::Allow,
+                ClientAuthentication::Unauthenticated,
+            ),
+        )]);
+
+        let decision = evaluate(&server, &cluster_nets(), &client("10.1.1.1", None), None);
+        assert_eq!(
+            decision.outcome,
+            Outcome::Permitted {
+                authorization: &authz,
+                action: AuthorizationAction::Allow,
+            }
+        );
+        assert_eq!(decision.route, None);
+
+        let decision = evaluate(&server, &cluster_nets(), &client("192.0.2.1", None), None);
+        assert_eq!(decision.outcome, Outcome::Unauthorized);
+        assert_eq!(decision.unmatched, vec![(&authz, Mismatch::Network)]);
+    }
+
+    #[test]
+    fn excepted_networks_do_not_match() {
+        let authz = authz_ref("authz");
+        let mut allow = mk_authz(
+            AuthorizationAction::Allow,
+            ClientAuthentication::Unauthenticated,
+        );
+        allow.networks = vec![NetworkMatch {
+            net: "10.0.0.0/8".parse().unwrap(),
+            except: vec!["10.1.0.0/16".parse().unwrap()],
+        }];
+        let server = mk_server(vec![(authz.clone(), allow)]);
+
+        let decision = evaluate(&server, &cluster_nets(), &client("10.2.1.1", None), None);
+        assert!(matches!(decision.outcome, Outcome::Permitted { .. }));
+
+        let decision = evaluate(&server, &cluster_nets(), &client("10.1.1.1", None), None);
+        assert_eq!(decision.outcome, Outcome::Unauthorized);
+        assert_eq!(decision.unmatched, vec![(&authz, Mismatch::Network)]);
+    }
+
+    #[test]
+    fn requires_identity() {
+        let authz = authz_ref("authz");
+        let server = mk_server(vec![(
+            authz.clone(),
+            mk_authz(
+                AuthorizationAction::Allow,
+                ClientAuthentication::TlsAuthenticated(vec![
+                    "*.emojivoto.serviceaccount.identity.linkerd.cluster.local"
+                        .parse()
+                        .unwrap(),
+                ]),
+            ),
+        )]);
+
+        let decision = evaluate(&server, &cluster_nets(), &client("10.1.1.1", None), None);
+        assert_eq!(decision.unmatched, vec![(&authz, Mismatch::Tls)]);
+
+        let other = "web.booksapp.serviceaccount.identity.linkerd.cluster.local";
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", Some(other)),
+            None,
+        );
+        assert_eq!(decision.unmatched, vec![(&authz, Mismatch::Identity)]);
+
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", Some(SA_ID)),
+            None,
+        );
+        assert_eq!(
+            decision.outcome,
+            Outcome::Permitted {
+                authorization: &authz,
+                action: AuthorizationAction::Allow,
+            }
+        );
+    }
+
+    #[test]
+    fn deny_takes_precedence() {
+        let allow = authz_ref("allow");
+        let deny = authz_ref("deny");
+        let server = mk_server(vec![
+            (
+                allow,
+                mk_authz(
+                    AuthorizationAction::Allow,
+                    ClientAuthentication::Unauthenticated,
+                ),
+            ),
+            (
+                deny.clone(),
+                mk_authz(
+                    AuthorizationAction::Deny,
+                    ClientAuthentication::TlsAuthenticated(vec![IdentityMatch::Exact(
+                        SA_ID.to_string(),
+                    )]),
+                ),
+            ),
+        ]);
+
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", Some(SA_ID)),
+            None,
+        );
+        assert_eq!(
+            decision.outcome,
+            Outcome::Denied {
+                authorization: &deny
+            }
+        );
+
+        let decision = evaluate(&server, &cluster_nets(), &client("10.1.1.1", None), None);
+        assert!(matches!(
+            decision.outcome,
+            Outcome::Permitted {
+                action: AuthorizationAction::Allow,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn audit_permits_unauthorized_clients() {
+        let allow = authz_ref("allow");
+        let audit = AuthorizationRef::Default("audit".to_string());
+        let server = mk_server(vec![
+            (
+                allow.clone(),
+                mk_authz(
+                    AuthorizationAction::Allow,
+                    ClientAuthentication::TlsUnauthenticated,
+                ),
+            ),
+            (
+                audit.clone(),
+                mk_authz(
+                    AuthorizationAction::Audit,
+                    ClientAuthentication::Unauthenticated,
+                ),
+            ),
+        ]);
+
+        let decision = evaluate(&server, &cluster_nets(), &client("10.1.1.1", None), None);
+        assert_eq!(
+            decision.outcome,
+            Outcome::Permitted {
+                authorization: &audit,
+                action: AuthorizationAction::Audit,
+            }
+        );
+
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", Some(SA_ID)),
+            None,
+        );
+        assert_eq!(
+            decision.outcome,
+            Outcome::Permitted {
+                authorization: &allow,
+                action: AuthorizationAction::Allow,
+            }
+        );
+    }
+
+    #[test]
+    fn audit_deny_flags_permitted_clients() {
+        let allow = authz_ref("allow");
+        let deny = authz_ref("deny");
+        let server = mk_server(vec![
+            (
+                allow.clone(),
+                mk_authz(
+                    AuthorizationAction::Allow,
+                    ClientAuthentication::TlsUnauthenticated,
+                ),
+            ),
+            (
+                deny.clone(),
+                ClientAuthorization {
+                    networks: vec!["10.1.0.0/16".parse::<IpNet>().unwrap().into()],
+                    ..mk_authz(
+                        AuthorizationAction::AuditDeny,
+                        ClientAuthentication::Unauthenticated,
+                    )
+                },
+            ),
+        ]);
+
+        // Permitted clients in the denied network are flagged.
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", Some(SA_ID)),
+            None,
+        );
+        assert_eq!(
+            decision.outcome,
+            Outcome::Permitted {
+                authorization: &deny,
+                action: AuthorizationAction::Audit,
+            }
+        );
+
+        // The audited deny does not permit clients itself.
+        let decision = evaluate(&server, &cluster_nets(), &client("10.1.1.1", None), None);
+        assert_eq!(decision.outcome, Outcome::Unauthorized);
+
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.2.1.1", Some(SA_ID)),
+            None,
+        );
+        assert_eq!(
+            decision.outcome,
+            Outcome::Permitted {
+                authorization: &allow,
+                action: AuthorizationAction::Allow,
+            }
+        );
+    }
+
+    #[test]
+    fn selects_http_route() {
+        let route_authz = authz_ref("route-authz");
+        let mut server = mk_server(vec![(
+            authz_ref("server-authz"),
+            mk_authz(
+                AuthorizationAction::Allow,
+                ClientAuthentication::Unauthenticated,
+            ),
+        )]);
+        server.protocol = ProxyProtocol::Http1;
+        server.http_routes.insert(
+            "api".to_string(),
+            InboundHttpRoute {
+                hostnames: vec![HostMatch::Suffix {
+                    reverse_labels: vec!["com".to_string(), "example".to_string()],
+                }],
+                rules: vec![
+                    InboundHttpRouteRule {
+                        matches: vec![HttpRouteMatch {
+                            path: Some(PathMatch::Exact("/api/admin".to_string())),
+                            headers: vec![],
+                            query_params: vec![],
+                            method: None,
+                        }],
+                        filters: vec![],
+                    },
+                    InboundHttpRouteRule {
+                        matches: vec![HttpRouteMatch {
+                            path: Some(PathMatch::Prefix("/api".to_string())),
+                            headers: vec![],
+                            query_params: vec![],
+                            method: Some(http::Method::GET),
+                        }],
+                        filters: vec![],
+                    },
+                ],
+                authorizations: Some((
+                    route_authz.clone(),
+                    mk_authz(
+                        AuthorizationAction::Allow,
+                        ClientAuthentication::Unauthenticated,
+                    ),
+                ))
+                .into_iter()
+                .collect(),
+                rank: 0,
+            },
+        );
+
+        let get = |uri: &str| {
+            http::Request::builder()
+                .method(http::Method::GET)
+                .uri(uri)
+                .body(())
+                .unwrap()
+        };
+
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", None),
+            Some(&get("http://web.example.com/api/list")),
+        );
+        assert_eq!(
+            decision.route,
+            Some(RouteMatch::Http {
+                name: "api",
+                rule: 1
+            })
+        );
+        assert_eq!(
+            decision.outcome,
+            Outcome::Permitted {
+                authorization: &route_authz,
+                action: AuthorizationAction::Allow,
+            }
+        );
+
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", None),
+            Some(&get("http://web.example.com/api/admin")),
+        );
+        assert_eq!(
+            decision.route,
+            Some(RouteMatch::Http {
+                name: "api",
+                rule: 0
+            })
+        );
+
+        // Prefixes match whole path segments.
+        for uri in [
+            "http://web.example.com/apis",
+            "http://example.com/api/list",
+            "http://web.example.org/api/list",
+        ] {
+            let decision = evaluate(
+                &server,
+                &cluster_nets(),
+                &client("10.1.1.1", None),
+                Some(&get(uri)),
+            );
+            assert_eq!(decision.outcome, Outcome::NoRoute, "{}", uri);
+        }
+
+        // Connections are authorized by the server's authorizations.
+        let decision = evaluate(&server, &cluster_nets(), &client("10.1.1.1", None), None);
+        assert_eq!(decision.route, None);
+        assert_eq!(
+            decision.outcome,
+            Outcome::Permitted {
+                authorization: &authz_ref("server-authz"),
+                action: AuthorizationAction::Allow,
+            }
+        );
+    }
+
+    #[test]
+    fn matches_http_request_details() {
+        let mut server = mk_server(vec![]);
+        server.protocol = ProxyProtocol::Http1;
+        let rule = |m: HttpRouteMatch| InboundHttpRouteRule {
+            matches: vec![m],
+            filters: vec![],
+        };
+        server.http_routes.insert(
+            "route".to_string(),
+            InboundHttpRoute {
+                hostnames: vec![],
+                rules: vec![
+                    rule(HttpRouteMatch {
+                        path: Some(PathMatch::Regex(Regex::new("/api|/api/admin").unwrap())),
+                        headers: vec![],
+                        query_params: vec![],
+                        method: None,
+                    }),
+                    rule(HttpRouteMatch {
+                        path: None,
+                        headers: vec![],
+                        query_params: vec![QueryParamMatch::Exact(
+                            "user".to_string(),
+                            "a b/c".to_string(),
+                        )],
+                        method: None,
+                    }),
+                ],
+                authorizations: Default::default(),
+                rank: 0,
+            },
+        );
+        let get = |uri: &str| http::Request::builder().uri(uri).body(()).unwrap();
+
+        // Regular expressions must match the whole path, even when a shorter
+        // alternative matches first.
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", None),
+            Some(&get("/api/admin")),
+        );
+        assert_eq!(
+            decision.route,
+            Some(RouteMatch::Http {
+                name: "route",
+                rule: 0
+            })
+        );
+
+        // Query parameter values are decoded before they are matched.
+        for uri in ["/?user=a%20b%2Fc", "/?x=y&user=a+b/c"] {
+            let decision = evaluate(
+                &server,
+                &cluster_nets(),
+                &client("10.1.1.1", None),
+                Some(&get(uri)),
+            );
+            assert_eq!(
+                decision.route,
+                Some(RouteMatch::Http {
+                    name: "route",
+                    rule: 1
+                }),
+                "{}",
+                uri
+            );
+        }
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", None),
+            Some(&get("/?user=a%20b")),
+        );
+        assert_eq!(decision.outcome, Outcome::NoRoute);
+    }
+
+    #[test]
+    fn request_hosts() {
+        for (uri, host, expected) in [
+            (
+                "http://Web.Example.com:8080/",
+                None,
+                Some("web.example.com"),
+            ),
+            ("http://[::1]:8080/", None, Some("::1")),
+            ("/", Some("web.example.com:8080"), Some("web.example.com")),
+            ("/", Some("web.example.com"), Some("web.example.com")),
+            ("/", Some("[fd00::1]:8080"), Some("fd00::1")),
+            ("/", Some("[fd00::1]"), Some("fd00::1")),
+            ("/", None, None),
+        ] {
+            let mut req = http::Request::builder().uri(uri);
+            if let Some(host) = host {
+                req = req.header(http::header::HOST, host);
+            }
+            let req = req.body(()).unwrap();
+            assert_eq!(
+                request_host(&req).as_deref(),
+                expected,
+                "{} with host {:?}",
+                uri,
+                host
+            );
+        }
+    }
+
+    #[test]
+    fn selects_grpc_route() {
+        let mut server = mk_server(vec![]);
+        server.protocol = ProxyProtocol::Grpc;
+        server.grpc_routes.insert(
+            "emoji".to_string(),
+            InboundGrpcRoute {
+                hostnames: vec![],
+                rules: vec![InboundGrpcRouteRule {
+                    matches: vec![GrpcRouteMatch {
+                        method: Some(GrpcMethodMatch {
+                            service: Some("emojivoto.v1.EmojiService".to_string()),
+                            method: None,
+                        }),
+                        headers: vec![],
+                    }],
+                    filters: vec![],
+                }],
+                authorizations: Some((
+                    authz_ref("authz"),
+                    mk_authz(
+                        AuthorizationAction::Allow,
+                        ClientAuthentication::Unauthenticated,
+                    ),
+                ))
+                .into_iter()
+                .collect(),
+                rank: 0,
+            },
+        );
+
+        let post = |uri: &str| {
+            http::Request::builder()
+                .method(http::Method::POST)
+                .uri(uri)
+                .body(())
+                .unwrap()
+        };
+
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", None),
+            Some(&post("/emojivoto.v1.EmojiService/ListAll")),
+        );
+        assert_eq!(
+            decision.route,
+            Some(RouteMatch::Grpc {
+                name: "emoji",
+                rule: 0
+            })
+        );
+        assert!(matches!(decision.outcome, Outcome::Permitted { .. }));
+
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", None),
+            Some(&post("/emojivoto.v1.VotingService/VoteDoughnut")),
+        );
+        assert_eq!(decision.outcome, Outcome::NoRoute);
+    }
+
+    #[test]
+    fn opaque_servers_ignore_requests() {
+        let authz = authz_ref("authz");
+        let mut server = mk_server(vec![(
+            authz,
+            mk_authz(
+                AuthorizationAction::Allow,
+                ClientAuthentication::Unauthenticated,
+            ),
+        )]);
+        server.protocol = ProxyProtocol::Opaque;
+        server.http_routes.insert(
+            "route".to_string(),
+            InboundHttpRoute {
+                hostnames: vec![],
+                rules: vec![],
+                authorizations: Default::default(),
+                rank: 0,
+            },
+        );
+
+        let req = http::Request::builder().uri("/").body(()).unwrap();
+        let decision = evaluate(
+            &server,
+            &cluster_nets(),
+            &client("10.1.1.1", None),
+            Some(&req),
+        );
+        assert_eq!(decision.route, None);
+        assert!(matches!(decision.outcome, Outcome::Permitted { .. }));
+    }
+
+    fn cluster_nets() -> Vec<IpNet> {
+        vec![CLUSTER_NET.parse().unwrap()]
+    }
+
+    fn client(addr: &str, identity: Option<&str>) -> Client {
+        Client {
+            addr: addr.parse().unwrap(),
+            tls: identity.is_some(),
+            identity: identity.map(ToString::to_string),
+        }
+    }
+
+    fn authz_ref(name: &str) -> AuthorizationRef {
+        AuthorizationRef::AuthorizationPolicy(name.to_string())
+    }
+
+    fn mk_authz(
+        action: AuthorizationAction,
+        authentication: ClientAuthentication,
+    ) -> ClientAuthorization {
+        ClientAuthorization {
+            action,
+            networks: vec![],
+            authentication,
+        }
+    }
+
+    fn mk_server(authorizations: Vec<(AuthorizationRef, ClientAuthorization)>) -> InboundServer {
+        InboundServer {
+            reference: ServerRef::Server("srv".to_string()),
+            protocol: ProxyProtocol::Detect {
+                timeout: Duration::from_secs(10),
+            },
+            authorizations: authorizations.into_iter().collect(),
+            http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
+        }
+    }
+}
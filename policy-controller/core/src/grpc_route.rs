@@ -0,0 +1,142 @@
+use crate::{
+    http_route::{self, Fragment, HeaderMatch, HostMatch, InboundFilter},
+    AuthorizationRef, ClientAuthorization,
+};
+use ahash::AHashMap as HashMap;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InboundGrpcRoute {
+    pub hostnames: Vec<HostMatch>,
+    pub rules: Vec<InboundGrpcRouteRule>,
+    pub authorizations: HashMap<AuthorizationRef, ClientAuthorization>,
+
+    /// The route's rank among a server's routes by age and name, as with
+    /// HTTP routes.
+    pub rank: usize,
+}
+
+/// The specificity of a match according to the Gateway API's GRPCRoute
+/// precedence rules. Greater values take precedence.
+///
+/// Each field is compared in order, continuing on ties:
+///
+/// * Characters in a service match.
+/// * Characters in a method match.
+/// * Header matches.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Precedence {
+    pub service_chars: usize,
+    pub method_chars: usize,
+    pub headers: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InboundGrpcRouteRule {
+    pub matches: Vec<GrpcRouteMatch>,
+    pub filters: Vec<InboundFilter>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrpcRouteMatch {
+    pub method: Option<GrpcMethodMatch>,
+    pub headers: Vec<HeaderMatch>,
+}
+
+/// Matches a gRPC service and method. When either is omitted, all values
+/// match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrpcMethodMatch {
+    pub service: Option<String>,
+    pub method: Option<String>,
+}
+
+/// Orders a server's routes by the precedence of their matches, as with
+/// [`http_route::fragments_by_precedence`].
+pub fn fragments_by_precedence<'r>(
+    routes: impl IntoIterator<Item = (&'r String, &'r InboundGrpcRoute)>,
+) -> Vec<(&'r String, InboundGrpcRoute)> {
+    InboundGrpcRoute::fragments(routes)
+        .into_iter()
+        .enumerate()
+        .map(|(rank, fragment)| {
+            let Fragment {
+                name,
+                route,
+                hostname,
+                rules,
+            } = fragment;
+            let rules = rules
+                .into_iter()
+                .map(|(rule, matches)| {
+                    let rule = &route.rules[rule];
+                    InboundGrpcRouteRule {
+                        matches: matches
+                            .into_iter()
+                            .map(|m| rule.matches[m].clone())
+                            .collect(),
+                        filters: rule.filters.clone(),
+                    }
+                })
+                .collect();
+            let route = InboundGrpcRoute {
+                hostnames: hostname
+                    .map(|h| route.hostnames[h].clone())
+                    .into_iter()
+                    .collect(),
+                rules,
+                authorizations: route.authorizations.clone(),
+                rank,
+            };
+            (name, route)
+        })
+        .collect()
+}
+
+// === impl InboundGrpcRoute ===
+
+impl InboundGrpcRoute {
+    pub(crate) fn fragments<'r>(
+        routes: impl IntoIterator<Item = (&'r String, &'r Self)>,
+    ) -> Vec<Fragment<'r, Self>> {
+        http_route::fragments(
+            routes,
+            |route| route.rank,
+            |route| route.hostnames.as_slice(),
+            |route| {
+                route
+                    .rules
+                    .iter()
+                    .map(|rule| {
+                        rule.matches
+                            .iter()
+                            .map(GrpcRouteMatch::precedence)
+                            .collect()
+                    })
+                    .collect()
+            },
+            Precedence::default(),
+        )
+    }
+}
+
+// === impl GrpcRouteMatch ===
+
+impl GrpcRouteMatch {
+    pub fn precedence(&self) -> Precedence {
+        let (service_chars, method_chars) = self
+            .method
+            .as_ref()
+            .map(|m| {
+                (
+                    m.service.as_ref().map_or(0, String::len),
+                    m.method.as_ref().map_or(0, String::len),
+                )
+            })
+            .unwrap_or_default();
+        Precedence {
+            service_chars,
+            method_chars,
+            headers: self.headers.len(),
+        }
+    }
+}
@@ -14,6 +14,47 @@ pub struct InboundHttpRoute {
     pub hostnames: Vec<HostMatch>,
     pub rules: Vec<InboundHttpRouteRule>,
     pub authorizations: HashMap<AuthorizationRef, ClientAuthorization>,
+
+    /// The route's rank among a server's routes. Routes are indexed with
+    /// their rank by age and name, which orders matches of equal precedence.
+    /// [`fragments_by_precedence`] ranks the fragments that are served to
+    /// proxies by the precedence of their matches.
+    pub rank: usize,
+}
+
+/// The specificity of a match according to the Gateway API's precedence
+/// rules. Greater values take precedence.
+///
+/// Each field is compared in order, continuing on ties:
+///
+/// * Exact path matches.
+/// * Characters in a path prefix or in the literal prefix of a path regex.
+/// * Path prefix matches, which are preferred to regexes.
+/// * Method matches.
+/// * Header matches.
+/// * Query param matches.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Precedence {
+    pub exact_path: bool,
+    pub path_chars: usize,
+    pub prefix_path: bool,
+    pub method: bool,
+    pub headers: usize,
+    pub query_params: usize,
+}
+
+/// A portion of a route, as ordered by [`fragments`].
+#[derive(Debug)]
+pub(crate) struct Fragment<'r, R> {
+    pub(crate) name: &'r String,
+    pub(crate) route: &'r R,
+
+    /// The index of the fragment's hostname, if the route has hostnames.
+    pub(crate) hostname: Option<usize>,
+
+    /// The indices of the fragment's rules, each with the indices of the
+    /// matches it includes. A rule without matches matches all requests.
+    pub(crate) rules: Vec<(usize, Vec<usize>)>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -97,6 +138,231 @@ pub enum QueryParamMatch {
     Regex(String, Regex),
 }
 
+/// Orders a server's routes by the precedence of their matches.
+///
+/// The Gateway API defines precedence for each hostname and match rather than
+/// for each route, so matches from different routes may be interleaved. Routes
+/// are split into fragments, each with at most one hostname and a run of the
+/// route's matches, such that the first fragment that matches a request is
+/// the one that takes precedence. Each fragment's rank is its position in the
+/// returned list.
+pub fn fragments_by_precedence<'r>(
+    routes: impl IntoIterator<Item = (&'r String, &'r InboundHttpRoute)>,
+) -> Vec<(&'r String, InboundHttpRoute)> {
+    InboundHttpRoute::fragments(routes)
+        .into_iter()
+        .enumerate()
+        .map(|(rank, fragment)| {
+            let Fragment {
+                name,
+                route,
+                hostname,
+                rules,
+            } = fragment;
+            let rules = rules
+                .into_iter()
+                .map(|(rule, matches)| {
+                    let rule = &route.rules[rule];
+                    InboundHttpRouteRule {
+                        matches: matches
+                            .into_iter()
+                            .map(|m| rule.matches[m].clone())
+                            .collect(),
+                        filters: rule.filters.clone(),
+                    }
+                })
+                .collect();
+            let route = InboundHttpRoute {
+                hostnames: hostname
+                    .map(|h| route.hostnames[h].clone())
+                    .into_iter()
+                    .collect(),
+                rules,
+                authorizations: route.authorizations.clone(),
+                rank,
+            };
+            (name, route)
+        })
+        .collect()
+}
+
+/// Orders the hostnames and matches of a set of routes by precedence,
+/// grouping consecutive matches of the same route and hostname into
+/// fragments.
+///
+/// Matches with equal precedence are ordered by their route's rank and then
+/// in the order in which they are declared.
+pub(crate) fn fragments<'r, R, P: Ord + Clone>(
+    routes: impl IntoIterator<Item = (&'r String, &'r R)>,
+    rank: impl Fn(&R) -> usize,
+    hostnames: impl Fn(&R) -> &[HostMatch],
+    matches: impl Fn(&R) -> Vec<Vec<P>>,
+    match_all: P,
+) -> Vec<Fragment<'r, R>> {
+    use std::cmp::Reverse;
+
+    let mut ordered = Vec::new();
+    for (name, route) in routes {
+        let hosts = hostnames(route);
+        let hosts = if hosts.is_empty() {
+            vec![(None, (0, 0))]
+        } else {
+            hosts
+                .iter()
+                .enumerate()
+                .map(|(i, h)| (Some(i), h.chars()))
+                .collect()
+        };
+        let rules = matches(route);
+        for (host, host_chars) in hosts {
+            for (rule, precedences) in rules.iter().enumerate() {
+                let precedences = if precedences.is_empty() {
+                    vec![(None, match_all.clone())]
+                } else {
+                    precedences
+                        .iter()
+                        .cloned()
+                        .enumerate()
+                        .map(|(i, p)| (Some(i), p))
+                        .collect()
+                };
+                for (m, precedence) in precedences {
+                    let key = (
+                        Reverse(host_chars),
+                        Reverse(precedence),
+                        rank(route),
+                        name,
+                        host,
+                        rule,
+                        m,
+                    );
+                    ordered.push((key, route));
+                }
+            }
+        }
+    }
+    ordered.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut fragments = Vec::<Fragment<'r, R>>::new();
+    for ((_, _, _, name, hostname, rule, m), route) in ordered {
+        match fragments.last_mut() {
+            Some(f) if f.name == name && f.hostname == hostname => match f.rules.last_mut() {
+                Some((r, matches)) if *r == rule => matches.extend(m),
+                _ => f.rules.push((rule, m.into_iter().collect())),
+            },
+            _ => fragments.push(Fragment {
+                name,
+                route,
+                hostname,
+                rules: vec![(rule, m.into_iter().collect())],
+            }),
+        }
+    }
+    fragments
+}
+
+// === impl InboundHttpRoute ===
+
+impl InboundHttpRoute {
+    pub(crate) fn fragments<'r>(
+        routes: impl IntoIterator<Item = (&'r String, &'r Self)>,
+    ) -> Vec<Fragment<'r, Self>> {
+        fragments(
+            routes,
+            |route| route.rank,
+            |route| route.hostnames.as_slice(),
+            |route| {
+                route
+                    .rules
+                    .iter()
+                    .map(|rule| {
+                        rule.matches
+                            .iter()
+                            .map(HttpRouteMatch::precedence)
+                            .collect()
+                    })
+                    .collect()
+            },
+            Precedence::MATCH_ALL,
+        )
+    }
+}
+
+// === impl HostMatch ===
+
+impl HostMatch {
+    /// Returns the number of characters in the hostname if it is not a
+    /// wildcard, and the number of characters in the hostname as written.
+    pub(crate) fn chars(&self) -> (usize, usize) {
+        match self {
+            Self::Exact(host) => (host.len(), host.len()),
+            // A suffix match is written as `*.<suffix>`.
+            Self::Suffix { reverse_labels } => {
+                let suffix = reverse_labels.iter().map(|l| l.len() + 1).sum::<usize>();
+                (0, suffix + 1)
+            }
+        }
+    }
+}
+
+// === impl Precedence ===
+
+impl Precedence {
+    /// The precedence of a match on all requests, i.e. the `/` path prefix.
+    pub(crate) const MATCH_ALL: Self = Self {
+        exact_path: false,
+        path_chars: 1,
+        prefix_path: true,
+        method: false,
+        headers: 0,
+        query_params: 0,
+    };
+}
+
+// === impl HttpRouteMatch ===
+
+impl HttpRouteMatch {
+    pub fn precedence(&self) -> Precedence {
+        let (exact_path, path_chars, prefix_path) = match &self.path {
+            Some(PathMatch::Exact(path)) => (true, path.len(), false),
+            Some(PathMatch::Prefix(path)) => (false, path.len(), true),
+            Some(PathMatch::Regex(re)) => (false, literal_prefix_len(re.as_str()), false),
+            None => (false, Precedence::MATCH_ALL.path_chars, true),
+        };
+        Precedence {
+            exact_path,
+            path_chars,
+            prefix_path,
+            method: self.method.is_some(),
+            headers: self.headers.len(),
+            query_params: self.query_params.len(),
+        }
+    }
+}
+
+/// Returns the number of characters that every path matching the regex must
+/// start with, e.g. 5 for `^/foo/[0-9]+`.
+fn literal_prefix_len(re: &str) -> usize {
+    let mut chars = re.strip_prefix('^').unwrap_or(re).chars().peekable();
+    let mut len = 0;
+    while let Some(c) = chars.next() {
+        let literal = match c {
+            '\\' => match chars.next() {
+                Some(c) if !c.is_ascii_alphanumeric() => c,
+                _ => break,
+            },
+            '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$' => break,
+            c => c,
+        };
+        // A literal followed by an optional quantifier need not be matched.
+        if matches!(chars.peek(), Some('?' | '*' | '{')) {
+            break;
+        }
+        len += literal.len_utf8();
+    }
+    len
+}
+
 // === impl PathMatch ===
 
 impl PartialEq for PathMatch {
@@ -145,3 +411,114 @@ impl PartialEq for QueryParamMatch {
 }
 
 impl Eq for QueryParamMatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(path: PathMatch) -> HttpRouteMatch {
+        HttpRouteMatch {
+            path: Some(path),
+            headers: vec![],
+            query_params: vec![],
+            method: None,
+        }
+    }
+
+    fn route(rank: usize, rules: Vec<Vec<HttpRouteMatch>>) -> InboundHttpRoute {
+        InboundHttpRoute {
+            hostnames: vec![],
+            rules: rules
+                .into_iter()
+                .map(|matches| InboundHttpRouteRule {
+                    matches,
+                    filters: vec![],
+                })
+                .collect(),
+            authorizations: HashMap::default(),
+            rank,
+        }
+    }
+
+    #[test]
+    fn match_precedence() {
+        let prefix = |p: &str| path(PathMatch::Prefix(p.to_string())).precedence();
+
+        // Exact paths take precedence over any prefix.
+        assert!(path(PathMatch::Exact("/".to_string())).precedence() > prefix("/foo/bar"));
+
+        // A regex is ranked by its literal prefix, below an equal path prefix.
+        let regex = path(PathMatch::regex("^/foo/[0-9]+").unwrap()).precedence();
+        assert_eq!(regex.path_chars, "/foo/".len());
+        assert!(regex > prefix("/foo"));
+        assert!(regex < prefix("/foo/"));
+
+        // Method matches take precedence over header matches.
+        let method = HttpRouteMatch {
+            method: Some(Method::GET),
+            ..path(PathMatch::Prefix("/".to_string()))
+        };
+        let header = HttpRouteMatch {
+            headers: vec![HeaderMatch::Exact(
+                HeaderName::from_static("x-foo"),
+                HeaderValue::from_static("bar"),
+            )],
+            ..path(PathMatch::Prefix("/".to_string()))
+        };
+        assert!(method.precedence() > header.precedence());
+    }
+
+    #[test]
+    fn fragments_interleave_matches() {
+        let prefix = |p: &str| path(PathMatch::Prefix(p.to_string()));
+        let exact = |p: &str| path(PathMatch::Exact(p.to_string()));
+        let routes = [
+            (
+                "route-a".to_string(),
+                route(0, vec![vec![prefix("/")], vec![prefix("/foo/bar")]]),
+            ),
+            (
+                "route-b".to_string(),
+                route(1, vec![vec![prefix("/foo"), exact("/baz")]]),
+            ),
+        ];
+
+        let fragments = fragments_by_precedence(routes.iter().map(|(n, r)| (n, r)));
+        let fragments = fragments
+            .iter()
+            .map(|(name, route)| {
+                assert_eq!(route.rules.len(), 1);
+                (name.as_str(), route.rank, route.rules[0].matches.clone())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fragments,
+            vec![
+                ("route-b", 0, vec![exact("/baz")]),
+                ("route-a", 1, vec![prefix("/foo/bar")]),
+                ("route-b", 2, vec![prefix("/foo")]),
+                ("route-a", 3, vec![prefix("/")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn fragments_order_ties_by_rank() {
+        let prefix = |p: &str| path(PathMatch::Prefix(p.to_string()));
+        let routes = [
+            ("route-a".to_string(), route(1, vec![vec![prefix("/foo")]])),
+            (
+                "route-b".to_string(),
+                route(0, vec![vec![prefix("/foo")], vec![prefix("/bar")]]),
+            ),
+        ];
+
+        let fragments = fragments_by_precedence(routes.iter().map(|(n, r)| (n, r)));
+        let names = fragments
+            .iter()
+            .map(|(name, route)| (name.as_str(), route.rules.len()))
+            .collect::<Vec<_>>();
+        // Consecutive matches of the same route are served together.
+        assert_eq!(names, vec![("route-b", 2), ("route-a", 1)]);
+    }
+}
@@ -12,6 +12,32 @@ pub enum IdentityMatch {
 
 // === impl IdentityMatch ===
 
+impl IdentityMatch {
+    /// Indicates whether a client's identity is matched.
+    pub fn matches(&self, id: &str) -> bool {
+        match self {
+            Self::Exact(name) => name == id,
+            Self::Suffix(suffix) => {
+                let mut labels = id.split('.').rev();
+                suffix
+                    .iter()
+                    .rev()
+                    .all(|s| labels.next() == Some(s.as_str()))
+            }
+        }
+    }
+
+    /// Indicates whether every identity matched by `other` is also matched by
+    /// this match.
+    pub fn covers(&self, other: &Self) -> bool {
+        match (self, other) {
+            (_, Self::Exact(id)) => self.matches(id),
+            (Self::Suffix(suffix), Self::Suffix(other)) => other.ends_with(suffix),
+            (Self::Exact(_), Self::Suffix(_)) => false,
+        }
+    }
+}
+
 impl FromStr for IdentityMatch {
     type Err = Infallible;
 
@@ -84,4 +110,70 @@ mod tests {
             Ok(IdentityMatch::Exact("foo.*.example.com".to_string()))
         );
     }
+
+    #[test]
+    fn matches() {
+        let sa = "web.emojivoto.serviceaccount.identity.linkerd.cluster.local";
+        for (m, id, expected) in [
+            (sa, sa, true),
+            ("*", sa, true),
+            (
+                "*.emojivoto.serviceaccount.identity.linkerd.cluster.local",
+                sa,
+                true,
+            ),
+            ("*.linkerd.cluster.local", sa, true),
+            (
+                "*.booksapp.serviceaccount.identity.linkerd.cluster.local",
+                sa,
+                false,
+            ),
+        ] {
+            assert_eq!(
+                m.parse::<IdentityMatch>().unwrap().matches(id),
+                expected,
+                "{} matching {}",
+                m,
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn covers() {
+        let sa = "web.emojivoto.serviceaccount.identity.linkerd.cluster.local";
+        for (a, b, expected) in [
+            (sa, sa, true),
+            ("*", sa, true),
+            (
+                "*.emojivoto.serviceaccount.identity.linkerd.cluster.local",
+                sa,
+                true,
+            ),
+            (
+                "*.linkerd.cluster.local",
+                "*.emojivoto.serviceaccount.identity.linkerd.cluster.local",
+                true,
+            ),
+            (
+                "*.emojivoto.serviceaccount.identity.linkerd.cluster.local",
+                "*.linkerd.cluster.local",
+                false,
+            ),
+            (
+                "*.booksapp.serviceaccount.identity.linkerd.cluster.local",
+                sa,
+                false,
+            ),
+            (
+                sa,
+                "*.emojivoto.serviceaccount.identity.linkerd.cluster.local",
+                false,
+            ),
+        ] {
+            let a = a.parse::<IdentityMatch>().unwrap();
+            let b = b.parse::<IdentityMatch>().unwrap();
+            assert_eq!(a.covers(&b), expected, "{} covering {}", a, b);
+        }
+    }
 }
@@ -1,12 +1,19 @@
 #![deny(warnings, rust_2018_idioms)]
 #![forbid(unsafe_code)]
 
+pub mod evaluate;
+pub mod grpc_route;
 pub mod http_route;
 mod identity_match;
 mod network_match;
+pub mod outbound;
 
 pub use self::{
-    http_route::InboundHttpRoute, identity_match::IdentityMatch, network_match::NetworkMatch,
+    grpc_route::InboundGrpcRoute,
+    http_route::InboundHttpRoute,
+    identity_match::IdentityMatch,
+    network_match::NetworkMatch,
+    outbound::{DiscoverOutboundPolicy, OutboundPolicy, OutboundPolicyStream},
 };
 use ahash::AHashMap as HashMap;
 use anyhow::Result;
@@ -24,14 +31,31 @@ pub trait DiscoverInboundServer<T> {
 
 pub type InboundServerStream = Pin<Box<dyn Stream<Item = InboundServer> + Send + Sync + 'static>>;
 
+/// Identifies a workload, within a namespace, for which inbound policy is
+/// discovered.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Workload {
+    /// A pod, by name.
+    Pod(String),
+
+    /// An `ExternalWorkload` (e.g. a VM), by name.
+    External(String),
+}
+
 /// Inbound server configuration.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct InboundServer {
     pub reference: ServerRef,
 
     pub protocol: ProxyProtocol,
+
+    /// Clients permitted (or refused) by the server. A client that matches a
+    /// `Deny` authorization is refused, even if it matches other
+    /// authorizations. Deny authorizations only match clients by network.
     pub authorizations: HashMap<AuthorizationRef, ClientAuthorization>,
+
     pub http_routes: HashMap<String, InboundHttpRoute>,
+    pub grpc_routes: HashMap<String, InboundGrpcRoute>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -40,11 +64,15 @@ pub enum ServerRef {
     Server(String),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum AuthorizationRef {
     Default(String),
     ServerAuthorization(String),
     AuthorizationPolicy(String),
+
+    /// Clients that an `AuthorizationPolicy` in audit mode would refuse if it
+    /// were enforced.
+    AuditedPolicy(String),
 }
 
 /// Describes how a proxy should handle inbound connections.
@@ -69,6 +97,9 @@ pub enum ProxyProtocol {
 /// Describes a class of authorized clients.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ClientAuthorization {
+    /// Indicates whether matching clients are permitted or refused.
+    pub action: AuthorizationAction,
+
     /// Limits which source networks this authorization applies to.
     pub networks: Vec<NetworkMatch>,
 
@@ -76,6 +107,27 @@ pub struct ClientAuthorization {
     pub authentication: ClientAuthentication,
 }
 
+/// Describes how an authorization applies to the clients it matches.
+///
+/// Deny authorizations take precedence over allow authorizations: a client
+/// that matches any deny authorization on a server (or route) is refused, even
+/// if it also matches an allow authorization.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AuthorizationAction {
+    Allow,
+    Deny,
+
+    /// Permits matching clients that no other authorization permits, but
+    /// flags them as clients that would be refused if the policy were
+    /// enforced.
+    Audit,
+
+    /// Flags matching clients that other authorizations permit as clients
+    /// that a `Deny` policy would refuse if it were enforced. It does not
+    /// permit clients itself.
+    AuditDeny,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ClientAuthentication {
     /// Indicates that clients need not be authenticated.
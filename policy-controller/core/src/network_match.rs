@@ -12,6 +12,78 @@ pub struct NetworkMatch {
 
 // === impl NetworkMatch ===
 
+impl NetworkMatch {
+    /// Indicates whether the address is in the network and not excluded.
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        self.net.contains(addr) && !self.except.iter().any(|net| net.contains(addr))
+    }
+
+    /// Returns networks that match exactly the addresses matched by this
+    /// network and not by `other`.
+    ///
+    /// Addresses that `other` excepts remain matched, so each of its excepted
+    /// networks that lies within this network is returned as a network of its
+    /// own (with this network's exceptions).
+    pub fn exclude(&self, other: &NetworkMatch) -> Vec<NetworkMatch> {
+        if other.net.contains(&self.net) {
+            // This network is excepted from `other` entirely.
+            if other.except.iter().any(|e| e.contains(&self.net)) {
+                return vec![self.clone()];
+            }
+            return other
+                .except
+                .iter()
+                .filter(|e| self.net.contains(*e))
+                .map(|e| NetworkMatch {
+                    net: *e,
+                    except: self.except.clone(),
+                })
+                .collect();
+        }
+
+        if self.net.contains(&other.net) {
+            let mut except = self.except.clone();
+            except.push(other.net);
+            let mut nets = vec![NetworkMatch {
+                net: self.net,
+                except,
+            }];
+            nets.extend(other.except.iter().map(|e| NetworkMatch {
+                net: *e,
+                except: self.except.clone(),
+            }));
+            return nets;
+        }
+
+        // The networks are disjoint.
+        vec![self.clone()]
+    }
+
+    /// Returns a network that matches exactly the addresses matched by both
+    /// this network and `other`, if any.
+    pub fn intersect(&self, other: &NetworkMatch) -> Option<NetworkMatch> {
+        let (inner, outer) = if other.net.contains(&self.net) {
+            (self, other)
+        } else if self.net.contains(&other.net) {
+            (other, self)
+        } else {
+            return None;
+        };
+        if outer.except.iter().any(|e| e.contains(&inner.net)) {
+            return None;
+        }
+        Some(NetworkMatch {
+            net: inner.net,
+            except: inner
+                .except
+                .iter()
+                .chain(outer.except.iter().filter(|e| inner.net.contains(*e)))
+                .copied()
+                .collect(),
+        })
+    }
+}
+
 impl From<IpAddr> for NetworkMatch {
     fn from(net: IpAddr) -> Self {
         IpNet::from(net).into()
@@ -38,3 +110,88 @@ impl From<Ipv6Net> for NetworkMatch {
         IpNet::from(net).into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn net(s: &str) -> IpNet {
+        s.parse().unwrap()
+    }
+
+    fn matches(nets: &[NetworkMatch], addr: &str) -> bool {
+        let addr = addr.parse().unwrap();
+        nets.iter().any(|n| n.contains(&addr))
+    }
+
+    #[test]
+    fn exclude_disjoint() {
+        let allow = NetworkMatch::from(net("10.0.0.0/16"));
+        let nets = allow.exclude(&net("192.168.0.0/16").into());
+        assert_eq!(nets, vec![allow.clone()]);
+
+        let nets = allow.exclude(&net("::/0").into());
+        assert_eq!(nets, vec![allow]);
+    }
+
+    #[test]
+    fn exclude_subnet() {
+        let allow = NetworkMatch {
+            net: net("10.0.0.0/8"),
+            except: vec![net("10.2.0.0/16")],
+        };
+        let deny = NetworkMatch {
+            net: net("10.1.0.0/16"),
+            except: vec![net("10.1.1.0/24")],
+        };
+        let nets = allow.exclude(&deny);
+        assert!(matches(&nets, "10.0.0.1"));
+        assert!(!matches(&nets, "10.1.0.1"));
+        assert!(matches(&nets, "10.1.1.1"));
+        assert!(!matches(&nets, "10.2.0.1"));
+        assert!(!matches(&nets, "192.168.0.1"));
+    }
+
+    #[test]
+    fn exclude_supernet() {
+        let allow = NetworkMatch::from(net("10.1.0.0/16"));
+
+        // The whole network is denied.
+        let nets = allow.exclude(&net("10.0.0.0/8").into());
+        assert!(nets.is_empty());
+
+        // Only the networks excepted from the deny remain.
+        let nets = allow.exclude(&NetworkMatch {
+            net: net("10.0.0.0/8"),
+            except: vec![net("10.1.1.0/24"), net("10.2.0.0/16")],
+        });
+        assert_eq!(nets, vec![NetworkMatch::from(net("10.1.1.0/24"))]);
+
+        // The deny excepts the whole network.
+        let nets = allow.exclude(&NetworkMatch {
+            net: net("10.0.0.0/8"),
+            except: vec![net("10.1.0.0/16")],
+        });
+        assert_eq!(nets, vec![allow]);
+    }
+
+    #[test]
+    fn intersect() {
+        let a = NetworkMatch {
+            net: net("10.0.0.0/8"),
+            except: vec![net("10.2.0.0/16")],
+        };
+        let b = NetworkMatch {
+            net: net("10.1.0.0/16"),
+            except: vec![net("10.1.1.0/24")],
+        };
+        let nets = a.intersect(&b).into_iter().collect::<Vec<_>>();
+        assert_eq!(a.intersect(&b), b.intersect(&a));
+        assert!(matches(&nets, "10.1.0.1"));
+        assert!(!matches(&nets, "10.1.1.1"));
+        assert!(!matches(&nets, "10.0.0.1"));
+
+        assert_eq!(a.intersect(&net("10.2.1.0/24").into()), None);
+        assert_eq!(a.intersect(&net("192.168.0.0/16").into()), None);
+    }
+}
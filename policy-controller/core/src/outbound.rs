@@ -0,0 +1,147 @@
+use crate::http_route::{
+    self, Fragment, HostMatch, HttpRouteMatch, Precedence, RequestHeaderModifierFilter,
+    RequestRedirectFilter,
+};
+use ahash::AHashMap as HashMap;
+use anyhow::Result;
+use futures::prelude::*;
+use std::{num::NonZeroU16, pin::Pin, time};
+
+/// Models outbound policy discovery for Service ports.
+#[async_trait::async_trait]
+pub trait DiscoverOutboundPolicy<T> {
+    async fn get_outbound_policy(&self, target: T) -> Result<Option<OutboundPolicy>>;
+
+    async fn watch_outbound_policy(&self, target: T) -> Result<Option<OutboundPolicyStream>>;
+}
+
+pub type OutboundPolicyStream = Pin<Box<dyn Stream<Item = OutboundPolicy> + Send + Sync + 'static>>;
+
+/// Outbound policy for a Service port.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutboundPolicy {
+    pub http_routes: HashMap<String, OutboundHttpRoute>,
+    pub authority: String,
+    pub name: String,
+    pub namespace: String,
+    pub port: NonZeroU16,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutboundHttpRoute {
+    pub hostnames: Vec<HostMatch>,
+    pub rules: Vec<OutboundHttpRouteRule>,
+
+    /// The route's rank among a Service port's routes by age and name, as
+    /// with [`http_route::InboundHttpRoute::rank`].
+    pub rank: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutboundHttpRouteRule {
+    pub matches: Vec<HttpRouteMatch>,
+    pub filters: Vec<Filter>,
+    pub backends: Vec<Backend>,
+
+    /// Bounds the total time spent handling a request, including retries.
+    pub request_timeout: Option<time::Duration>,
+
+    /// Bounds the time spent on each individual request to a backend.
+    pub backend_request_timeout: Option<time::Duration>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Backend {
+    Service(WeightedService),
+
+    /// A backend that could not be resolved. Requests routed to an invalid
+    /// backend fail.
+    Invalid {
+        weight: u32,
+        message: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightedService {
+    pub weight: u32,
+    pub authority: String,
+    pub name: String,
+    pub namespace: String,
+    pub port: NonZeroU16,
+    pub filters: Vec<Filter>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Filter {
+    RequestHeaderModifier(RequestHeaderModifierFilter),
+    RequestRedirect(RequestRedirectFilter),
+}
+
+/// Orders a Service port's routes by the precedence of their matches, as with
+/// [`http_route::fragments_by_precedence`].
+pub fn fragments_by_precedence<'r>(
+    routes: impl IntoIterator<Item = (&'r String, &'r OutboundHttpRoute)>,
+) -> Vec<(&'r String, OutboundHttpRoute)> {
+    OutboundHttpRoute::fragments(routes)
+        .into_iter()
+        .enumerate()
+        .map(|(rank, fragment)| {
+            let Fragment {
+                name,
+                route,
+                hostname,
+                rules,
+            } = fragment;
+            let rules = rules
+                .into_iter()
+                .map(|(rule, matches)| {
+                    let rule = &route.rules[rule];
+                    OutboundHttpRouteRule {
+                        matches: matches
+                            .into_iter()
+                            .map(|m| rule.matches[m].clone())
+                            .collect(),
+                        ..rule.clone()
+                    }
+                })
+                .collect();
+            let route = OutboundHttpRoute {
+                hostnames: hostname
+                    .map(|h| route.hostnames[h].clone())
+                    .into_iter()
+                    .collect(),
+                rules,
+                rank,
+            };
+            (name, route)
+        })
+        .collect()
+}
+
+// === impl OutboundHttpRoute ===
+
+impl OutboundHttpRoute {
+    fn fragments<'r>(
+        routes: impl IntoIterator<Item = (&'r String, &'r Self)>,
+    ) -> Vec<Fragment<'r, Self>> {
+        http_route::fragments(
+            routes,
+            |route| route.rank,
+            |route| route.hostnames.as_slice(),
+            |route| {
+                route
+                    .rules
+                    .iter()
+                    .map(|rule| {
+                        rule.matches
+                            .iter()
+                            .map(HttpRouteMatch::precedence)
+                            .collect()
+                    })
+                    .collect()
+            },
+            Precedence::MATCH_ALL,
+        )
+    }
+}
@@ -0,0 +1,21 @@
+use crate::http_route;
+use linkerd2_proxy_api::grpc_route as proto;
+use linkerd_policy_controller_core::grpc_route::{GrpcMethodMatch, GrpcRouteMatch};
+
+pub(crate) fn convert_match(
+    GrpcRouteMatch { method, headers }: GrpcRouteMatch,
+) -> proto::GrpcRouteMatch {
+    let headers = headers
+        .into_iter()
+        .map(http_route::convert_header_match)
+        .collect();
+
+    // Omitted services and methods are encoded as empty strings, which match
+    // all values.
+    let rpc = method.map(|GrpcMethodMatch { service, method }| proto::GrpcRpcMatch {
+        service: service.unwrap_or_default(),
+        method: method.unwrap_or_default(),
+    });
+
+    proto::GrpcRouteMatch { rpc, headers }
+}
@@ -25,19 +25,7 @@ pub(crate) fn convert_match(
         method,
     }: HttpRouteMatch,
 ) -> proto::HttpRouteMatch {
-    let headers = headers
-        .into_iter()
-        .map(|hm| match hm {
-            HeaderMatch::Exact(name, value) => proto::HeaderMatch {
-                name: name.to_string(),
-                value: Some(proto::header_match::Value::Exact(value.as_bytes().to_vec())),
-            },
-            HeaderMatch::Regex(name, re) => proto::HeaderMatch {
-                name: name.to_string(),
-                value: Some(proto::header_match::Value::Regex(re.to_string())),
-            },
-        })
-        .collect();
+    let headers = headers.into_iter().map(convert_header_match).collect();
 
     let path = path.map(|path| proto::PathMatch {
         kind: Some(match path {
@@ -69,6 +57,19 @@ pub(crate) fn convert_match(
     }
 }
 
+pub(crate) fn convert_header_match(hm: HeaderMatch) -> proto::HeaderMatch {
+    match hm {
+        HeaderMatch::Exact(name, value) => proto::HeaderMatch {
+            name: name.to_string(),
+            value: Some(proto::header_match::Value::Exact(value.as_bytes().to_vec())),
+        },
+        HeaderMatch::Regex(name, re) => proto::HeaderMatch {
+            name: name.to_string(),
+            value: Some(proto::header_match::Value::Regex(re.to_string())),
+        },
+    }
+}
+
 pub(crate) fn convert_failure_injector_filter(
     FailureInjectorFilter {
         status,
@@ -1,7 +1,12 @@
 #![deny(warnings, rust_2018_idioms)]
 #![forbid(unsafe_code)]
 
+mod grpc_route;
 mod http_route;
+mod metrics;
+pub mod outbound;
+
+pub use self::metrics::{InboundMetrics, Watcher, Watchers};
 
 use futures::prelude::*;
 use linkerd2_proxy_api::{
@@ -13,71 +18,120 @@ use linkerd2_proxy_api::{
     meta::{metadata, Metadata},
 };
 use linkerd_policy_controller_core::{
-    http_route::{InboundFilter, InboundHttpRoute, InboundHttpRouteRule},
-    AuthorizationRef, ClientAuthentication, ClientAuthorization, DiscoverInboundServer,
-    IdentityMatch, InboundServer, InboundServerStream, IpNet, NetworkMatch, ProxyProtocol,
-    ServerRef,
+    grpc_route::{
+        fragments_by_precedence as grpc_route_fragments, InboundGrpcRoute, InboundGrpcRouteRule,
+    },
+    http_route::{
+        fragments_by_precedence as http_route_fragments, InboundFilter, InboundHttpRoute,
+        InboundHttpRouteRule,
+    },
+    AuthorizationAction, AuthorizationRef, ClientAuthentication, ClientAuthorization,
+    DiscoverInboundServer, DiscoverOutboundPolicy, IdentityMatch, InboundServer,
+    InboundServerStream, IpNet, NetworkMatch, ProxyProtocol, ServerRef, Workload,
 };
 use maplit::*;
-use std::{num::NonZeroU16, sync::Arc};
+use metrics::WatchGuard;
+use std::{num::NonZeroU16, sync::Arc, time};
+use tokio::sync::watch;
+use tonic::codegen::Service;
 use tracing::trace;
 
+const OUTBOUND_PATH_PREFIX: &str = "/io.linkerd.proxy.outbound.OutboundPolicies/";
+
 #[derive(Clone, Debug)]
 pub struct Server<T> {
     discover: T,
     drain: drain::Watch,
-    cluster_networks: Arc<[IpNet]>,
+    cluster_networks: watch::Receiver<Arc<[IpNet]>>,
+    metrics: InboundMetrics,
 }
 
 // === impl Server ===
 
 impl<T> Server<T>
 where
-    T: DiscoverInboundServer<(String, String, NonZeroU16)> + Send + Sync + 'static,
+    T: DiscoverInboundServer<(String, Workload, NonZeroU16)> + Send + Sync + 'static,
 {
-    pub fn new(discover: T, cluster_networks: Vec<IpNet>, drain: drain::Watch) -> Self {
+    /// Creates a server that uses the networks published on `cluster_networks`
+    /// for authorizations that do not specify any networks.
+    pub fn new(
+        discover: T,
+        cluster_networks: watch::Receiver<Arc<[IpNet]>>,
+        metrics: InboundMetrics,
+        drain: drain::Watch,
+    ) -> Self {
         Self {
             discover,
             drain,
-            cluster_networks: cluster_networks.into(),
+            cluster_networks,
+            metrics,
         }
     }
 
-    pub async fn serve(
-        self,
-        addr: std::net::SocketAddr,
-        shutdown: impl std::future::Future<Output = ()>,
-    ) -> hyper::Result<()> {
-        let svc = InboundServerPoliciesServer::new(self);
-        hyper::Server::bind(&addr)
-            .http2_only(true)
-            .tcp_nodelay(true)
-            .serve(hyper::service::make_service_fn(move |_| {
-                future::ok::<_, std::convert::Infallible>(svc.clone())
-            }))
-            .with_graceful_shutdown(shutdown)
-            .await
+    async fn get(&self, spec: proto::PortSpec) -> Result<proto::Server, tonic::Status> {
+        let target = self.check_target(spec)?;
+
+        // Lookup the configuration for an inbound port. If the pod hasn't (yet)
+        // been indexed, return a Not Found error.
+        let started = time::Instant::now();
+        let res = self.discover.get_inbound_server(target).await;
+        self.metrics.lookup("GetPort", started);
+        let s = res
+            .map_err(|e| tonic::Status::internal(format!("lookup failed: {}", e)))?
+            .ok_or_else(|| tonic::Status::not_found("unknown server"))?;
+
+        let cluster_networks = self.cluster_networks.borrow().clone();
+        Ok(to_server(&s, &*cluster_networks))
+    }
+
+    async fn watch(&self, spec: proto::PortSpec) -> Result<BoxWatchStream, tonic::Status> {
+        let target = self.check_target(spec)?;
+        let drain = self.drain.clone();
+
+        let started = time::Instant::now();
+        let res = self.discover.watch_inbound_server(target.clone()).await;
+        self.metrics.lookup("WatchPort", started);
+        let rx = res
+            .map_err(|e| tonic::Status::internal(format!("lookup failed: {}", e)))?
+            .ok_or_else(|| tonic::Status::not_found("unknown server"))?;
+
+        Ok(response_stream(
+            drain,
+            rx,
+            self.cluster_networks.clone(),
+            self.metrics.watch(target),
+        ))
     }
 
     fn check_target(
         &self,
         proto::PortSpec { workload, port }: proto::PortSpec,
-    ) -> Result<(String, String, NonZeroU16), tonic::Status> {
-        // Parse a workload name in the form namespace:name.
-        let (ns, name) = match workload.split_once(':') {
+    ) -> Result<(String, Workload, NonZeroU16), tonic::Status> {
+        // Parse a workload name in the form namespace:name. External workloads
+        // are qualified as external:namespace:name.
+        let (external, target) = match workload.strip_prefix("external:") {
+            Some(target) => (true, target),
+            None => (false, workload.as_str()),
+        };
+        let (ns, name) = match target.split_once(':') {
             None => {
                 return Err(tonic::Status::invalid_argument(format!(
                     "Invalid workload: {}",
                     workload
                 )));
             }
-            Some((ns, pod)) if ns.is_empty() || pod.is_empty() => {
+            Some((ns, name)) if ns.is_empty() || name.is_empty() || name.contains(':') => {
                 return Err(tonic::Status::invalid_argument(format!(
                     "Invalid workload: {}",
                     workload
                 )));
             }
-            Some((ns, pod)) => (ns, pod),
+            Some((ns, name)) => (ns, name),
+        };
+        let workload = if external {
+            Workload::External(name.to_string())
+        } else {
+            Workload::Pod(name.to_string())
         };
 
         // Ensure that the port is in the valid range.
@@ -85,31 +139,57 @@ where
             .and_then(NonZeroU16::try_from)
             .map_err(|_| tonic::Status::invalid_argument(format!("Invalid port: {port}")))?;
 
-        Ok((ns.to_string(), name.to_string(), port))
+        Ok((ns.to_string(), workload, port))
     }
 }
 
+/// Serves the inbound and outbound policy APIs on a single address.
+pub async fn serve<T, U>(
+    addr: std::net::SocketAddr,
+    inbound: Server<T>,
+    outbound: outbound::Server<U>,
+    shutdown: impl std::future::Future<Output = ()>,
+) -> hyper::Result<()>
+where
+    T: DiscoverInboundServer<(String, Workload, NonZeroU16)> + Send + Sync + 'static,
+    U: DiscoverOutboundPolicy<(String, String, NonZeroU16)> + Send + Sync + 'static,
+{
+    let inbound = InboundServerPoliciesServer::new(inbound);
+    let outbound = outbound.svc();
+    hyper::Server::bind(&addr)
+        .http2_only(true)
+        .tcp_nodelay(true)
+        .serve(hyper::service::make_service_fn(move |_| {
+            let inbound = inbound.clone();
+            let outbound = outbound.clone();
+            future::ok::<_, std::convert::Infallible>(hyper::service::service_fn(
+                move |req: hyper::Request<hyper::Body>| {
+                    // Both generated services are always ready, so requests
+                    // are dispatched by path without polling for readiness.
+                    if req.uri().path().starts_with(OUTBOUND_PATH_PREFIX) {
+                        outbound.clone().call(req)
+                    } else {
+                        inbound.clone().call(req)
+                    }
+                },
+            ))
+        }))
+        .with_graceful_shutdown(shutdown)
+        .await
+}
+
 #[async_trait::async_trait]
 impl<T> InboundServerPolicies for Server<T>
 where
-    T: DiscoverInboundServer<(String, String, NonZeroU16)> + Send + Sync + 'static,
+    T: DiscoverInboundServer<(String, Workload, NonZeroU16)> + Send + Sync + 'static,
 {
     async fn get_port(
         &self,
         req: tonic::Request<proto::PortSpec>,
     ) -> Result<tonic::Response<proto::Server>, tonic::Status> {
-        let target = self.check_target(req.into_inner())?;
-
-        // Lookup the configuration for an inbound port. If the pod hasn't (yet)
-        // been indexed, return a Not Found error.
-        let s = self
-            .discover
-            .get_inbound_server(target)
-            .await
-            .map_err(|e| tonic::Status::internal(format!("lookup failed: {}", e)))?
-            .ok_or_else(|| tonic::Status::not_found("unknown server"))?;
-
-        Ok(tonic::Response::new(to_server(&s, &*self.cluster_networks)))
+        let res = self.get(req.into_inner()).await;
+        self.metrics.response("GetPort", &res);
+        res.map(tonic::Response::new)
     }
 
     type WatchPortStream = BoxWatchStream;
@@ -118,45 +198,60 @@ where
         &self,
         req: tonic::Request<proto::PortSpec>,
     ) -> Result<tonic::Response<BoxWatchStream>, tonic::Status> {
-        let target = self.check_target(req.into_inner())?;
-        let drain = self.drain.clone();
-        let rx = self
-            .discover
-            .watch_inbound_server(target)
-            .await
-            .map_err(|e| tonic::Status::internal(format!("lookup failed: {}", e)))?
-            .ok_or_else(|| tonic::Status::not_found("unknown server"))?;
-        Ok(tonic::Response::new(response_stream(
-            drain,
-            rx,
-            self.cluster_networks.clone(),
-        )))
+        let res = self.watch(req.into_inner()).await;
+        self.metrics.response("WatchPort", &res);
+        res.map(tonic::Response::new)
     }
 }
 
 type BoxWatchStream =
     std::pin::Pin<Box<dyn Stream<Item = Result<proto::Server, tonic::Status>> + Send + Sync>>;
 
+/// Publishes server updates until the server is removed or the controller
+/// shuts down. The watch is tracked by `watcher` until the stream is dropped.
 fn response_stream(
     drain: drain::Watch,
     mut rx: InboundServerStream,
-    cluster_networks: Arc<[IpNet]>,
+    mut cluster_networks: watch::Receiver<Arc<[IpNet]>>,
+    mut watcher: WatchGuard,
 ) -> BoxWatchStream {
     Box::pin(async_stream::try_stream! {
         tokio::pin! {
             let shutdown = drain.signaled();
         }
 
+        // The most recent server is retained so that it may be republished
+        // when the cluster's networks change.
+        let mut server = None;
+        let mut networks_closed = false;
+
         loop {
             tokio::select! {
                 // When the port is updated with a new server, update the server watch.
                 res = rx.next() => match res {
                     Some(s) => {
-                        yield to_server(&s, &*cluster_networks);
+                        let networks = cluster_networks.borrow_and_update().clone();
+                        watcher.updated();
+                        yield to_server(&s, &*networks);
+                        server = Some(s);
                     }
                     None => return,
                 },
 
+                // When the cluster's networks change, republish the current
+                // server, since its authorizations may depend on them.
+                res = cluster_networks.changed(), if !networks_closed => {
+                    if res.is_err() {
+                        networks_closed = true;
+                        continue;
+                    }
+                    if let Some(s) = server.as_ref() {
+                        let networks = cluster_networks.borrow_and_update().clone();
+                        watcher.updated();
+                        yield to_server(s, &*networks);
+                    }
+                },
+
                 // If the server starts shutting down, close the stream so that it doesn't hold the
                 // server open.
                 _ = (&mut shutdown) => {
@@ -168,39 +263,43 @@ fn response_stream(
 }
 
 fn to_server(srv: &InboundServer, cluster_networks: &[IpNet]) -> proto::Server {
-    // Convert the protocol object into a protobuf response.
+    // Convert the protocol object into a protobuf response. Routes are served
+    // as fragments ordered by the precedence of their matches, so a fragment's
+    // rank is conveyed by its position in the list.
     let protocol = proto::ProxyProtocol {
         kind: match srv.protocol {
             ProxyProtocol::Detect { timeout } => Some(proto::proxy_protocol::Kind::Detect(
                 proto::proxy_protocol::Detect {
                     timeout: Some(timeout.into()),
-                    http_routes: srv
-                        .http_routes
-                        .iter()
-                        .map(|(name, route)| to_http_route(name, route.clone(), cluster_networks))
+                    http_routes: http_route_fragments(srv.http_routes.iter())
+                        .into_iter()
+                        .map(|(name, route)| to_http_route(name, route, cluster_networks))
                         .collect(),
                 },
             )),
             ProxyProtocol::Http1 => Some(proto::proxy_protocol::Kind::Http1(
                 proto::proxy_protocol::Http1 {
-                    routes: srv
-                        .http_routes
-                        .iter()
-                        .map(|(name, route)| to_http_route(name, route.clone(), cluster_networks))
+                    routes: http_route_fragments(srv.http_routes.iter())
+                        .into_iter()
+                        .map(|(name, route)| to_http_route(name, route, cluster_networks))
                         .collect(),
                 },
             )),
             ProxyProtocol::Http2 => Some(proto::proxy_protocol::Kind::Http2(
                 proto::proxy_protocol::Http2 {
-                    routes: srv
-                        .http_routes
-                        .iter()
-                        .map(|(name, route)| to_http_route(name, route.clone(), cluster_networks))
+                    routes: http_route_fragments(srv.http_routes.iter())
+                        .into_iter()
+                        .map(|(name, route)| to_http_route(name, route, cluster_networks))
                         .collect(),
                 },
             )),
             ProxyProtocol::Grpc => Some(proto::proxy_protocol::Kind::Grpc(
-                proto::proxy_protocol::Grpc::default(),
+                proto::proxy_protocol::Grpc {
+                    routes: grpc_route_fragments(srv.grpc_routes.iter())
+                        .into_iter()
+                        .map(|(name, route)| to_grpc_route(name, route, cluster_networks))
+                        .collect(),
+                },
             )),
             ProxyProtocol::Opaque => Some(proto::proxy_protocol::Kind::Opaque(
                 proto::proxy_protocol::Opaque {},
@@ -212,11 +311,7 @@ fn to_server(srv: &InboundServer, cluster_networks: &[IpNet]) -> proto::Server {
     };
     trace!(?protocol);
 
-    let authorizations = srv
-        .authorizations
-        .iter()
-        .map(|(n, c)| to_authz(n, c, cluster_networks))
-        .collect();
+    let authorizations = to_authzs(&srv.authorizations, cluster_networks);
     trace!(?authorizations);
 
     let labels = match &srv.reference {
@@ -241,22 +336,246 @@ fn to_server(srv: &InboundServer, cluster_networks: &[IpNet]) -> proto::Server {
     }
 }
 
+/// Converts a set of authorizations to the proxy API.
+///
+/// The proxy API cannot (yet) describe deny authorizations, so they are
+/// applied to the allow authorizations before they are sent. Clients outside
+/// of a deny's networks remain permitted by each allow, and clients within
+/// them remain permitted only if the deny requires identities and the allow
+/// can exclude those identities. Clients that a deny excepts remain
+/// permitted.
+///
+/// The proxy API cannot describe "all identities except these", so an allow
+/// that does not require identities is not sent for a deny's networks when
+/// the deny requires identities, and allowed identities that overlap a denied
+/// identity are removed. This refuses some clients that the policy permits
+/// (e.g. other identities in an allowed suffix that contains a denied
+/// identity), but never permits a denied client.
+///
+/// Proxies use the first authorization that matches a client, so audit
+/// authorizations are sent after allow authorizations (so that they only flag
+/// clients that are not otherwise permitted). Clients that an audited deny
+/// would refuse are sent first, limited to the clients that the other
+/// authorizations permit, so that they are flagged without permitting any
+/// other clients.
+fn to_authzs<'a>(
+    authorizations: impl IntoIterator<Item = (&'a AuthorizationRef, &'a ClientAuthorization)>,
+    cluster_networks: &[IpNet],
+) -> Vec<proto::Authz> {
+    let mut denies = vec![];
+    let mut audit_denies = vec![];
+    let mut permits = vec![];
+    for (reference, authz) in authorizations {
+        match authz.action {
+            AuthorizationAction::Deny => denies.push(authz),
+            AuthorizationAction::AuditDeny => audit_denies.push((reference, authz)),
+            AuthorizationAction::Allow | AuthorizationAction::Audit => {
+                permits.push((reference, authz))
+            }
+        }
+    }
+    permits
+        .sort_by_key(|(reference, authz)| (authz.action == AuthorizationAction::Audit, *reference));
+
+    let permits = permits
+        .into_iter()
+        .flat_map(|(reference, authz)| {
+            let mut permitted = vec![ClientAuthorization {
+                networks: networks_or_cluster(&authz.networks, cluster_networks),
+                ..authz.clone()
+            }];
+            for deny in denies.iter() {
+                permitted = permitted
+                    .iter()
+                    .flat_map(|permit| exclude_denied(permit, deny, cluster_networks))
+                    .collect();
+            }
+            if permitted.is_empty() {
+                trace!(?reference, "Omitting authorization for denied clients");
+            }
+            permitted
+                .into_iter()
+                .map(|authz| (reference, authz))
+                .collect()
+        })
+        .collect::<Vec<_>>();
+
+    let audited = audit_denies.iter().flat_map(|(reference, deny)| {
+        let denied = networks_or_cluster(&deny.networks, cluster_networks);
+        permits.iter().filter_map(move |(_, permit)| {
+            let networks = permit
+                .networks
+                .iter()
+                .flat_map(|net| denied.iter().filter_map(move |deny| net.intersect(deny)))
+                .collect::<Vec<_>>();
+            if networks.is_empty() {
+                return None;
+            }
+            let authentication =
+                intersect_authentication(&permit.authentication, &deny.authentication)?;
+            let authz = ClientAuthorization {
+                action: AuthorizationAction::Audit,
+                networks,
+                authentication,
+            };
+            Some(to_authz(reference, &authz, cluster_networks))
+        })
+    });
+
+    audited
+        .chain(
+            permits
+                .iter()
+                .map(|(reference, authz)| to_authz(reference, authz, cluster_networks)),
+        )
+        .collect()
+}
+
+/// Returns authorizations that permit the clients that `permit` permits and
+/// that `deny` does not match. `permit` must specify its networks.
+fn exclude_denied(
+    permit: &ClientAuthorization,
+    deny: &ClientAuthorization,
+    cluster_networks: &[IpNet],
+) -> Vec<ClientAuthorization> {
+    let denied = networks_or_cluster(&deny.networks, cluster_networks);
+
+    let mut outside = permit.networks.clone();
+    for denied_net in denied.iter() {
+        outside = outside
+            .iter()
+            .flat_map(|net| net.exclude(denied_net))
+            .collect();
+    }
+    let inside = permit
+        .networks
+        .iter()
+        .flat_map(|net| denied.iter().filter_map(move |d| net.intersect(d)))
+        .collect::<Vec<_>>();
+
+    let mut authzs = vec![];
+    if !outside.is_empty() {
+        authzs.push(ClientAuthorization {
+            networks: outside,
+            ..permit.clone()
+        });
+    }
+    if !inside.is_empty() {
+        if let Some(authentication) =
+            exclude_authentication(&permit.authentication, &deny.authentication)
+        {
+            authzs.push(ClientAuthorization {
+                networks: inside,
+                authentication,
+                ..permit.clone()
+            });
+        }
+    }
+    authzs
+}
+
+/// Returns an authentication that matches clients that `permit` matches and
+/// `deny` does not, if the proxy API can express one without matching any
+/// denied clients.
+fn exclude_authentication(
+    permit: &ClientAuthentication,
+    deny: &ClientAuthentication,
+) -> Option<ClientAuthentication> {
+    let denied = match deny {
+        ClientAuthentication::TlsAuthenticated(denied) => denied,
+        // Denies that do not require identities match all clients.
+        ClientAuthentication::Unauthenticated | ClientAuthentication::TlsUnauthenticated => {
+            return None
+        }
+    };
+
+    match permit {
+        ClientAuthentication::TlsAuthenticated(identities) => {
+            let identities = identities
+                .iter()
+                .filter(|id| !denied.iter().any(|d| d.covers(id) || id.covers(d)))
+                .cloned()
+                .collect::<Vec<_>>();
+            if identities.is_empty() {
+                return None;
+            }
+            Some(ClientAuthentication::TlsAuthenticated(identities))
+        }
+        ClientAuthentication::Unauthenticated | ClientAuthentication::TlsUnauthenticated => None,
+    }
+}
+
+/// Returns an authentication that matches exactly the clients that both
+/// `permit` and `deny` match, if any.
+fn intersect_authentication(
+    permit: &ClientAuthentication,
+    deny: &ClientAuthentication,
+) -> Option<ClientAuthentication> {
+    let denied = match deny {
+        ClientAuthentication::TlsAuthenticated(denied) => denied,
+        ClientAuthentication::Unauthenticated | ClientAuthentication::TlsUnauthenticated => {
+            return Some(permit.clone())
+        }
+    };
+
+    let identities = match permit {
+        ClientAuthentication::TlsAuthenticated(identities) => identities
+            .iter()
+            .flat_map(|id| {
+                denied.iter().filter_map(move |d| {
+                    if id.covers(d) {
+                        Some(d.clone())
+                    } else if d.covers(id) {
+                        Some(id.clone())
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect::<Vec<_>>(),
+        ClientAuthentication::Unauthenticated | ClientAuthentication::TlsUnauthenticated => {
+            denied.clone()
+        }
+    };
+    if identities.is_empty() {
+        return None;
+    }
+    Some(ClientAuthentication::TlsAuthenticated(identities))
+}
+
+/// Returns the given networks or, if there are none, the cluster networks.
+fn networks_or_cluster(networks: &[NetworkMatch], cluster_networks: &[IpNet]) -> Vec<NetworkMatch> {
+    if networks.is_empty() {
+        return cluster_networks.iter().copied().map(Into::into).collect();
+    }
+    networks.to_vec()
+}
+
 fn to_authz(
     reference: &AuthorizationRef,
     ClientAuthorization {
         networks,
         authentication,
+        action,
     }: &ClientAuthorization,
     cluster_networks: &[IpNet],
 ) -> proto::Authz {
     let meta = Metadata {
         kind: Some(match reference {
             AuthorizationRef::Default(name) => metadata::Kind::Default(name.clone()),
-            AuthorizationRef::AuthorizationPolicy(name) => {
+            AuthorizationRef::AuthorizationPolicy(name) | AuthorizationRef::AuditedPolicy(name) => {
+                // Audited clients are permitted, so they are distinguished
+                // from clients that are actually authorized by the kind.
+                let kind = if *action == AuthorizationAction::Audit {
+                    "audit-authorizationpolicy"
+                } else {
+                    "authorizationpolicy"
+                };
                 metadata::Kind::Resource(api::meta::Resource {
                     group: "policy.linkerd.io".to_string(),
-                    kind: "authorizationpolicy".to_string(),
+                    kind: kind.to_string(),
                     name: name.clone(),
+                    ..Default::default()
                 })
             }
             AuthorizationRef::ServerAuthorization(name) => {
@@ -264,6 +583,7 @@ fn to_authz(
                     group: "policy.linkerd.io".to_string(),
                     kind: "serverauthorization".to_string(),
                     name: name.clone(),
+                    ..Default::default()
                 })
             }
         }),
@@ -271,7 +591,7 @@ fn to_authz(
 
     // TODO labels are deprecated, but we want to continue to support them for older proxies. This
     // can be removed in 2.13.
-    let labels = match reference {
+    let mut labels = match reference {
         AuthorizationRef::Default(name) => convert_args!(hashmap!(
             "group" => "",
             "kind" => "default",
@@ -282,13 +602,21 @@ fn to_authz(
             "kind" => "serverauthorization",
             "name" => name,
         )),
-        AuthorizationRef::AuthorizationPolicy(name) => convert_args!(hashmap!(
-            "group" => "policy.linkerd.io",
-            "kind" => "authorizationpolicy",
-            "name" => name,
-        )),
+        AuthorizationRef::AuthorizationPolicy(name) | AuthorizationRef::AuditedPolicy(name) => {
+            convert_args!(hashmap!(
+                "group" => "policy.linkerd.io",
+                "kind" => "authorizationpolicy",
+                "name" => name,
+            ))
+        }
     };
 
+    // Older proxies only report labels, so audited clients are flagged by a
+    // label as well.
+    if *action == AuthorizationAction::Audit {
+        labels.insert("mode".to_string(), "audit".to_string());
+    }
+
     let networks = if networks.is_empty() {
         cluster_networks
             .iter()
@@ -370,6 +698,7 @@ fn to_http_route(
         hostnames,
         rules,
         authorizations,
+        ..
     }: InboundHttpRoute,
     cluster_networks: &[IpNet],
 ) -> proto::HttpRoute {
@@ -378,6 +707,7 @@ fn to_http_route(
             group: "gateway.networking.k8s.io".to_string(),
             kind: "HTTPRoute".to_string(),
             name: name.to_string(),
+            ..Default::default()
         })),
     };
 
@@ -396,10 +726,7 @@ fn to_http_route(
         )
         .collect();
 
-    let authorizations = authorizations
-        .iter()
-        .map(|(n, c)| to_authz(n, c, cluster_networks))
-        .collect();
+    let authorizations = to_authzs(&authorizations, cluster_networks);
 
     proto::HttpRoute {
         metadata: Some(metadata),
@@ -426,3 +753,65 @@ fn convert_filter(filter: InboundFilter) -> proto::http_route::Filter {
         }),
     }
 }
+
+fn to_grpc_route(
+    name: impl ToString,
+    InboundGrpcRoute {
+        hostnames,
+        rules,
+        authorizations,
+        ..
+    }: InboundGrpcRoute,
+    cluster_networks: &[IpNet],
+) -> proto::GrpcRoute {
+    let metadata = Metadata {
+        kind: Some(metadata::Kind::Resource(api::meta::Resource {
+            group: "gateway.networking.k8s.io".to_string(),
+            kind: "GRPCRoute".to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        })),
+    };
+
+    let hosts = hostnames
+        .into_iter()
+        .map(http_route::convert_host_match)
+        .collect();
+
+    let rules = rules
+        .into_iter()
+        .map(
+            |InboundGrpcRouteRule { matches, filters }| proto::grpc_route::Rule {
+                matches: matches.into_iter().map(grpc_route::convert_match).collect(),
+                filters: filters
+                    .into_iter()
+                    .filter_map(convert_grpc_filter)
+                    .collect(),
+            },
+        )
+        .collect();
+
+    let authorizations = to_authzs(&authorizations, cluster_networks);
+
+    proto::GrpcRoute {
+        metadata: Some(metadata),
+        hosts,
+        rules,
+        authorizations,
+    }
+}
+
+/// Converts a filter for a gRPC route. Filters that only apply to HTTP routes
+/// (i.e. redirects and HTTP failure injection) are never produced for gRPC
+/// routes and are dropped.
+fn convert_grpc_filter(filter: InboundFilter) -> Option<proto::grpc_route::Filter> {
+    use proto::grpc_route::filter::Kind;
+
+    let kind = match filter {
+        InboundFilter::RequestHeaderModifier(f) => {
+            Kind::RequestHeaderModifier(http_route::convert_header_modifier_filter(f))
+        }
+        InboundFilter::FailureInjector(_) | InboundFilter::RequestRedirect(_) => return None,
+    };
+    Some(proto::grpc_route::Filter { kind: Some(kind) })
+}
@@ -0,0 +1,231 @@
+//! Instruments the inbound policy API.
+//!
+//! Prometheus metrics describe requests, lookups, and watch streams, while
+//! `Watchers` tracks each open watch so that proxies with stale policies may
+//! be identified.
+
+use linkerd_policy_controller_core::Workload;
+use parking_lot::Mutex;
+use prometheus_client::{
+    encoding::text::Encode,
+    metrics::{
+        counter::Counter,
+        family::Family,
+        gauge::Gauge,
+        histogram::{exponential_buckets, Histogram},
+    },
+    registry::Registry,
+};
+use std::{collections::HashMap, num::NonZeroU16, sync::Arc, time};
+
+/// Metrics for the inbound policy API.
+#[derive(Clone)]
+pub struct InboundMetrics {
+    requests: Family<RequestLabels, Counter>,
+    lookup_duration: Family<MethodLabels, Histogram>,
+    watches: Gauge,
+    updates: Counter,
+    update_intervals: Histogram,
+    watchers: Watchers,
+}
+
+/// Tracks the watches that are currently open.
+#[derive(Clone, Debug, Default)]
+pub struct Watchers(Arc<Mutex<WatchersInner>>);
+
+/// Describes an open watch.
+#[derive(Clone, Debug)]
+pub struct Watcher {
+    pub namespace: String,
+    pub workload: Workload,
+    pub port: NonZeroU16,
+    pub connected_at: time::SystemTime,
+
+    /// The time at which the last update was sent on the stream, if any.
+    pub last_update_at: Option<time::SystemTime>,
+
+    /// The number of updates sent on the stream.
+    pub updates: u64,
+}
+
+/// Records a watch stream's updates, removing the watch when dropped.
+pub(crate) struct WatchGuard {
+    id: u64,
+    updates: u64,
+    last_update: time::Instant,
+    watchers: Watchers,
+    watches: Gauge,
+    update_counter: Counter,
+    update_intervals: Histogram,
+}
+
+#[derive(Debug, Default)]
+struct WatchersInner {
+    next_id: u64,
+    by_id: HashMap<u64, Watcher>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Encode)]
+struct RequestLabels {
+    method: &'static str,
+    grpc_status: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Encode)]
+struct MethodLabels {
+    method: &'static str,
+}
+
+// === impl InboundMetrics ===
+
+impl InboundMetrics {
+    pub fn register(registry: &mut Registry) -> Self {
+        let metrics = Self::default();
+        registry.register(
+            "requests",
+            "The number of inbound policy requests, by method and gRPC status code",
+            Box::new(metrics.requests.clone()),
+        );
+        registry.register(
+            "lookup_duration_seconds",
+            "The time taken to look up a workload's port in the index",
+            Box::new(metrics.lookup_duration.clone()),
+        );
+        registry.register(
+            "watches",
+            "The number of open watch streams",
+            Box::new(metrics.watches.clone()),
+        );
+        registry.register(
+            "watch_updates",
+            "The number of updates sent on watch streams",
+            Box::new(metrics.updates.clone()),
+        );
+        registry.register(
+            "watch_update_interval_seconds",
+            "The time between a watch stream's updates, observed as each update is sent",
+            Box::new(metrics.update_intervals.clone()),
+        );
+        metrics
+    }
+
+    /// Returns a handle to the open watches.
+    pub fn watchers(&self) -> Watchers {
+        self.watchers.clone()
+    }
+
+    pub(crate) fn response<T>(&self, method: &'static str, res: &Result<T, tonic::Status>) {
+        let code = match res {
+            Ok(_) => tonic::Code::Ok,
+            Err(status) => status.code(),
+        };
+        self.requests
+            .get_or_create(&RequestLabels {
+                method,
+                grpc_status: (code as i32).to_string(),
+            })
+            .inc();
+    }
+
+    pub(crate) fn lookup(&self, method: &'static str, started: time::Instant) {
+        self.lookup_duration
+            .get_or_create(&MethodLabels { method })
+            .observe(started.elapsed().as_secs_f64());
+    }
+
+    /// Registers a watch, which is removed when the returned guard is dropped.
+    pub(crate) fn watch(
+        &self,
+        (namespace, workload, port): (String, Workload, NonZeroU16),
+    ) -> WatchGuard {
+        self.watches.inc();
+        let id = self.watchers.insert(Watcher {
+            namespace,
+            workload,
+            port,
+            connected_at: time::SystemTime::now(),
+            last_update_at: None,
+            updates: 0,
+        });
+        WatchGuard {
+            id,
+            updates: 0,
+            last_update: time::Instant::now(),
+            watchers: self.watchers.clone(),
+            watches: self.watches.clone(),
+            update_counter: self.updates.clone(),
+            update_intervals: self.update_intervals.clone(),
+        }
+    }
+}
+
+impl Default for InboundMetrics {
+    fn default() -> Self {
+        Self {
+            requests: Family::default(),
+            // 10us to ~0.2s.
+            lookup_duration: Family::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(0.00001, 4.0, 8))
+            }),
+            watches: Gauge::default(),
+            updates: Counter::default(),
+            // 10ms to ~3h. The first update is observed from when the stream
+            // connected.
+            update_intervals: Histogram::new(exponential_buckets(0.01, 10.0, 7)),
+            watchers: Watchers::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for InboundMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InboundMetrics").finish_non_exhaustive()
+    }
+}
+
+// === impl Watchers ===
+
+impl Watchers {
+    /// Lists the open watches, ordered by the time at which they connected.
+    pub fn list(&self) -> Vec<Watcher> {
+        let mut watchers = self.0.lock().by_id.values().cloned().collect::<Vec<_>>();
+        watchers.sort_by_key(|w| w.connected_at);
+        watchers
+    }
+
+    fn insert(&self, watcher: Watcher) -> u64 {
+        let mut inner = self.0.lock();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.by_id.insert(id, watcher);
+        id
+    }
+}
+
+// === impl WatchGuard ===
+
+impl WatchGuard {
+    /// Records that an update is being sent on the stream.
+    pub(crate) fn updated(&mut self) {
+        let now = time::Instant::now();
+        self.update_intervals.observe(
+            now.saturating_duration_since(self.last_update)
+                .as_secs_f64(),
+        );
+        self.last_update = now;
+        self.update_counter.inc();
+
+        self.updates += 1;
+        if let Some(watcher) = self.watchers.0.lock().by_id.get_mut(&self.id) {
+            watcher.last_update_at = Some(time::SystemTime::now());
+            watcher.updates = self.updates;
+        }
+    }
+}
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        self.watchers.0.lock().by_id.remove(&self.id);
+        self.watches.dec();
+    }
+}
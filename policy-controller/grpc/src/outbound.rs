@@ -0,0 +1,386 @@
+use crate::http_route;
+use futures::prelude::*;
+use linkerd2_proxy_api::{
+    self as api,
+    meta::{metadata, Metadata},
+    outbound::{
+        self as proto,
+        outbound_policies_server::{OutboundPolicies, OutboundPoliciesServer},
+    },
+};
+use linkerd_policy_controller_core::outbound::{
+    self, Backend, DiscoverOutboundPolicy, Filter, OutboundHttpRoute, OutboundHttpRouteRule,
+    OutboundPolicy, OutboundPolicyStream, WeightedService,
+};
+use std::{num::NonZeroU16, sync::Arc, time};
+
+/// How long outbound proxies wait to detect the protocol of a connection.
+const DETECT_TIMEOUT: time::Duration = time::Duration::from_secs(10);
+
+/// Limits the number of requests that may be queued for a backend.
+const QUEUE_CAPACITY: u32 = 100;
+
+/// How long requests may wait in a backend's queue before failing.
+const QUEUE_FAILFAST_TIMEOUT: time::Duration = time::Duration::from_secs(3);
+
+#[derive(Clone, Debug)]
+pub struct Server<T> {
+    discover: T,
+    drain: drain::Watch,
+    cluster_domain: Arc<str>,
+}
+
+// === impl Server ===
+
+impl<T> Server<T>
+where
+    T: DiscoverOutboundPolicy<(String, String, NonZeroU16)> + Send + Sync + 'static,
+{
+    pub fn new(discover: T, cluster_domain: impl Into<Arc<str>>, drain: drain::Watch) -> Self {
+        Self {
+            discover,
+            drain,
+            cluster_domain: cluster_domain.into(),
+        }
+    }
+
+    pub fn svc(self) -> OutboundPoliciesServer<Self> {
+        OutboundPoliciesServer::new(self)
+    }
+
+    /// Parses a target in the form `name.namespace.svc.cluster-domain:port`.
+    fn check_target(
+        &self,
+        proto::TrafficSpec { target, .. }: proto::TrafficSpec,
+    ) -> Result<(String, String, NonZeroU16), tonic::Status> {
+        let authority = match target {
+            Some(proto::traffic_spec::Target::Authority(authority)) => authority,
+            Some(proto::traffic_spec::Target::Addr(_)) => {
+                return Err(tonic::Status::unimplemented(
+                    "address targets are not supported",
+                ));
+            }
+            None => return Err(tonic::Status::invalid_argument("missing target")),
+        };
+
+        let invalid =
+            || tonic::Status::invalid_argument(format!("Invalid authority: {}", authority));
+
+        let (host, port) = authority.rsplit_once(':').ok_or_else(invalid)?;
+        let port = port
+            .parse::<NonZeroU16>()
+            .map_err(|_| tonic::Status::invalid_argument(format!("Invalid port: {}", port)))?;
+
+        let suffix = format!(".svc.{}", self.cluster_domain);
+        let (name, ns) = host
+            .trim_end_matches('.')
+            .strip_suffix(&*suffix)
+            .and_then(|svc| svc.split_once('.'))
+            .ok_or_else(invalid)?;
+        if name.is_empty() || ns.is_empty() || ns.contains('.') {
+            return Err(invalid());
+        }
+
+        Ok((ns.to_string(), name.to_string(), port))
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> OutboundPolicies for Server<T>
+where
+    T: DiscoverOutboundPolicy<(String, String, NonZeroU16)> + Send + Sync + 'static,
+{
+    async fn get(
+        &self,
+        req: tonic::Request<proto::TrafficSpec>,
+    ) -> Result<tonic::Response<proto::OutboundPolicy>, tonic::Status> {
+        let target = self.check_target(req.into_inner())?;
+
+        // Lookup the configuration for a Service port. If the Service hasn't
+        // (yet) been indexed, return a Not Found error.
+        let policy = self
+            .discover
+            .get_outbound_policy(target)
+            .await
+            .map_err(|e| tonic::Status::internal(format!("lookup failed: {}", e)))?
+            .ok_or_else(|| tonic::Status::not_found("unknown service"))?;
+
+        Ok(tonic::Response::new(to_service(policy)))
+    }
+
+    type WatchStream = BoxWatchStream;
+
+    async fn watch(
+        &self,
+        req: tonic::Request<proto::TrafficSpec>,
+    ) -> Result<tonic::Response<BoxWatchStream>, tonic::Status> {
+        let target = self.check_target(req.into_inner())?;
+        let drain = self.drain.clone();
+        let rx = self
+            .discover
+            .watch_outbound_policy(target)
+            .await
+            .map_err(|e| tonic::Status::internal(format!("lookup failed: {}", e)))?
+            .ok_or_else(|| tonic::Status::not_found("unknown service"))?;
+        Ok(tonic::Response::new(response_stream(drain, rx)))
+    }
+}
+
+type BoxWatchStream = std::pin::Pin<
+    Box<dyn Stream<Item = Result<proto::OutboundPolicy, tonic::Status>> + Send + Sync>,
+>;
+
+fn response_stream(drain: drain::Watch, mut rx: OutboundPolicyStream) -> BoxWatchStream {
+    Box::pin(async_stream::try_stream! {
+        tokio::pin! {
+            let shutdown = drain.signaled();
+        }
+
+        loop {
+            tokio::select! {
+                // When the Service is updated with a new policy, update the watch.
+                res = rx.next() => match res {
+                    Some(policy) => {
+                        yield to_service(policy);
+                    }
+                    None => return,
+                },
+
+                // If the server starts shutting down, close the stream so that it doesn't hold the
+                // server open.
+                _ = (&mut shutdown) => {
+                    return;
+                }
+            }
+        }
+    })
+}
+
+fn to_service(
+    OutboundPolicy {
+        http_routes,
+        authority,
+        name,
+        namespace,
+        port,
+    }: OutboundPolicy,
+) -> proto::OutboundPolicy {
+    let metadata = Metadata {
+        kind: Some(metadata::Kind::Resource(api::meta::Resource {
+            group: "core".to_string(),
+            kind: "Service".to_string(),
+            name,
+            namespace,
+            port: u16::from(port).into(),
+            ..Default::default()
+        })),
+    };
+
+    // Routes are served as fragments, ordered by the precedence of their
+    // matches.
+    let routes = outbound::fragments_by_precedence(http_routes.iter())
+        .into_iter()
+        .map(|(name, route)| to_http_route(name.clone(), route))
+        .collect::<Vec<_>>();
+
+    let routes = if routes.is_empty() {
+        // Without any routes, all requests are sent to the Service.
+        vec![default_http_route(authority)]
+    } else {
+        routes
+    };
+
+    proto::OutboundPolicy {
+        metadata: Some(metadata),
+        protocol: Some(proto::ProxyProtocol {
+            kind: Some(proto::proxy_protocol::Kind::Detect(
+                proto::proxy_protocol::Detect {
+                    timeout: DETECT_TIMEOUT.try_into().ok(),
+                    opaque: Some(proto::proxy_protocol::Opaque::default()),
+                    http1: Some(proto::proxy_protocol::Http1 {
+                        routes: routes.clone(),
+                        ..Default::default()
+                    }),
+                    http2: Some(proto::proxy_protocol::Http2 {
+                        routes,
+                        ..Default::default()
+                    }),
+                },
+            )),
+        }),
+    }
+}
+
+fn default_http_route(authority: String) -> proto::HttpRoute {
+    proto::HttpRoute {
+        metadata: Some(Metadata {
+            kind: Some(metadata::Kind::Default("http".to_string())),
+        }),
+        hosts: vec![],
+        rules: vec![proto::http_route::Rule {
+            matches: vec![api::http_route::HttpRouteMatch {
+                path: Some(api::http_route::PathMatch {
+                    kind: Some(api::http_route::path_match::Kind::Prefix("/".to_string())),
+                }),
+                ..Default::default()
+            }],
+            backends: Some(proto::http_route::Distribution {
+                kind: Some(proto::http_route::distribution::Kind::FirstAvailable(
+                    proto::http_route::distribution::FirstAvailable {
+                        backends: vec![proto::http_route::RouteBackend {
+                            backend: Some(service_backend(authority)),
+                            ..Default::default()
+                        }],
+                    },
+                )),
+            }),
+            ..Default::default()
+        }],
+    }
+}
+
+fn to_http_route(
+    name: String,
+    OutboundHttpRoute {
+        hostnames, rules, ..
+    }: OutboundHttpRoute,
+) -> proto::HttpRoute {
+    let metadata = Metadata {
+        kind: Some(metadata::Kind::Resource(api::meta::Resource {
+            group: "gateway.networking.k8s.io".to_string(),
+            kind: "HTTPRoute".to_string(),
+            name,
+            ..Default::default()
+        })),
+    };
+
+    let hosts = hostnames
+        .into_iter()
+        .map(http_route::convert_host_match)
+        .collect();
+
+    let rules = rules
+        .into_iter()
+        .map(
+            |OutboundHttpRouteRule {
+                 matches,
+                 filters,
+                 backends,
+                 request_timeout,
+                 backend_request_timeout,
+             }| {
+                let backends = backends
+                    .into_iter()
+                    .map(|backend| to_route_backend(backend, backend_request_timeout))
+                    .collect();
+                proto::http_route::Rule {
+                    matches: matches.into_iter().map(http_route::convert_match).collect(),
+                    filters: filters.into_iter().map(convert_filter).collect(),
+                    backends: Some(proto::http_route::Distribution {
+                        kind: Some(proto::http_route::distribution::Kind::RandomAvailable(
+                            proto::http_route::distribution::RandomAvailable { backends },
+                        )),
+                    }),
+                    request_timeout: request_timeout.and_then(|t| t.try_into().ok()),
+                }
+            },
+        )
+        .collect();
+
+    proto::HttpRoute {
+        metadata: Some(metadata),
+        hosts,
+        rules,
+    }
+}
+
+fn to_route_backend(
+    backend: Backend,
+    request_timeout: Option<time::Duration>,
+) -> proto::http_route::WeightedRouteBackend {
+    let request_timeout = request_timeout.and_then(|t| t.try_into().ok());
+    match backend {
+        Backend::Service(WeightedService {
+            weight,
+            authority,
+            filters,
+            ..
+        }) => proto::http_route::WeightedRouteBackend {
+            weight,
+            backend: Some(proto::http_route::RouteBackend {
+                backend: Some(service_backend(authority)),
+                filters: filters.into_iter().map(convert_filter).collect(),
+                request_timeout,
+            }),
+        },
+
+        // Requests to invalid backends fail with an error.
+        Backend::Invalid { weight, message } => proto::http_route::WeightedRouteBackend {
+            weight,
+            backend: Some(proto::http_route::RouteBackend {
+                backend: Some(proto::Backend {
+                    metadata: Some(Metadata {
+                        kind: Some(metadata::Kind::Default("invalid".to_string())),
+                    }),
+                    queue: Some(default_queue()),
+                    kind: None,
+                }),
+                filters: vec![proto::http_route::Filter {
+                    kind: Some(proto::http_route::filter::Kind::FailureInjector(
+                        api::http_route::HttpFailureInjector {
+                            status: 500,
+                            message,
+                            ratio: Some(api::http_route::Ratio {
+                                numerator: 1,
+                                denominator: 1,
+                            }),
+                        },
+                    )),
+                }],
+                request_timeout,
+            }),
+        },
+    }
+}
+
+fn service_backend(authority: String) -> proto::Backend {
+    proto::Backend {
+        metadata: Some(Metadata {
+            kind: Some(metadata::Kind::Default(authority.clone())),
+        }),
+        queue: Some(default_queue()),
+        kind: Some(proto::backend::Kind::Balancer(proto::backend::BalanceP2c {
+            discovery: Some(proto::backend::EndpointDiscovery {
+                kind: Some(proto::backend::endpoint_discovery::Kind::Dst(
+                    proto::backend::endpoint_discovery::DestinationGet { path: authority },
+                )),
+            }),
+            load: Some(proto::backend::balance_p2c::Load::PeakEwma(
+                proto::backend::balance_p2c::PeakEwma {
+                    default_rtt: time::Duration::from_millis(30).try_into().ok(),
+                    decay: time::Duration::from_secs(10).try_into().ok(),
+                },
+            )),
+        })),
+    }
+}
+
+fn default_queue() -> proto::Queue {
+    proto::Queue {
+        capacity: QUEUE_CAPACITY,
+        failfast_timeout: QUEUE_FAILFAST_TIMEOUT.try_into().ok(),
+    }
+}
+
+fn convert_filter(filter: Filter) -> proto::http_route::Filter {
+    use proto::http_route::filter::Kind;
+
+    proto::http_route::Filter {
+        kind: Some(match filter {
+            Filter::RequestHeaderModifier(f) => {
+                Kind::RequestHeaderModifier(http_route::convert_header_modifier_filter(f))
+            }
+            Filter::RequestRedirect(f) => Kind::Redirect(http_route::convert_redirect_filter(f)),
+        }),
+    }
+}
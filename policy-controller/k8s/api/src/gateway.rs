@@ -0,0 +1,10 @@
+//! Gateway API resources that are not (yet) provided by the `k8s-gateway-api`
+//! crate.
+
+pub mod grpcroute;
+pub mod referencegrant;
+
+pub use self::{
+    grpcroute::{GrpcRoute, GrpcRouteSpec},
+    referencegrant::{ReferenceGrant, ReferenceGrantSpec},
+};
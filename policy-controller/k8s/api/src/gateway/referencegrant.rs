@@ -0,0 +1,66 @@
+/// ReferenceGrant identifies kinds of resources in other namespaces that are
+/// trusted to reference the specified kinds of resources in the same namespace
+/// as the policy.
+///
+/// Each ReferenceGrant can be used to represent a unique trust relationship.
+/// Additional ReferenceGrants can be used to add to the set of trusted sources
+/// of inbound references for the namespace they are defined within.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    kube::CustomResource,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
+#[kube(
+    group = "gateway.networking.k8s.io",
+    version = "v1alpha2",
+    kind = "ReferenceGrant",
+    struct = "ReferenceGrant",
+    namespaced
+)]
+pub struct ReferenceGrantSpec {
+    /// From describes the trusted namespaces and kinds that can reference the
+    /// resources described in "To". Each entry in this list must be
+    /// considered to be an additional place that references can be valid
+    /// from.
+    pub from: Vec<ReferenceGrantFrom>,
+
+    /// To describes the resources that may be referenced by the resources
+    /// described in "From". Each entry in this list must be considered to be
+    /// an additional place that references can be valid to.
+    pub to: Vec<ReferenceGrantTo>,
+}
+
+/// ReferenceGrantFrom describes trusted namespaces and kinds.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+pub struct ReferenceGrantFrom {
+    /// Group is the group of the referent. When empty, the Kubernetes core
+    /// API group is inferred.
+    pub group: String,
+
+    /// Kind is the kind of the referent.
+    pub kind: String,
+
+    /// Namespace is the namespace of the referent.
+    pub namespace: String,
+}
+
+/// ReferenceGrantTo describes what Kinds are allowed as targets of the
+/// references.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+pub struct ReferenceGrantTo {
+    /// Group is the group of the referent. When empty, the Kubernetes core
+    /// API group is inferred.
+    pub group: String,
+
+    /// Kind is the kind of the referent.
+    pub kind: String,
+
+    /// Name is the name of the referent. When unspecified, this policy refers
+    /// to all resources of the specified Group and Kind in the local
+    /// namespace.
+    pub name: Option<String>,
+}
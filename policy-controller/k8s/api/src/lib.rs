@@ -1,13 +1,22 @@
 #![deny(warnings, rust_2018_idioms)]
 #![forbid(unsafe_code)]
 
+pub mod gateway;
 pub mod labels;
 pub mod policy;
+pub mod workload;
 
 pub use self::labels::Labels;
-pub use k8s_openapi::api::{
-    self,
-    core::v1::{Namespace, Node, NodeSpec, Pod, PodSpec, PodStatus, ServiceAccount},
+pub use k8s_openapi::{
+    api::{
+        self,
+        core::v1::{
+            Namespace, Node, NodeSpec, Pod, PodSpec, PodStatus, Service, ServiceAccount,
+            ServicePort, ServiceSpec,
+        },
+    },
+    apimachinery::pkg::apis::meta::v1::{Condition, Time},
+    chrono,
 };
 pub use kube::{
     api::{ObjectMeta, Resource, ResourceExt},
@@ -8,12 +8,15 @@ pub mod server_authorization;
 pub mod target_ref;
 
 pub use self::{
-    authorization_policy::{AuthorizationPolicy, AuthorizationPolicySpec},
+    authorization_policy::{
+        Action as AuthorizationAction, AuthorizationPolicy, AuthorizationPolicySpec,
+        AuthorizationPolicyStatus, Mode as AuthorizationMode,
+    },
     httproute::{HttpRoute, HttpRouteSpec},
     meshtls_authentication::{MeshTLSAuthentication, MeshTLSAuthenticationSpec},
     network::Network,
     network_authentication::{NetworkAuthentication, NetworkAuthenticationSpec},
-    server::{Server, ServerSpec},
+    server::{Server, ServerSpec, ServerStatus},
     server_authorization::{ServerAuthorization, ServerAuthorizationSpec},
     target_ref::{ClusterTargetRef, LocalTargetRef, NamespacedTargetRef},
 };
@@ -1,4 +1,6 @@
 use super::{LocalTargetRef, NamespacedTargetRef};
+use crate::labels;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
 
 #[derive(
     Clone, Debug, kube::CustomResource, serde::Deserialize, serde::Serialize, schemars::JsonSchema,
@@ -7,10 +9,61 @@ use super::{LocalTargetRef, NamespacedTargetRef};
     group = "policy.linkerd.io",
     version = "v1alpha1",
     kind = "AuthorizationPolicy",
+    status = "AuthorizationPolicyStatus",
     namespaced
 )]
 #[serde(rename_all = "camelCase")]
 pub struct AuthorizationPolicySpec {
-    pub target_ref: LocalTargetRef,
+    /// References the resource to which the policy applies.
+    ///
+    /// Exactly one of `target_ref` and `target_selector` must be set.
+    pub target_ref: Option<LocalTargetRef>,
+
+    /// Selects the `Server`s in the policy's namespace to which the policy
+    /// applies by their labels.
+    pub target_selector: Option<labels::Selector>,
+
     pub required_authentication_refs: Vec<NamespacedTargetRef>,
+
+    /// Determines whether clients matching the required authentications are
+    /// permitted or refused. Defaults to `Allow`.
+    ///
+    /// `Deny` policies take precedence over `Allow` policies on the same
+    /// target, and `Deny` policies that target a `Server` also apply to its
+    /// routes.
+    pub action: Option<Action>,
+
+    /// Determines whether the policy is enforced. In `Audit` mode, clients
+    /// that the policy would refuse are permitted, but their traffic is
+    /// flagged as unauthorized. Clients that enforced policies refuse remain
+    /// refused. Defaults to `Enforce`.
+    pub mode: Option<Mode>,
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize, schemars::JsonSchema,
+)]
+pub enum Action {
+    Allow,
+    Deny,
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize, schemars::JsonSchema,
+)]
+pub enum Mode {
+    Enforce,
+    Audit,
+}
+
+/// Describes whether the policy was accepted by the controller.
+#[derive(
+    Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizationPolicyStatus {
+    /// Conditions describe the current state of the policy, e.g. whether its
+    /// target and authentication references could be found.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
 }
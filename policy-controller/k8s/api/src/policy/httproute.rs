@@ -1,8 +1,9 @@
 pub use k8s_gateway_api::{
-    CommonRouteSpec, Hostname, HttpHeaderMatch, HttpHeaderName, HttpMethod, HttpPathMatch,
-    HttpQueryParamMatch, HttpRequestHeaderFilter, HttpRequestRedirectFilter, HttpRouteMatch,
-    LocalObjectReference, ParentReference, RouteStatus,
+    BackendRef, CommonRouteSpec, Hostname, HttpBackendRef, HttpHeader, HttpHeaderMatch,
+    HttpHeaderName, HttpMethod, HttpPathMatch, HttpQueryParamMatch, HttpRequestHeaderFilter,
+    HttpRequestRedirectFilter, HttpRouteMatch, LocalObjectReference, ParentReference, RouteStatus,
 };
+use std::time;
 
 /// HTTPRoute provides a way to route HTTP requests. This includes the
 /// capability to match requests by hostname, path, header, or query param.
@@ -124,8 +125,40 @@ pub struct HttpRouteRule {
     ///
     /// Support: Core
     pub filters: Option<Vec<HttpRouteFilter>>,
+
+    /// BackendRefs defines the backend(s) where matching requests should be
+    /// sent. This is only used when the route's parent is a Service.
+    ///
+    /// If unspecified, requests are sent to the parent Service.
+    pub backend_refs: Option<Vec<HttpBackendRef>>,
+
+    /// Timeouts defines the timeouts that can be configured for an HTTP
+    /// request.
+    pub timeouts: Option<HttpRouteTimeouts>,
+}
+
+/// HTTPRouteTimeouts defines timeouts that can be configured for an HTTPRoute.
+///
+/// Durations are expressed as strings in the form `1h30m`, `10s` or `500ms`.
+#[derive(
+    Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpRouteTimeouts {
+    /// Request specifies the maximum duration for the proxy to respond to an
+    /// HTTP request which matches this rule, including retries to backends.
+    pub request: Option<String>,
+
+    /// BackendRequest specifies a timeout for an individual request from the
+    /// proxy to a backend. It must be less than or equal to the `request`
+    /// timeout.
+    pub backend_request: Option<String>,
 }
 
+#[derive(Debug, thiserror::Error)]
+#[error("invalid duration: {0:?}")]
+pub struct InvalidDuration(String);
+
 /// HTTPRouteFilter defines processing steps that must be completed during the
 /// request or response lifecycle. HTTPRouteFilters are meant as an extension
 /// point to express processing that may be done in Gateway implementations.
@@ -161,3 +194,87 @@ pub struct HttpRouteStatus {
     #[serde(flatten)]
     pub inner: RouteStatus,
 }
+
+// === impl HttpRouteTimeouts ===
+
+impl HttpRouteTimeouts {
+    pub fn request_timeout(&self) -> Result<Option<time::Duration>, InvalidDuration> {
+        self.request.as_deref().map(parse_duration).transpose()
+    }
+
+    pub fn backend_request_timeout(&self) -> Result<Option<time::Duration>, InvalidDuration> {
+        self.backend_request
+            .as_deref()
+            .map(parse_duration)
+            .transpose()
+    }
+}
+
+/// Parses a Go-style duration like `1h30m`, `10s` or `250ms`.
+///
+/// Only the units supported by the Gateway API (`h`, `m`, `s` and `ms`) are
+/// accepted.
+pub fn parse_duration(s: &str) -> Result<time::Duration, InvalidDuration> {
+    let invalid = || InvalidDuration(s.to_string());
+
+    let mut rest = s;
+    let mut total = time::Duration::ZERO;
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        if digits == 0 {
+            return Err(invalid());
+        }
+        let value = rest[..digits].parse::<u64>().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+
+        let (unit, len) = if rest.starts_with("ms") {
+            (time::Duration::from_millis(1), 2)
+        } else if rest.starts_with('h') {
+            (time::Duration::from_secs(60 * 60), 1)
+        } else if rest.starts_with('m') {
+            (time::Duration::from_secs(60), 1)
+        } else if rest.starts_with('s') {
+            (time::Duration::from_secs(1), 1)
+        } else {
+            return Err(invalid());
+        };
+        rest = &rest[len..];
+
+        let value = u32::try_from(value).map_err(|_| invalid())?;
+        total = unit
+            .checked_mul(value)
+            .and_then(|d| total.checked_add(d))
+            .ok_or_else(invalid)?;
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        for (s, expected) in [
+            ("10s", time::Duration::from_secs(10)),
+            ("500ms", time::Duration::from_millis(500)),
+            ("1h30m", time::Duration::from_secs(90 * 60)),
+            ("1m30s250ms", time::Duration::from_millis(90_250)),
+        ] {
+            assert_eq!(parse_duration(s).unwrap(), expected, "{}", s);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        for s in ["", "10", "s", "1d", "1.5s", "-1s", "10 s"] {
+            assert!(parse_duration(s).is_err(), "{} must not parse", s);
+        }
+    }
+}
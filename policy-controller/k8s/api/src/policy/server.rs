@@ -1,4 +1,5 @@
 use super::super::labels;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
 use kube::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -10,6 +11,7 @@ use std::num::NonZeroU16;
     group = "policy.linkerd.io",
     version = "v1beta1",
     kind = "Server",
+    status = "ServerStatus",
     namespaced
 )]
 #[serde(rename_all = "camelCase")]
@@ -19,14 +21,38 @@ pub struct ServerSpec {
     pub proxy_protocol: Option<ProxyProtocol>,
 }
 
-/// References a pod spec's port by name or number.
+/// Describes whether the server conflicts with other servers.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatus {
+    /// Conditions describe the current state of the server, e.g. whether it
+    /// selects pod ports that are already selected by another server.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+}
+
+/// References a pod spec's port by name or number, or an inclusive range of
+/// port numbers (e.g. `9000-9019`).
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
 #[serde(untagged)]
 pub enum Port {
     Number(NonZeroU16),
+    Range(PortRange),
     Name(String),
 }
 
+/// An inclusive range of port numbers, formatted as `<start>-<end>`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PortRange {
+    pub start: NonZeroU16,
+    pub end: NonZeroU16,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("not a valid port range: {0}")]
+pub struct PortRangeParseError(String);
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 pub enum ProxyProtocol {
     #[serde(rename = "unknown")]
@@ -42,3 +68,129 @@ pub enum ProxyProtocol {
     #[serde(rename = "TLS")]
     Tls,
 }
+
+// === impl Port ===
+
+impl Port {
+    /// Indicates whether two port references may refer to the same pod port.
+    ///
+    /// Named ports are only compared by name, since their numeric values are
+    /// not known without a pod spec.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a == b,
+            (Self::Number(p), Self::Range(r)) | (Self::Range(r), Self::Number(p)) => r.contains(*p),
+            (Self::Range(a), Self::Range(b)) => a.start <= b.end && b.start <= a.end,
+            (Self::Name(a), Self::Name(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+// === impl PortRange ===
+
+impl PortRange {
+    #[inline]
+    pub fn contains(&self, port: NonZeroU16) -> bool {
+        self.start <= port && port <= self.end
+    }
+
+    /// Iterates over all ports in the range.
+    pub fn iter(&self) -> impl Iterator<Item = NonZeroU16> {
+        (self.start.get()..=self.end.get()).filter_map(|p| NonZeroU16::try_from(p).ok())
+    }
+}
+
+impl std::str::FromStr for PortRange {
+    type Err = PortRangeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || PortRangeParseError(s.to_string());
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let start = start.trim().parse::<NonZeroU16>().map_err(|_| invalid())?;
+        let end = end.trim().parse::<NonZeroU16>().map_err(|_| invalid())?;
+        if start > end {
+            return Err(invalid());
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl TryFrom<String> for PortRange {
+    type Error = PortRangeParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<PortRange> for String {
+    fn from(range: PortRange) -> String {
+        range.to_string()
+    }
+}
+
+impl std::fmt::Display for PortRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl JsonSchema for PortRange {
+    fn schema_name() -> String {
+        "PortRange".to_string()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_ports() {
+        for (json, expected) in [
+            (
+                serde_json::json!(8080),
+                Port::Number(8080.try_into().unwrap()),
+            ),
+            (
+                serde_json::json!("9000-9019"),
+                Port::Range(PortRange {
+                    start: 9000.try_into().unwrap(),
+                    end: 9019.try_into().unwrap(),
+                }),
+            ),
+            (
+                serde_json::json!("admin-http"),
+                Port::Name("admin-http".to_string()),
+            ),
+            (
+                serde_json::json!("9019-9000"),
+                Port::Name("9019-9000".to_string()),
+            ),
+        ] {
+            let port = serde_json::from_value::<Port>(json.clone()).unwrap();
+            assert_eq!(port, expected, "{}", json);
+        }
+    }
+
+    #[test]
+    fn port_overlaps() {
+        let num = |p: u16| Port::Number(p.try_into().unwrap());
+        let range = |s: &str| Port::Range(s.parse().unwrap());
+        assert!(num(9000).overlaps(&range("9000-9019")));
+        assert!(range("9000-9019").overlaps(&num(9019)));
+        assert!(!range("9000-9019").overlaps(&num(9020)));
+        assert!(range("9000-9019").overlaps(&range("9010-9029")));
+        assert!(!range("9000-9019").overlaps(&range("9020-9029")));
+        assert!(!range("9000-9019").overlaps(&Port::Name("http".to_string())));
+    }
+}
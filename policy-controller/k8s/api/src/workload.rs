@@ -0,0 +1,5 @@
+//! Resources in the `workload.linkerd.io` API group.
+
+pub mod external_workload;
+
+pub use self::external_workload::{ExternalWorkload, ExternalWorkloadSpec};
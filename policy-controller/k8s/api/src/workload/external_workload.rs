@@ -0,0 +1,70 @@
+use std::num::NonZeroU16;
+
+/// Describes a single workload (e.g. a VM) that runs outside of Kubernetes
+/// and is meshed by a Linkerd proxy.
+///
+/// External workloads are selected by `Server` pod selectors by their labels,
+/// just like pods.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    kube::CustomResource,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
+#[kube(
+    group = "workload.linkerd.io",
+    version = "v1alpha1",
+    kind = "ExternalWorkload",
+    namespaced
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalWorkloadSpec {
+    /// The workload's mesh identity.
+    pub mesh_tls: MeshTls,
+
+    /// The ports exposed by the workload.
+    pub ports: Option<Vec<PortSpec>>,
+
+    /// The IP addresses at which the workload is reachable.
+    #[serde(rename = "workloadIPs")]
+    pub workload_ips: Option<Vec<WorkloadIp>>,
+}
+
+/// The TLS identity of an external workload.
+#[derive(
+    Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct MeshTls {
+    /// The workload's TLS identity, e.g.
+    /// `spiffe://root.linkerd.cluster.local/vm-0`.
+    pub identity: String,
+
+    /// The server name used by clients to establish TLS connections to the
+    /// workload.
+    pub server_name: String,
+}
+
+/// A port exposed by an external workload.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortSpec {
+    /// An optional name for the port, which may be referenced by `Server`
+    /// resources.
+    pub name: Option<String>,
+
+    pub port: NonZeroU16,
+
+    /// The port's protocol. Only `TCP` ports are considered by policy.
+    pub protocol: Option<String>,
+}
+
+/// An IP address at which an external workload is reachable.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
+pub struct WorkloadIp {
+    pub ip: String,
+}
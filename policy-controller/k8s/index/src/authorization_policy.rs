@@ -1,4 +1,5 @@
 use anyhow::Result;
+use linkerd_policy_controller_core::AuthorizationAction;
 use linkerd_policy_controller_k8s_api::{
     self as k8s,
     policy::{LocalTargetRef, NamespacedTargetRef},
@@ -9,12 +10,19 @@ use linkerd_policy_controller_k8s_api::{
 pub(crate) struct Spec {
     pub target: Target,
     pub authentications: Vec<AuthenticationTarget>,
+    pub action: AuthorizationAction,
+
+    /// Indicates that the policy is not enforced; clients that it would refuse
+    /// are instead flagged.
+    pub audit: bool,
 }
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum Target {
     HttpRoute(String),
+    GrpcRoute(String),
     Server(String),
+    ServerSelector(k8s::labels::Selector),
     Namespace,
 }
 
@@ -44,7 +52,14 @@ impl TryFrom<k8s::policy::AuthorizationPolicySpec> for Spec {
     type Error = anyhow::Error;
 
     fn try_from(ap: k8s::policy::AuthorizationPolicySpec) -> Result<Self> {
-        let target = target(ap.target_ref)?;
+        let target = match (ap.target_ref, ap.target_selector) {
+            (Some(t), None) => target(t)?,
+            (None, Some(selector)) => Target::ServerSelector(selector),
+            (Some(_), Some(_)) => {
+                anyhow::bail!("targetRef and targetSelector may not both be set")
+            }
+            (None, None) => anyhow::bail!("one of targetRef or targetSelector must be set"),
+        };
 
         let authentications = ap
             .required_authentication_refs
@@ -52,9 +67,17 @@ impl TryFrom<k8s::policy::AuthorizationPolicySpec> for Spec {
             .map(authentication_ref)
             .collect::<Result<Vec<_>>>()?;
 
+        let action = match ap.action {
+            None | Some(k8s::policy::AuthorizationAction::Allow) => AuthorizationAction::Allow,
+            Some(k8s::policy::AuthorizationAction::Deny) => AuthorizationAction::Deny,
+        };
+        let audit = ap.mode == Some(k8s::policy::AuthorizationMode::Audit);
+
         Ok(Self {
             target,
             authentications,
+            action,
+            audit,
         })
     }
 }
@@ -68,6 +91,7 @@ fn target(t: LocalTargetRef) -> Result<Target> {
         {
             Ok(Target::HttpRoute(t.name))
         }
+        t if t.targets_kind::<k8s::gateway::GrpcRoute>() => Ok(Target::GrpcRoute(t.name)),
         _ => anyhow::bail!(
             "unsupported authorization target type: {}",
             t.canonical_kind()
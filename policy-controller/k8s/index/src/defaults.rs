@@ -14,6 +14,10 @@ pub enum DefaultPolicy {
 
     /// Indicates that all traffic is denied unless explicitly permitted by an authorization policy.
     Deny,
+
+    /// Indicates that all traffic is permitted, but flagged as unauthorized so
+    /// that it may be observed before a `deny` default is enforced.
+    Audit,
 }
 
 // === impl DefaultPolicy ===
@@ -40,6 +44,7 @@ impl std::str::FromStr for DefaultPolicy {
                 cluster_only: true,
             }),
             "deny" => Ok(Self::Deny),
+            "audit" => Ok(Self::Audit),
             s => Err(anyhow!("invalid mode: {:?}", s)),
         }
     }
@@ -65,6 +70,7 @@ impl std::fmt::Display for DefaultPolicy {
                 cluster_only: true,
             } => "cluster-unauthenticated".fmt(f),
             Self::Deny => "deny".fmt(f),
+            Self::Audit => "audit".fmt(f),
         }
     }
 }
@@ -77,6 +83,7 @@ mod test {
     fn test_parse_displayed() {
         for default in [
             DefaultPolicy::Deny,
+            DefaultPolicy::Audit,
             DefaultPolicy::Allow {
                 authenticated_only: true,
                 cluster_only: false,
@@ -0,0 +1,171 @@
+use crate::{
+    http_route::{convert, InboundParentRef},
+    pod::PortSet,
+};
+use ahash::AHashMap as HashMap;
+use anyhow::{bail, Error, Result};
+use linkerd_policy_controller_core::{grpc_route, http_route};
+use linkerd_policy_controller_k8s_api::{gateway::grpcroute as api, Time};
+use std::num::NonZeroU16;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InboundGrpcRouteBinding {
+    pub parents: Vec<InboundParentRef>,
+    pub route: grpc_route::InboundGrpcRoute,
+
+    /// Used to order routes with equal precedence, oldest first.
+    pub creation_timestamp: Option<Time>,
+}
+
+impl TryFrom<api::GrpcRoute> for InboundGrpcRouteBinding {
+    type Error = Error;
+
+    fn try_from(route: api::GrpcRoute) -> Result<Self, Self::Error> {
+        let route_ns = route.metadata.namespace.as_deref();
+        let creation_timestamp = route.metadata.creation_timestamp;
+        Self::check_parents(route_ns, route.spec.inner.parent_refs.iter().flatten())?;
+        let parents = InboundParentRef::collect_from(route_ns, route.spec.inner.parent_refs)?;
+        let route = Self::try_route(route.spec.hostnames, route.spec.rules)?;
+        Ok(InboundGrpcRouteBinding {
+            parents,
+            route,
+            creation_timestamp,
+        })
+    }
+}
+
+impl InboundGrpcRouteBinding {
+    /// Fails if the route references a `Server` in another namespace. Only
+    /// HTTPRoutes may attach to Servers in other namespaces.
+    pub(crate) fn check_parents<'p>(
+        route_ns: Option<&str>,
+        parent_refs: impl IntoIterator<Item = &'p api::ParentReference>,
+    ) -> Result<()> {
+        for parent_ref in parent_refs {
+            if let Some(Ok(InboundParentRef::Server {
+                namespace: Some(ns),
+                ..
+            })) = InboundParentRef::from_parent_ref(route_ns, parent_ref.clone())
+            {
+                bail!(
+                    "GRPCRoute may not reference a Server in another namespace ({})",
+                    ns
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts a route's hostnames and rules, independently of its parent
+    /// references.
+    pub(crate) fn try_route(
+        hostnames: Option<Vec<api::Hostname>>,
+        rules: Option<Vec<api::GrpcRouteRule>>,
+    ) -> Result<grpc_route::InboundGrpcRoute> {
+        let hostnames = hostnames
+            .into_iter()
+            .flatten()
+            .map(convert::http_match)
+            .collect();
+
+        let rules = rules
+            .into_iter()
+            .flatten()
+            .map(Self::try_rule)
+            .collect::<Result<_>>()?;
+
+        Ok(grpc_route::InboundGrpcRoute {
+            hostnames,
+            rules,
+            authorizations: HashMap::default(),
+            rank: 0,
+        })
+    }
+
+    /// Indicates whether the route selects the named server on the given port.
+    /// GRPCRoutes only have parents in their own namespace.
+    #[inline]
+    pub(crate) fn selects_server_port(
+        &self,
+        name: &str,
+        port: NonZeroU16,
+        port_names: &HashMap<String, PortSet>,
+    ) -> bool {
+        self.parents
+            .iter()
+            .any(|p| p.selects_server_port(None, name, port, port_names))
+    }
+
+    fn try_rule(
+        api::GrpcRouteRule {
+            matches,
+            filters,
+            backend_refs: _,
+        }: api::GrpcRouteRule,
+    ) -> Result<grpc_route::InboundGrpcRouteRule> {
+        let matches = matches
+            .into_iter()
+            .flatten()
+            .map(Self::try_match)
+            .collect::<Result<_>>()?;
+
+        let filters = filters
+            .into_iter()
+            .flatten()
+            .map(Self::try_filter)
+            .collect::<Result<_>>()?;
+
+        Ok(grpc_route::InboundGrpcRouteRule { matches, filters })
+    }
+
+    pub(crate) fn try_match(
+        api::GrpcRouteMatch { method, headers }: api::GrpcRouteMatch,
+    ) -> Result<grpc_route::GrpcRouteMatch> {
+        let method = method
+            .map(
+                |api::GrpcMethodMatch {
+                     r#type,
+                     service,
+                     method,
+                 }| {
+                    if let Some(api::GrpcMethodMatchType::RegularExpression) = r#type {
+                        bail!("RegularExpression method matches are not supported");
+                    }
+                    let service = service.filter(|s| !s.is_empty());
+                    let method = method.filter(|m| !m.is_empty());
+                    if service.is_none() && method.is_none() {
+                        bail!("method match must specify a service or a method");
+                    }
+                    Ok(grpc_route::GrpcMethodMatch { service, method })
+                },
+            )
+            .transpose()?;
+
+        let headers = headers
+            .into_iter()
+            .flatten()
+            .map(convert::header_match)
+            .collect::<Result<_>>()?;
+
+        Ok(grpc_route::GrpcRouteMatch { method, headers })
+    }
+
+    fn try_filter(filter: api::GrpcRouteFilter) -> Result<http_route::InboundFilter> {
+        let filter = match filter {
+            api::GrpcRouteFilter::RequestHeaderModifier {
+                request_header_modifier,
+            } => {
+                let filter = convert::req_header_modifier(request_header_modifier)?;
+                http_route::InboundFilter::RequestHeaderModifier(filter)
+            }
+
+            api::GrpcRouteFilter::RequestMirror { .. } => {
+                bail!("RequestMirror filter is not supported")
+            }
+            api::GrpcRouteFilter::ExtensionRef { .. } => {
+                bail!("ExtensionRef filter is not supported")
+            }
+        };
+        Ok(filter)
+    }
+}
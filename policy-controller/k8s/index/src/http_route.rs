@@ -1,19 +1,40 @@
+use crate::pod::PortSet;
 use ahash::AHashMap as HashMap;
-use anyhow::{bail, Error, Result};
+use anyhow::{bail, Result};
 use k8s_gateway_api as api;
 use linkerd_policy_controller_core::http_route;
-use linkerd_policy_controller_k8s_api::policy::httproute as policy;
+use linkerd_policy_controller_k8s_api::{policy::httproute as policy, ResourceExt, Time};
 use std::num::NonZeroU16;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct InboundRouteBinding {
     pub parents: Vec<InboundParentRef>,
     pub route: http_route::InboundHttpRoute,
+
+    /// The API group of the route resource. Cross-namespace references from
+    /// the route must be permitted by a `ReferenceGrant` for this group.
+    pub group: &'static str,
+
+    /// Used to order routes with equal precedence, oldest first.
+    pub creation_timestamp: Option<Time>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum InboundParentRef {
-    Server(String),
+    Server {
+        name: String,
+
+        /// The namespace of the Server, if it differs from the route's
+        /// namespace. Such references must be permitted by a `ReferenceGrant`
+        /// in the Server's namespace.
+        namespace: Option<String>,
+
+        /// Limits the route to a single port on the Server's pods.
+        port: Option<NonZeroU16>,
+
+        /// Limits the route to a named port on the Server's pods.
+        section_name: Option<String>,
+    },
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
@@ -21,33 +42,52 @@ pub enum InvalidParentRef {
     #[error("HTTPRoute resource does not reference a Server resource")]
     DoesNotSelectServer,
 
-    #[error("HTTPRoute resource may not reference a parent Server in an other namespace")]
-    ServerInAnotherNamespace,
-
-    #[error("HTTPRoute resource may not reference a parent by port")]
-    SpecifiesPort,
-
-    #[error("HTTPRoute resource may not reference a parent by section name")]
-    SpecifiesSection,
+    #[error("HTTPRoute resource may not reference parent port 0")]
+    InvalidPort,
 }
 
-impl TryFrom<api::HttpRoute> for InboundRouteBinding {
-    type Error = Error;
+impl InboundRouteBinding {
+    pub(crate) fn try_from_gateway(route: api::HttpRoute) -> Result<Self> {
+        let route_ns = route.namespace().expect("HTTPRoute must have a namespace");
+        let creation_timestamp = route.metadata.creation_timestamp;
+        let parents =
+            InboundParentRef::collect_from(Some(&route_ns), route.spec.inner.parent_refs)?;
+        let route = Self::try_gateway_route(route.spec.hostnames, route.spec.rules)?;
+        Ok(InboundRouteBinding {
+            parents,
+            route,
+            group: "gateway.networking.k8s.io",
+            creation_timestamp,
+        })
+    }
+
+    pub(crate) fn try_from_policy(route: policy::HttpRoute) -> Result<Self> {
+        let route_ns = route.namespace().expect("HTTPRoute must have a namespace");
+        let creation_timestamp = route.metadata.creation_timestamp;
+        let parents =
+            InboundParentRef::collect_from(Some(&route_ns), route.spec.inner.parent_refs)?;
+        let route = Self::try_policy_route(route.spec.hostnames, route.spec.rules)?;
+        Ok(InboundRouteBinding {
+            parents,
+            route,
+            group: "policy.linkerd.io",
+            creation_timestamp,
+        })
+    }
 
-    fn try_from(route: api::HttpRoute) -> Result<Self, Self::Error> {
-        let route_ns = route.metadata.namespace.as_deref();
-        let parents = InboundParentRef::collect_from(route_ns, route.spec.inner.parent_refs)?;
-        let hostnames = route
-            .spec
-            .hostnames
+    /// Converts a `gateway.networking.k8s.io` route's hostnames and rules,
+    /// independently of its parent references.
+    pub(crate) fn try_gateway_route(
+        hostnames: Option<Vec<api::Hostname>>,
+        rules: Option<Vec<api::HttpRouteRule>>,
+    ) -> Result<http_route::InboundHttpRoute> {
+        let hostnames = hostnames
             .into_iter()
             .flatten()
             .map(convert::http_match)
             .collect();
 
-        let rules = route
-            .spec
-            .rules
+        let rules = rules
             .into_iter()
             .flatten()
             .map(
@@ -55,65 +95,73 @@ impl TryFrom<api::HttpRoute> for InboundRouteBinding {
                      matches,
                      filters,
                      backend_refs: _,
-                 }| Self::try_rule(matches, filters, Self::try_gateway_filter),
+                 }| { Self::try_rule(matches, filters, Self::try_gateway_filter) },
             )
             .collect::<Result<_>>()?;
 
-        Ok(InboundRouteBinding {
-            parents,
-            route: http_route::InboundHttpRoute {
-                hostnames,
-                rules,
-                authorizations: HashMap::default(),
-            },
+        Ok(http_route::InboundHttpRoute {
+            hostnames,
+            rules,
+            authorizations: HashMap::default(),
+            rank: 0,
         })
     }
-}
-
-impl TryFrom<policy::HttpRoute> for InboundRouteBinding {
-    type Error = Error;
 
-    fn try_from(route: policy::HttpRoute) -> Result<Self, Self::Error> {
-        let route_ns = route.metadata.namespace.as_deref();
-        let parents = InboundParentRef::collect_from(route_ns, route.spec.inner.parent_refs)?;
-        let hostnames = route
-            .spec
-            .hostnames
+    /// Converts a `policy.linkerd.io` route's hostnames and rules,
+    /// independently of its parent references.
+    pub(crate) fn try_policy_route(
+        hostnames: Option<Vec<api::Hostname>>,
+        rules: Option<Vec<policy::HttpRouteRule>>,
+    ) -> Result<http_route::InboundHttpRoute> {
+        let hostnames = hostnames
             .into_iter()
             .flatten()
             .map(convert::http_match)
             .collect();
 
-        let rules = route
-            .spec
-            .rules
+        let rules = rules
             .into_iter()
             .flatten()
-            .map(|policy::HttpRouteRule { matches, filters }| {
-                Self::try_rule(matches, filters, Self::try_policy_filter)
-            })
+            .map(
+                |policy::HttpRouteRule {
+                     matches,
+                     filters,
+                     backend_refs: _,
+                     timeouts: _,
+                 }| { Self::try_rule(matches, filters, Self::try_policy_filter) },
+            )
             .collect::<Result<_>>()?;
 
-        Ok(InboundRouteBinding {
-            parents,
-            route: http_route::InboundHttpRoute {
-                hostnames,
-                rules,
-                authorizations: HashMap::default(),
-            },
+        Ok(http_route::InboundHttpRoute {
+            hostnames,
+            rules,
+            authorizations: HashMap::default(),
+            rank: 0,
         })
     }
-}
 
-impl InboundRouteBinding {
     #[inline]
-    pub fn selects_server(&self, name: &str) -> bool {
+    pub(crate) fn selects_server_port(
+        &self,
+        server_ns: Option<&str>,
+        name: &str,
+        port: NonZeroU16,
+        port_names: &HashMap<String, PortSet>,
+    ) -> bool {
         self.parents
             .iter()
-            .any(|p| matches!(p, InboundParentRef::Server(n) if n == name))
+            .any(|p| p.selects_server_port(server_ns, name, port, port_names))
     }
 
-    fn try_match(
+    /// Returns the namespaces of the route's parents that are not in the
+    /// route's namespace.
+    pub(crate) fn foreign_parent_namespaces(&self) -> impl Iterator<Item = &str> {
+        self.parents.iter().filter_map(|p| match p {
+            InboundParentRef::Server { namespace, .. } => namespace.as_deref(),
+        })
+    }
+
+    pub(crate) fn try_match(
         api::HttpRouteMatch {
             path,
             headers,
@@ -136,15 +184,7 @@ impl InboundRouteBinding {
         let headers = headers
             .into_iter()
             .flatten()
-            .map(|hm| match hm {
-                api::HttpHeaderMatch::Exact { name, value } => Ok(http_route::HeaderMatch::Exact(
-                    name.parse()?,
-                    value.parse()?,
-                )),
-                api::HttpHeaderMatch::RegularExpression { name, value } => Ok(
-                    http_route::HeaderMatch::Regex(name.parse()?, value.parse()?),
-                ),
-            })
+            .map(convert::header_match)
             .collect::<Result<_>>()?;
 
         let query_params = query_params
@@ -207,6 +247,7 @@ impl InboundRouteBinding {
                 http_route::InboundFilter::RequestRedirect(filter)
             }
 
+            // Inbound proxies cannot mirror or rewrite requests.
             api::HttpRouteFilter::RequestMirror { .. } => {
                 bail!("RequestMirror filter is not supported")
             }
@@ -239,7 +280,7 @@ impl InboundRouteBinding {
 }
 
 impl InboundParentRef {
-    fn collect_from(
+    pub(crate) fn collect_from(
         route_ns: Option<&str>,
         parent_refs: Option<Vec<api::ParentReference>>,
     ) -> Result<Vec<Self>, InvalidParentRef> {
@@ -257,7 +298,9 @@ impl InboundParentRef {
         Ok(parents)
     }
 
-    fn from_parent_ref(
+    /// Converts a parent reference, returning `None` if the parent is not a
+    /// `Server`.
+    pub(crate) fn from_parent_ref(
         route_ns: Option<&str>,
         api::ParentReference {
             group,
@@ -280,23 +323,54 @@ impl InboundParentRef {
             }
         }
 
-        if namespace.is_some() && namespace.as_deref() != route_ns {
-            return Some(Err(InvalidParentRef::ServerInAnotherNamespace));
-        }
-        if port.is_some() {
-            return Some(Err(InvalidParentRef::SpecifiesPort));
-        }
-        if section_name.is_some() {
-            return Some(Err(InvalidParentRef::SpecifiesSection));
-        }
+        let namespace = namespace.filter(|ns| Some(ns.as_str()) != route_ns);
+        let port = match port.map(NonZeroU16::try_from).transpose() {
+            Ok(port) => port,
+            Err(_) => return Some(Err(InvalidParentRef::InvalidPort)),
+        };
+
+        Some(Ok(InboundParentRef::Server {
+            name,
+            namespace,
+            port,
+            section_name,
+        }))
+    }
 
-        Some(Ok(InboundParentRef::Server(name)))
+    /// Indicates whether the parent selects the named server on the given pod
+    /// port. The server's namespace is unset when it is in the route's
+    /// namespace. `port_names` maps the pod's named ports to port numbers.
+    pub(crate) fn selects_server_port(
+        &self,
+        server_ns: Option<&str>,
+        server: &str,
+        port: NonZeroU16,
+        port_names: &HashMap<String, PortSet>,
+    ) -> bool {
+        match self {
+            Self::Server {
+                name,
+                namespace,
+                port: parent_port,
+                section_name,
+            } => {
+                name == server
+                    && namespace.as_deref() == server_ns
+                    && parent_port.map_or(true, |p| p == port)
+                    && section_name.as_deref().map_or(true, |section| {
+                        port_names
+                            .get(section)
+                            .map_or(false, |ports| ports.contains(&port))
+                    })
+            }
+        }
     }
 }
 
-mod convert {
+pub(crate) mod convert {
     use super::*;
-    pub(super) fn http_match(hostname: api::Hostname) -> http_route::HostMatch {
+
+    pub(crate) fn http_match(hostname: api::Hostname) -> http_route::HostMatch {
         if hostname.starts_with("*.") {
             let mut reverse_labels = hostname
                 .split('.')
@@ -309,7 +383,20 @@ mod convert {
             http_route::HostMatch::Exact(hostname)
         }
     }
-    pub(super) fn req_header_modifier(
+
+    pub(crate) fn header_match(hm: api::HttpHeaderMatch) -> Result<http_route::HeaderMatch> {
+        match hm {
+            api::HttpHeaderMatch::Exact { name, value } => Ok(http_route::HeaderMatch::Exact(
+                name.parse()?,
+                value.parse()?,
+            )),
+            api::HttpHeaderMatch::RegularExpression { name, value } => Ok(
+                http_route::HeaderMatch::Regex(name.parse()?, value.parse()?),
+            ),
+        }
+    }
+
+    pub(crate) fn req_header_modifier(
         api::HttpRequestHeaderFilter { set, add, remove }: api::HttpRequestHeaderFilter,
     ) -> Result<http_route::RequestHeaderModifierFilter> {
         Ok(http_route::RequestHeaderModifierFilter {
@@ -331,7 +418,7 @@ mod convert {
         })
     }
 
-    pub(super) fn req_redirect(
+    pub(crate) fn req_redirect(
         api::HttpRequestRedirectFilter {
             scheme,
             hostname,
@@ -343,14 +430,18 @@ mod convert {
         Ok(http_route::RequestRedirectFilter {
             scheme: scheme.as_deref().map(TryInto::try_into).transpose()?,
             host: hostname,
-            path: path.map(|path_mod| match path_mod {
-                api::HttpPathModifier::ReplaceFullPath(s) => http_route::PathModifier::Full(s),
-                api::HttpPathModifier::ReplacePrefixMatch(s) => http_route::PathModifier::Prefix(s),
-            }),
+            path: path.map(path_modifier),
             port: port.and_then(|p| NonZeroU16::try_from(p).ok()),
             status: status_code
                 .map(http_route::StatusCode::try_from)
                 .transpose()?,
         })
     }
+
+    fn path_modifier(path_mod: api::HttpPathModifier) -> http_route::PathModifier {
+        match path_mod {
+            api::HttpPathModifier::ReplaceFullPath(s) => http_route::PathModifier::Full(s),
+            api::HttpPathModifier::ReplacePrefixMatch(s) => http_route::PathModifier::Prefix(s),
+        }
+    }
 }
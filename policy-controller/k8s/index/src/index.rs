@@ -1,5 +1,6 @@
-//! Keeps track of `Pod`, `Server`, and `ServerAuthorization` resources to
-//! provide a dynamic server configuration for all known ports on all pods.
+//! Keeps track of `Namespace`, `Pod`, `Server`, and `ServerAuthorization`
+//! resources to provide a dynamic server configuration for all known ports on
+//! all pods.
 //!
 //! The `Index` type exposes a single public method: `Index::pod_server_rx`,
 //! which is used to lookup pod/ports (i.e. by the gRPC API). Otherwise, it
@@ -7,18 +8,21 @@
 //! kubernetes resources.
 
 use crate::{
-    authorization_policy, defaults::DefaultPolicy, http_route::InboundRouteBinding,
-    meshtls_authentication, network_authentication, pod, server, server_authorization, ClusterInfo,
+    authorization_policy, defaults::DefaultPolicy, grpc_route::InboundGrpcRouteBinding,
+    http_route::InboundRouteBinding, meshtls_authentication, metrics::IndexMetrics,
+    network_authentication, pod, reference_grant, server, server_authorization, ClusterInfo,
 };
 use ahash::{AHashMap as HashMap, AHashSet as HashSet};
 use anyhow::{anyhow, bail, Result};
 use linkerd_policy_controller_core::{
-    AuthorizationRef, ClientAuthentication, ClientAuthorization, IdentityMatch, InboundHttpRoute,
-    InboundServer, IpNet, Ipv4Net, Ipv6Net, NetworkMatch, ProxyProtocol, ServerRef,
+    AuthorizationAction, AuthorizationRef, ClientAuthentication, ClientAuthorization,
+    IdentityMatch, InboundGrpcRoute, InboundHttpRoute, InboundServer, IpNet, Ipv4Net, Ipv6Net,
+    NetworkMatch, ProxyProtocol, ServerRef,
 };
 use linkerd_policy_controller_k8s_api::{self as k8s, policy::server::Port, ResourceExt};
 use parking_lot::RwLock;
-use std::{collections::hash_map::Entry, num::NonZeroU16, sync::Arc};
+use prometheus_client::registry::Registry;
+use std::{collections::hash_map::Entry, num::NonZeroU16, sync::Arc, time};
 use tokio::sync::watch;
 use tracing::info_span;
 
@@ -32,6 +36,29 @@ pub struct Index {
     cluster_info: Arc<ClusterInfo>,
     namespaces: NamespaceIndex,
     authentications: AuthenticationNsIndex,
+    reference_grants: ReferenceGrantNsIndex,
+    cluster_networks: ClusterNetworks,
+    metrics: IndexMetrics,
+}
+
+/// Tracks the networks that include the cluster's pod and service IPs.
+///
+/// Networks are discovered from the `spec.podCIDRs` of each `Node`, when nodes
+/// are indexed. Otherwise, the configured networks are used.
+#[derive(Debug)]
+struct ClusterNetworks {
+    /// The networks configured in `ClusterInfo`. These are used when no
+    /// nodes report pod networks.
+    configured: Vec<IpNet>,
+
+    /// Pod networks by node name.
+    by_node: HashMap<String, Vec<IpNet>>,
+
+    /// A sender used to broadcast updates to the cluster's networks.
+    tx: watch::Sender<Arc<[IpNet]>>,
+
+    /// A receiver that is updated when the cluster's networks change.
+    rx: watch::Receiver<Arc<[IpNet]>>,
 }
 
 /// Holds all `Pod`, `Server`, and `ServerAuthorization` indices by-namespace.
@@ -39,6 +66,12 @@ pub struct Index {
 struct NamespaceIndex {
     cluster_info: Arc<ClusterInfo>,
     by_ns: HashMap<String, Namespace>,
+
+    /// The names of routes that have a parent `Server` in another namespace,
+    /// by the parent's namespace and then by the route's namespace.
+    foreign_routes: HashMap<String, HashMap<String, HashSet<String>>>,
+
+    metrics: IndexMetrics,
 }
 
 /// Holds all `NetworkAuthentication` and `MeshTLSAuthentication` indices by-namespace.
@@ -50,6 +83,28 @@ struct AuthenticationNsIndex {
     by_ns: HashMap<String, AuthenticationIndex>,
 }
 
+/// Holds all `ReferenceGrant` resources by-namespace.
+///
+/// This is separate from `NamespaceIndex` because grants permit resources in
+/// other namespaces to reference the resources in the grant's namespace.
+#[derive(Debug, Default)]
+pub(crate) struct ReferenceGrantNsIndex {
+    by_ns: HashMap<String, HashMap<String, reference_grant::Spec>>,
+}
+
+/// An `HTTPRoute` with a parent `Server` in another namespace.
+///
+/// Routes are indexed in their own namespace, so these are looked up by the
+/// parent's namespace whenever a namespace is reindexed. The route's
+/// authorizations are determined by policies in the route's namespace.
+#[derive(Debug)]
+struct ForeignHttpRoute {
+    namespace: String,
+    name: String,
+    binding: InboundRouteBinding,
+    authorizations: HashMap<AuthorizationRef, ClientAuthorization>,
+}
+
 /// Holds `Pod`, `Server`, and `ServerAuthorization` indices for a single namespace.
 #[derive(Debug)]
 struct Namespace {
@@ -62,6 +117,11 @@ struct Namespace {
 struct PodIndex {
     namespace: String,
     by_name: HashMap<String, Pod>,
+
+    /// External workloads are indexed like pods so that they may be selected
+    /// by `Server` resources. They are tracked separately because their names
+    /// may overlap with pod names.
+    external_workloads: HashMap<String, Pod>,
 }
 
 /// Holds a single pod's data with the server watches for all known ports.
@@ -106,11 +166,16 @@ struct PolicyIndex {
     namespace: String,
     cluster_info: Arc<ClusterInfo>,
 
+    /// Default settings, as configured by the namespace's annotations. Pod
+    /// annotations take precedence over these settings.
+    settings: pod::Settings,
+
     servers: HashMap<String, server::Server>,
     server_authorizations: HashMap<String, server_authorization::ServerAuthz>,
 
     authorization_policies: HashMap<String, authorization_policy::Spec>,
     http_routes: HashMap<String, InboundRouteBinding>,
+    grpc_routes: HashMap<String, InboundGrpcRouteBinding>,
 }
 
 #[derive(Debug, Default)]
@@ -119,6 +184,19 @@ struct AuthenticationIndex {
     network: HashMap<String, network_authentication::Spec>,
 }
 
+/// A route binding that is stored in a namespace's `PolicyIndex`.
+trait RouteBinding: PartialEq + Sized {
+    fn routes(policy: &mut PolicyIndex) -> &mut HashMap<String, Self>;
+}
+
+/// A route resource that may be bound to servers as a `B`.
+trait BindRoute<B: RouteBinding>: ResourceExt {
+    /// The route's kind, as reported in metrics.
+    const KIND: &'static str;
+
+    fn bind(self) -> Result<B>;
+}
+
 struct NsUpdate<T> {
     added: Vec<(String, T)>,
     removed: HashSet<String>,
@@ -129,16 +207,98 @@ struct NsUpdate<T> {
 impl Index {
     pub fn shared(cluster_info: impl Into<Arc<ClusterInfo>>) -> SharedIndex {
         let cluster_info = cluster_info.into();
+        let (tx, rx) = watch::channel(cluster_info.networks.clone().into());
+        let metrics = IndexMetrics::default();
         Arc::new(RwLock::new(Self {
+            cluster_networks: ClusterNetworks {
+                configured: cluster_info.networks.clone(),
+                by_node: HashMap::default(),
+                tx,
+                rx,
+            },
             cluster_info: cluster_info.clone(),
             namespaces: NamespaceIndex {
                 cluster_info,
                 by_ns: HashMap::default(),
+                foreign_routes: HashMap::default(),
+                metrics: metrics.clone(),
             },
             authentications: AuthenticationNsIndex::default(),
+            reference_grants: ReferenceGrantNsIndex::default(),
+            metrics,
         }))
     }
 
+    /// Registers the index's metrics.
+    pub fn register_metrics(&self, registry: &mut Registry) {
+        self.metrics.register(registry);
+    }
+
+    /// Updates the index's resource and watch gauges from its current state.
+    /// This should be called before metrics are encoded.
+    pub fn refresh_metrics(&self) {
+        let mut counts = Vec::new();
+        let mut watches = 0;
+        for (ns, index) in self.namespaces.by_ns.iter() {
+            let PolicyIndex {
+                servers,
+                server_authorizations,
+                authorization_policies,
+                http_routes,
+                grpc_routes,
+                ..
+            } = &index.policy;
+            counts.extend([
+                ("Pod", ns.clone(), index.pods.by_name.len()),
+                (
+                    "ExternalWorkload",
+                    ns.clone(),
+                    index.pods.external_workloads.len(),
+                ),
+                ("Server", ns.clone(), servers.len()),
+                (
+                    "ServerAuthorization",
+                    ns.clone(),
+                    server_authorizations.len(),
+                ),
+                (
+                    "AuthorizationPolicy",
+                    ns.clone(),
+                    authorization_policies.len(),
+                ),
+                ("HTTPRoute", ns.clone(), http_routes.len()),
+                ("GRPCRoute", ns.clone(), grpc_routes.len()),
+            ]);
+
+            // The index holds a receiver for each port, so only additional
+            // receivers are counted as watches.
+            watches += index
+                .pods
+                .by_name
+                .values()
+                .chain(index.pods.external_workloads.values())
+                .flat_map(|pod| pod.port_servers.values())
+                .map(|server| server.tx.receiver_count().saturating_sub(1))
+                .sum::<usize>();
+        }
+        for (ns, authns) in self.authentications.by_ns.iter() {
+            counts.extend([
+                ("MeshTLSAuthentication", ns.clone(), authns.meshtls.len()),
+                ("NetworkAuthentication", ns.clone(), authns.network.len()),
+            ]);
+        }
+        for (ns, grants) in self.reference_grants.by_ns.iter() {
+            counts.push(("ReferenceGrant", ns.clone(), grants.len()));
+        }
+
+        self.metrics.set_resources(counts, watches);
+    }
+
+    /// Obtains a receiver that is updated when the cluster's networks change.
+    pub fn cluster_networks_rx(&self) -> watch::Receiver<Arc<[IpNet]>> {
+        self.cluster_networks.rx.clone()
+    }
+
     /// Obtains a pod:port's server receiver.
     ///
     /// An error is returned if the pod is not found. If the port is not found,
@@ -160,14 +320,80 @@ impl Index {
             .get_mut(pod)
             .ok_or_else(|| anyhow::anyhow!("pod {}.{} not found", pod, namespace))?;
         Ok(pod
-            .port_server_or_default(port, &self.cluster_info)
+            .port_server_or_default(port, &ns.policy.settings, &self.cluster_info)
+            .rx
+            .clone())
+    }
+
+    /// Obtains an external workload:port's server receiver.
+    ///
+    /// An error is returned if the workload is not found. If the port is not
+    /// found, a default is server is created.
+    pub fn external_workload_server_rx(
+        &mut self,
+        namespace: &str,
+        workload: &str,
+        port: NonZeroU16,
+    ) -> Result<watch::Receiver<InboundServer>> {
+        let ns = self
+            .namespaces
+            .by_ns
+            .get_mut(namespace)
+            .ok_or_else(|| anyhow::anyhow!("namespace not found: {}", namespace))?;
+        let workload = ns
+            .pods
+            .external_workloads
+            .get_mut(workload)
+            .ok_or_else(|| {
+                anyhow::anyhow!("external workload {}.{} not found", workload, namespace)
+            })?;
+        Ok(workload
+            .port_server_or_default(port, &ns.policy.settings, &self.cluster_info)
             .rx
             .clone())
     }
 
+    /// Returns a pod:port's current server without indexing the port.
+    ///
+    /// `None` is returned if the pod is not found. If the port is not indexed,
+    /// the default server is returned.
+    pub fn pod_server(
+        &self,
+        namespace: &str,
+        pod: &str,
+        port: NonZeroU16,
+    ) -> Option<InboundServer> {
+        let ns = self.namespaces.by_ns.get(namespace)?;
+        let pod = ns.pods.by_name.get(pod)?;
+        Some(pod.port_server(port, &ns.policy.settings, &self.cluster_info))
+    }
+
+    /// Returns an external workload:port's current server without indexing
+    /// the port.
+    ///
+    /// `None` is returned if the workload is not found. If the port is not
+    /// indexed, the default server is returned.
+    pub fn external_workload_server(
+        &self,
+        namespace: &str,
+        workload: &str,
+        port: NonZeroU16,
+    ) -> Option<InboundServer> {
+        let ns = self.namespaces.by_ns.get(namespace)?;
+        let workload = ns.pods.external_workloads.get(workload)?;
+        Some(workload.port_server(port, &ns.policy.settings, &self.cluster_info))
+    }
+
     fn ns_with_reindex(&mut self, namespace: String, f: impl FnOnce(&mut Namespace) -> bool) {
-        self.namespaces
-            .get_with_reindex(namespace, &self.authentications, f)
+        let parents = self.namespaces.foreign_parent_namespaces(&namespace);
+        if self.namespaces.get_with_reindex(
+            namespace.clone(),
+            &self.authentications,
+            &self.reference_grants,
+            f,
+        ) {
+            self.reindex_foreign_parents(&namespace, parents);
+        }
     }
 
     fn ns_or_default_with_reindex(
@@ -175,57 +401,123 @@ impl Index {
         namespace: String,
         f: impl FnOnce(&mut Namespace) -> bool,
     ) {
-        self.namespaces
-            .get_or_default_with_reindex(namespace, &self.authentications, f)
+        let parents = self.namespaces.foreign_parent_namespaces(&namespace);
+        if self.namespaces.get_or_default_with_reindex(
+            namespace.clone(),
+            &self.authentications,
+            &self.reference_grants,
+            f,
+        ) {
+            self.reindex_foreign_parents(&namespace, parents);
+        }
+    }
+
+    /// Reindexes the namespaces of `Server`s that are parents of routes in
+    /// `namespace`, including `prior` parent namespaces from before the
+    /// namespace was updated.
+    fn reindex_foreign_parents(&mut self, namespace: &str, mut prior: HashSet<String>) {
+        prior.extend(self.namespaces.foreign_parent_namespaces(namespace));
+        self.namespaces.index_foreign_routes(namespace, &prior);
+        for ns in prior.into_iter() {
+            tracing::debug!(%ns, "Reindexing namespace with routes from {}", namespace);
+            self.namespaces
+                .reindex(&ns, &self.authentications, &self.reference_grants);
+        }
     }
 
     fn reindex_all(&mut self) {
         tracing::debug!("Reindexing all namespaces");
+        let started = time::Instant::now();
+        let namespaces = self.namespaces.by_ns.keys().cloned().collect::<Vec<_>>();
+        for ns in namespaces.into_iter() {
+            self.namespaces
+                .reindex(&ns, &self.authentications, &self.reference_grants);
+        }
+        self.metrics.observe_reindex_all(started);
+    }
+
+    /// Updates the cluster's networks from the pod networks of all indexed
+    /// nodes. When the networks change, all namespaces are reindexed so that
+    /// default servers use the new networks.
+    fn update_cluster_networks(&mut self) {
+        let networks = self
+            .cluster_networks
+            .networks(&self.cluster_info.service_networks);
+        if networks == self.cluster_info.networks {
+            tracing::debug!("Cluster networks unchanged");
+            return;
+        }
+
+        tracing::info!(?networks, "Updating cluster networks");
+        let cluster_info = Arc::new(ClusterInfo {
+            networks: networks.clone(),
+            ..(*self.cluster_info).clone()
+        });
+        self.cluster_info = cluster_info.clone();
+        self.namespaces.cluster_info = cluster_info.clone();
         for ns in self.namespaces.by_ns.values_mut() {
-            ns.reindex(&self.authentications);
+            ns.policy.cluster_info = cluster_info.clone();
+        }
+
+        // Publish the new networks before reindexing so that updated servers
+        // are served with the new networks.
+        self.cluster_networks
+            .tx
+            .send(networks.into())
+            .expect("a receiver is held by the index");
+        self.reindex_all();
+    }
+
+    fn apply_ns_settings(&mut self, namespace: String, settings: pod::Settings) {
+        tracing::trace!(?settings);
+
+        // Namespaces without any settings need not be created in the index.
+        if settings == pod::Settings::default() {
+            self.ns_with_reindex(namespace, |ns| ns.policy.update_settings(settings))
+        } else {
+            self.ns_or_default_with_reindex(namespace, |ns| ns.policy.update_settings(settings))
         }
     }
 
-    fn apply_route<R>(&mut self, route: R)
+    fn apply_route<R, B>(&mut self, route: R)
     where
-        R: ResourceExt,
-        InboundRouteBinding: TryFrom<R>,
-        <InboundRouteBinding as TryFrom<R>>::Error: std::fmt::Display,
+        R: BindRoute<B>,
+        B: RouteBinding,
     {
-        let ns = route.namespace().expect("HttpRoute must have a namespace");
+        let ns = route.namespace().expect("route must have a namespace");
         let name = route.name_unchecked();
         let _span = info_span!("apply", %ns, %name).entered();
 
-        let route_binding = match route.try_into() {
+        let route_binding = match route.bind() {
             Ok(binding) => binding,
             Err(error) => {
-                tracing::info!(%ns, %name, %error, "Ignoring HTTPRoute");
+                tracing::info!(%ns, %name, %error, "Ignoring route");
+                self.metrics.rejected(R::KIND, "invalid");
                 return;
             }
         };
 
-        self.ns_or_default_with_reindex(ns, |ns| ns.policy.update_http_route(name, route_binding))
+        self.ns_or_default_with_reindex(ns, |ns| ns.policy.update_route(name, route_binding))
     }
 
-    fn reset_route<R>(&mut self, routes: Vec<R>, deleted: HashMap<String, HashSet<String>>)
+    fn reset_route<R, B>(&mut self, routes: Vec<R>, deleted: HashMap<String, HashSet<String>>)
     where
-        R: ResourceExt,
-        InboundRouteBinding: TryFrom<R>,
-        <InboundRouteBinding as TryFrom<R>>::Error: std::fmt::Display,
+        R: BindRoute<B>,
+        B: RouteBinding,
     {
         let _span = info_span!("reset").entered();
 
         // Aggregate all of the updates by namespace so that we only reindex
         // once per namespace.
-        type Ns = NsUpdate<InboundRouteBinding>;
-        let mut updates_by_ns = HashMap::<String, Ns>::default();
+        let mut updates_by_ns = HashMap::<String, NsUpdate<B>>::default();
         for route in routes.into_iter() {
-            let namespace = route.namespace().expect("HttpRoute must be namespaced");
+            let namespace = route.namespace().expect("route must be namespaced");
             let name = route.name_unchecked();
-            let route_binding = match route.try_into() {
+            let route_binding = match route.bind() {
                 Ok(binding) => binding,
                 Err(error) => {
-                    tracing::info!(ns = %namespace, %name, %error, "Ignoring HTTPRoute");
+                    tracing::info!(ns = %namespace, %name, %error, "Ignoring route");
+                    self.metrics.rejected(R::KIND, "invalid");
                     continue;
                 }
             };
@@ -239,14 +531,14 @@ impl Index {
             updates_by_ns.entry(ns).or_default().removed = names;
         }
 
-        for (namespace, Ns { added, removed }) in updates_by_ns.into_iter() {
+        for (namespace, NsUpdate { added, removed }) in updates_by_ns.into_iter() {
             if added.is_empty() {
                 // If there are no live resources in the namespace, we do not
                 // want to create a default namespace instance, we just want to
                 // clear out all resources for the namespace (and then drop the
                 // whole namespace, if necessary).
                 self.ns_with_reindex(namespace, |ns| {
-                    ns.policy.http_routes.clear();
+                    B::routes(&mut ns.policy).clear();
                     true
                 });
             } else {
@@ -256,10 +548,10 @@ impl Index {
                 self.ns_or_default_with_reindex(namespace, |ns| {
                     let mut changed = !removed.is_empty();
                     for name in removed.into_iter() {
-                        ns.policy.http_routes.remove(&name);
+                        B::routes(&mut ns.policy).remove(&name);
                     }
                     for (name, route_binding) in added.into_iter() {
-                        changed = ns.policy.update_http_route(name, route_binding) || changed;
+                        changed = ns.policy.update_route(name, route_binding) || changed;
                     }
                     changed
                 });
@@ -267,9 +559,77 @@ impl Index {
         }
     }
 
-    fn delete_route(&mut self, ns: String, name: String) {
+    fn delete_route<B: RouteBinding>(&mut self, ns: String, name: String) {
         let _span = info_span!("delete", %ns, %name).entered();
-        self.ns_with_reindex(ns, |ns| ns.policy.http_routes.remove(&name).is_some())
+        self.ns_with_reindex(ns, |ns| B::routes(&mut ns.policy).remove(&name).is_some())
+    }
+}
+
+impl kubert::index::IndexClusterResource<k8s::Namespace> for Index {
+    fn apply(&mut self, ns: k8s::Namespace) {
+        let name = ns.name_unchecked();
+        let _span = info_span!("apply", ns = %name).entered();
+
+        let settings = pod::Settings::from_metadata(&ns.metadata);
+        self.apply_ns_settings(name, settings)
+    }
+
+    fn delete(&mut self, name: String) {
+        let _span = info_span!("delete", ns = %name).entered();
+        self.apply_ns_settings(name, pod::Settings::default())
+    }
+
+    fn reset(&mut self, nss: Vec<k8s::Namespace>, deleted: HashSet<String>) {
+        let _span = info_span!("reset").entered();
+
+        for ns in nss.into_iter() {
+            let name = ns.name_unchecked();
+            let settings = pod::Settings::from_metadata(&ns.metadata);
+            self.apply_ns_settings(name, settings);
+        }
+
+        for name in deleted.into_iter() {
+            self.apply_ns_settings(name, pod::Settings::default());
+        }
+    }
+}
+
+impl kubert::index::IndexClusterResource<k8s::Node> for Index {
+    fn apply(&mut self, node: k8s::Node) {
+        let name = node.name_unchecked();
+        let _span = info_span!("apply", node = %name).entered();
+
+        let networks = node_pod_networks(&node);
+        if self.cluster_networks.by_node.get(&name) == Some(&networks) {
+            tracing::debug!("No changes");
+            return;
+        }
+        self.cluster_networks.by_node.insert(name, networks);
+        self.update_cluster_networks();
+    }
+
+    fn delete(&mut self, name: String) {
+        let _span = info_span!("delete", node = %name).entered();
+        if self.cluster_networks.by_node.remove(&name).is_some() {
+            self.update_cluster_networks();
+        }
+    }
+
+    fn reset(&mut self, nodes: Vec<k8s::Node>, deleted: HashSet<String>) {
+        let _span = info_span!("reset").entered();
+
+        for node in nodes.into_iter() {
+            let networks = node_pod_networks(&node);
+            self.cluster_networks
+                .by_node
+                .insert(node.name_unchecked(), networks);
+        }
+
+        for name in deleted.into_iter() {
+            self.cluster_networks.by_node.remove(&name);
+        }
+
+        self.update_cluster_networks();
     }
 }
 
@@ -285,12 +645,21 @@ impl kubert::index::IndexNamespacedResource<k8s::Pod> for Index {
         // Add or update the pod. If the pod was not already present in the
         // index with the same metadata, index it against the policy resources,
         // updating its watches.
+        let foreign = self
+            .namespaces
+            .foreign_http_routes(&namespace, &self.authentications);
         let ns = self.namespaces.get_or_default(namespace);
         match ns.pods.update(name, meta, port_names) {
             Ok(None) => {}
-            Ok(Some(pod)) => pod.reindex_servers(&ns.policy, &self.authentications),
+            Ok(Some(pod)) => pod.reindex_servers(
+                &ns.policy,
+                &self.authentications,
+                &self.reference_grants,
+                &foreign,
+            ),
             Err(error) => {
                 tracing::error!(%error, "Illegal pod update");
+                self.metrics.rejected("Pod", "ports_changed");
             }
         }
     }
@@ -311,6 +680,46 @@ impl kubert::index::IndexNamespacedResource<k8s::Pod> for Index {
     // handle resets specially.
 }
 
+impl kubert::index::IndexNamespacedResource<k8s::workload::ExternalWorkload> for Index {
+    fn apply(&mut self, workload: k8s::workload::ExternalWorkload) {
+        let namespace = workload.namespace().unwrap();
+        let name = workload.name_unchecked();
+        let _span = info_span!("apply", ns = %namespace, %name).entered();
+
+        let port_names = pod::external_workload_port_names(&workload.spec);
+        let meta = pod::Meta::from_metadata(workload.metadata);
+
+        // Unlike pods, an external workload's ports may change at runtime, so
+        // the workload is reindexed whenever its metadata or ports change.
+        let foreign = self
+            .namespaces
+            .foreign_http_routes(&namespace, &self.authentications);
+        let ns = self.namespaces.get_or_default(namespace);
+        if let Some(workload) = ns.pods.update_external_workload(name, meta, port_names) {
+            workload.reindex_servers(
+                &ns.policy,
+                &self.authentications,
+                &self.reference_grants,
+                &foreign,
+            );
+        }
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        tracing::debug!(%ns, %name, "delete");
+        if let Entry::Occupied(mut ns) = self.namespaces.by_ns.entry(ns) {
+            // Once the workload is removed, there's nothing else to update.
+            // Any open watches will complete.
+            if ns.get_mut().pods.external_workloads.remove(&name).is_some() && ns.get().is_empty() {
+                ns.remove();
+            }
+        }
+    }
+
+    // Since apply only reindexes a single workload at a time, there's no need
+    // to handle resets specially.
+}
+
 impl kubert::index::IndexNamespacedResource<k8s::policy::Server> for Index {
     fn apply(&mut self, srv: k8s::policy::Server) {
         let ns = srv.namespace().expect("server must be namespaced");
@@ -386,7 +795,10 @@ impl kubert::index::IndexNamespacedResource<k8s::policy::ServerAuthorization> fo
             Ok(meta) => self.ns_or_default_with_reindex(ns, move |ns| {
                 ns.policy.update_server_authz(name, meta)
             }),
-            Err(error) => tracing::error!(%error, "Illegal server authorization update"),
+            Err(error) => {
+                tracing::error!(%error, "Illegal server authorization update");
+                self.metrics.rejected("ServerAuthorization", "invalid");
+            }
         }
     }
 
@@ -420,7 +832,8 @@ impl kubert::index::IndexNamespacedResource<k8s::policy::ServerAuthorization> fo
                     .added
                     .push((name, saz)),
                 Err(error) => {
-                    tracing::error!(ns = %namespace, %name, %error, "Illegal server authorization update")
+                    tracing::error!(ns = %namespace, %name, %error, "Illegal server authorization update");
+                    self.metrics.rejected("ServerAuthorization", "invalid");
                 }
             }
         }
@@ -467,6 +880,7 @@ impl kubert::index::IndexNamespacedResource<k8s::policy::AuthorizationPolicy> fo
             Ok(spec) => spec,
             Err(error) => {
                 tracing::warn!(%error, "Invalid authorization policy");
+                self.metrics.rejected("AuthorizationPolicy", "invalid");
                 return;
             }
         };
@@ -504,7 +918,8 @@ impl kubert::index::IndexNamespacedResource<k8s::policy::AuthorizationPolicy> fo
                     .added
                     .push((name, spec)),
                 Err(error) => {
-                    tracing::error!(ns = %namespace, %name, %error, "Illegal server authorization update")
+                    tracing::error!(ns = %namespace, %name, %error, "Illegal server authorization update");
+                    self.metrics.rejected("AuthorizationPolicy", "invalid");
                 }
             }
         }
@@ -554,6 +969,7 @@ impl kubert::index::IndexNamespacedResource<k8s::policy::MeshTLSAuthentication>
             Ok(spec) => spec,
             Err(error) => {
                 tracing::warn!(%error, "Invalid MeshTLSAuthentication");
+                self.metrics.rejected("MeshTLSAuthentication", "invalid");
                 return;
             }
         };
@@ -599,6 +1015,7 @@ impl kubert::index::IndexNamespacedResource<k8s::policy::MeshTLSAuthentication>
                 Ok(spec) => spec,
                 Err(error) => {
                     tracing::warn!(ns = %namespace, %name, %error, "Invalid MeshTLSAuthentication");
+                    self.metrics.rejected("MeshTLSAuthentication", "invalid");
                     return;
                 }
             };
@@ -631,6 +1048,7 @@ impl kubert::index::IndexNamespacedResource<k8s::policy::NetworkAuthentication>
             Ok(spec) => spec,
             Err(error) => {
                 tracing::warn!(%error, "Invalid NetworkAuthentication");
+                self.metrics.rejected("NetworkAuthentication", "invalid");
                 return;
             }
         };
@@ -674,6 +1092,7 @@ impl kubert::index::IndexNamespacedResource<k8s::policy::NetworkAuthentication>
                 Ok(spec) => spec,
                 Err(error) => {
                     tracing::warn!(ns = %namespace, %name, %error, "Invalid NetworkAuthentication");
+                    self.metrics.rejected("NetworkAuthentication", "invalid");
                     return;
                 }
             };
@@ -698,11 +1117,11 @@ impl kubert::index::IndexNamespacedResource<k8s::policy::NetworkAuthentication>
 
 impl kubert::index::IndexNamespacedResource<k8s_gateway_api::HttpRoute> for Index {
     fn apply(&mut self, route: k8s_gateway_api::HttpRoute) {
-        self.apply_route(route)
+        self.apply_route::<_, InboundRouteBinding>(route)
     }
 
     fn delete(&mut self, ns: String, name: String) {
-        self.delete_route(ns, name)
+        self.delete_route::<InboundRouteBinding>(ns, name)
     }
 
     fn reset(
@@ -710,17 +1129,17 @@ impl kubert::index::IndexNamespacedResource<k8s_gateway_api::HttpRoute> for Inde
         routes: Vec<k8s_gateway_api::HttpRoute>,
         deleted: HashMap<String, HashSet<String>>,
     ) {
-        self.reset_route(routes, deleted)
+        self.reset_route::<_, InboundRouteBinding>(routes, deleted)
     }
 }
 
 impl kubert::index::IndexNamespacedResource<k8s::policy::HttpRoute> for Index {
     fn apply(&mut self, route: k8s::policy::HttpRoute) {
-        self.apply_route(route)
+        self.apply_route::<_, InboundRouteBinding>(route)
     }
 
     fn delete(&mut self, ns: String, name: String) {
-        self.delete_route(ns, name)
+        self.delete_route::<InboundRouteBinding>(ns, name)
     }
 
     fn reset(
@@ -728,7 +1147,75 @@ impl kubert::index::IndexNamespacedResource<k8s::policy::HttpRoute> for Index {
         routes: Vec<k8s::policy::HttpRoute>,
         deleted: HashMap<String, HashSet<String>>,
     ) {
-        self.reset_route(routes, deleted)
+        self.reset_route::<_, InboundRouteBinding>(routes, deleted)
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::gateway::GrpcRoute> for Index {
+    fn apply(&mut self, route: k8s::gateway::GrpcRoute) {
+        self.apply_route::<_, InboundGrpcRouteBinding>(route)
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        self.delete_route::<InboundGrpcRouteBinding>(ns, name)
+    }
+
+    fn reset(
+        &mut self,
+        routes: Vec<k8s::gateway::GrpcRoute>,
+        deleted: HashMap<String, HashSet<String>>,
+    ) {
+        self.reset_route::<_, InboundGrpcRouteBinding>(routes, deleted)
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::gateway::ReferenceGrant> for Index {
+    fn apply(&mut self, grant: k8s::gateway::ReferenceGrant) {
+        let ns = grant
+            .namespace()
+            .expect("ReferenceGrant must have a namespace");
+        let name = grant.name_unchecked();
+        let _span = info_span!("apply", %ns, %name).entered();
+
+        let spec = reference_grant::Spec::from_resource(grant);
+        if self.reference_grants.update(ns, name, spec) {
+            self.reindex_all();
+        }
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        let _span = info_span!("delete", %ns, %name).entered();
+
+        if self.reference_grants.delete(ns, &name) {
+            self.reindex_all();
+        }
+    }
+
+    fn reset(
+        &mut self,
+        grants: Vec<k8s::gateway::ReferenceGrant>,
+        deleted: HashMap<String, HashSet<String>>,
+    ) {
+        let _span = info_span!("reset").entered();
+
+        let mut changed = false;
+        for grant in grants.into_iter() {
+            let ns = grant
+                .namespace()
+                .expect("ReferenceGrant must have a namespace");
+            let name = grant.name_unchecked();
+            let spec = reference_grant::Spec::from_resource(grant);
+            changed = self.reference_grants.update(ns, name, spec) || changed;
+        }
+        for (ns, names) in deleted.into_iter() {
+            for name in names.into_iter() {
+                changed = self.reference_grants.delete(ns.clone(), &name) || changed;
+            }
+        }
+
+        if changed {
+            self.reindex_all();
+        }
     }
 }
 
@@ -744,38 +1231,152 @@ impl NamespaceIndex {
     /// Gets the given namespace and, if it exists, passes it to the given
     /// function. If the function returns true, all pods in the namespace are
     /// reindexed; or, if the function returns false and the namespace is empty,
-    /// it is removed from the index.
+    /// it is removed from the index. Returns true if the namespace changed.
     fn get_with_reindex(
         &mut self,
         namespace: String,
         authns: &AuthenticationNsIndex,
+        grants: &ReferenceGrantNsIndex,
         f: impl FnOnce(&mut Namespace) -> bool,
-    ) {
-        if let Entry::Occupied(mut ns) = self.by_ns.entry(namespace) {
-            if f(ns.get_mut()) {
-                if ns.get().is_empty() {
-                    ns.remove();
-                } else {
-                    ns.get_mut().reindex(authns);
-                }
-            }
+    ) -> bool {
+        let ns = match self.by_ns.get_mut(&namespace) {
+            Some(ns) => ns,
+            None => return false,
+        };
+        if !f(ns) {
+            return false;
+        }
+        if ns.is_empty() {
+            self.by_ns.remove(&namespace);
+        } else {
+            self.reindex(&namespace, authns, grants);
         }
+        true
     }
 
     /// Gets the given namespace (or creates it) and passes it to the given
     /// function. If the function returns true, all pods in the namespace are
-    /// reindexed.
+    /// reindexed. Returns true if the namespace changed.
     fn get_or_default_with_reindex(
         &mut self,
         namespace: String,
         authns: &AuthenticationNsIndex,
+        grants: &ReferenceGrantNsIndex,
         f: impl FnOnce(&mut Namespace) -> bool,
+    ) -> bool {
+        let ns = self.get_or_default(namespace.clone());
+        if !f(ns) {
+            return false;
+        }
+        self.reindex(&namespace, authns, grants);
+        true
+    }
+
+    /// Reindexes all pods in the given namespace, including routes from other
+    /// namespaces that have parents in this namespace.
+    fn reindex(
+        &mut self,
+        namespace: &str,
+        authns: &AuthenticationNsIndex,
+        grants: &ReferenceGrantNsIndex,
     ) {
-        let ns = self.get_or_default(namespace);
-        if f(ns) {
-            ns.reindex(authns);
+        let started = time::Instant::now();
+        let foreign = self.foreign_http_routes(namespace, authns);
+        if let Some(ns) = self.by_ns.get_mut(namespace) {
+            ns.reindex(authns, grants, &foreign);
+            self.metrics.observe_namespace_reindex(started);
         }
     }
+
+    /// Returns the namespaces of `Server`s in other namespaces that are
+    /// parents of the given namespace's routes.
+    fn foreign_parent_namespaces(&self, namespace: &str) -> HashSet<String> {
+        self.by_ns
+            .get(namespace)
+            .into_iter()
+            .flat_map(|ns| ns.policy.http_routes.values())
+            .flat_map(|binding| binding.foreign_parent_namespaces())
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// Updates the routes in `route_ns` that are indexed as having a parent
+    /// `Server` in each of the given `parent_namespaces`.
+    fn index_foreign_routes(&mut self, route_ns: &str, parent_namespaces: &HashSet<String>) {
+        for parent_ns in parent_namespaces.iter() {
+            let names = self
+                .by_ns
+                .get(route_ns)
+                .into_iter()
+                .flat_map(|ns| ns.policy.http_routes.iter())
+                .filter(|(_, binding)| {
+                    binding
+                        .foreign_parent_namespaces()
+                        .any(|ns| ns == parent_ns.as_str())
+                })
+                .map(|(name, _)| name.clone())
+                .collect::<HashSet<_>>();
+
+            if names.is_empty() {
+                if let Some(by_route_ns) = self.foreign_routes.get_mut(parent_ns) {
+                    by_route_ns.remove(route_ns);
+                    if by_route_ns.is_empty() {
+                        self.foreign_routes.remove(parent_ns);
+                    }
+                }
+            } else {
+                self.foreign_routes
+                    .entry(parent_ns.clone())
+                    .or_default()
+                    .insert(route_ns.to_string(), names);
+            }
+        }
+    }
+
+    /// Collects routes in other namespaces that have a parent `Server` in the
+    /// given namespace.
+    fn foreign_http_routes(
+        &self,
+        namespace: &str,
+        authns: &AuthenticationNsIndex,
+    ) -> Vec<ForeignHttpRoute> {
+        let by_route_ns = match self.foreign_routes.get(namespace) {
+            Some(by_route_ns) => by_route_ns,
+            None => return vec![],
+        };
+        by_route_ns
+            .iter()
+            .filter_map(|(route_ns, names)| Some((route_ns, names, self.by_ns.get(route_ns)?)))
+            .flat_map(|(route_ns, names, ns)| {
+                names.iter().filter_map(move |name| {
+                    let binding = ns.policy.http_routes.get(name)?;
+                    let target = authorization_policy::Target::HttpRoute(name.clone());
+                    Some(ForeignHttpRoute {
+                        namespace: route_ns.clone(),
+                        name: name.clone(),
+                        binding: binding.clone(),
+                        authorizations: ns.policy.route_client_authzs(&target, authns),
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+// === impl ClusterNetworks ===
+
+impl ClusterNetworks {
+    /// Returns the pod networks of all nodes merged with the service networks,
+    /// aggregated into the smallest equivalent list of networks. If no nodes
+    /// report pod networks, the configured networks are returned.
+    fn networks(&self, service_networks: &[IpNet]) -> Vec<IpNet> {
+        let mut networks = self.by_node.values().flatten().copied().collect::<Vec<_>>();
+        if networks.is_empty() {
+            return self.configured.clone();
+        }
+        networks.extend(service_networks.iter().copied());
+        IpNet::aggregate(&networks)
+    }
 }
 
 // === impl Namespace ===
@@ -786,14 +1387,17 @@ impl Namespace {
             pods: PodIndex {
                 namespace: namespace.clone(),
                 by_name: HashMap::default(),
+                external_workloads: HashMap::default(),
             },
             policy: PolicyIndex {
                 namespace,
                 cluster_info,
+                settings: pod::Settings::default(),
                 servers: HashMap::default(),
                 server_authorizations: HashMap::default(),
                 authorization_policies: HashMap::default(),
                 http_routes: HashMap::default(),
+                grpc_routes: HashMap::default(),
             },
         }
     }
@@ -805,8 +1409,13 @@ impl Namespace {
     }
 
     #[inline]
-    fn reindex(&mut self, authns: &AuthenticationNsIndex) {
-        self.pods.reindex(&self.policy, authns);
+    fn reindex(
+        &mut self,
+        authns: &AuthenticationNsIndex,
+        grants: &ReferenceGrantNsIndex,
+        foreign: &[ForeignHttpRoute],
+    ) {
+        self.pods.reindex(&self.policy, authns, grants, foreign);
     }
 }
 
@@ -815,7 +1424,7 @@ impl Namespace {
 impl PodIndex {
     #[inline]
     fn is_empty(&self) -> bool {
-        self.by_name.is_empty()
+        self.by_name.is_empty() && self.external_workloads.is_empty()
     }
 
     fn update(
@@ -854,11 +1463,48 @@ impl PodIndex {
         Ok(Some(pod))
     }
 
-    fn reindex(&mut self, policy: &PolicyIndex, authns: &AuthenticationNsIndex) {
+    fn update_external_workload(
+        &mut self,
+        name: String,
+        meta: pod::Meta,
+        port_names: HashMap<String, pod::PortSet>,
+    ) -> Option<&mut Pod> {
+        match self.external_workloads.entry(name.clone()) {
+            Entry::Vacant(entry) => Some(entry.insert(Pod {
+                meta,
+                port_names,
+                port_servers: pod::PortMap::default(),
+            })),
+
+            Entry::Occupied(entry) => {
+                let workload = entry.into_mut();
+                if workload.meta == meta && workload.port_names == port_names {
+                    tracing::debug!(workload = %name, "No changes");
+                    return None;
+                }
+                tracing::debug!(workload = %name, "Updating");
+                workload.meta = meta;
+                workload.port_names = port_names;
+                Some(workload)
+            }
+        }
+    }
+
+    fn reindex(
+        &mut self,
+        policy: &PolicyIndex,
+        authns: &AuthenticationNsIndex,
+        grants: &ReferenceGrantNsIndex,
+        foreign: &[ForeignHttpRoute],
+    ) {
         let _span = info_span!("reindex", ns = %self.namespace).entered();
         for (name, pod) in self.by_name.iter_mut() {
             let _span = info_span!("pod", pod = %name).entered();
-            pod.reindex_servers(policy, authns);
+            pod.reindex_servers(policy, authns, grants, foreign);
+        }
+        for (name, workload) in self.external_workloads.iter_mut() {
+            let _span = info_span!("external_workload", workload = %name).entered();
+            workload.reindex_servers(policy, authns, grants, foreign);
         }
     }
 }
@@ -867,7 +1513,13 @@ impl PodIndex {
 
 impl Pod {
     /// Determines the policies for ports on this pod.
-    fn reindex_servers(&mut self, policy: &PolicyIndex, authentications: &AuthenticationNsIndex) {
+    fn reindex_servers(
+        &mut self,
+        policy: &PolicyIndex,
+        authentications: &AuthenticationNsIndex,
+        grants: &ReferenceGrantNsIndex,
+        foreign: &[ForeignHttpRoute],
+    ) {
         // Keep track of the ports that are already known in the pod so that, after applying server
         // matches, we can ensure remaining ports are set to the default policy.
         let mut unmatched_ports = self.port_servers.keys().copied().collect::<pod::PortSet>();
@@ -882,11 +1534,13 @@ impl Pod {
             std::hash::BuildHasherDefault::<pod::PortHasher>::default(),
         );
 
-        for (srvname, server) in policy.servers.iter() {
+        // Servers are considered in order of precedence so that, when multiple servers select the
+        // same port, the same server is chosen regardless of the order in which they were indexed.
+        for (srvname, server) in policy.servers_by_precedence().into_iter() {
             if server.pod_selector.matches(&self.meta.labels) {
                 for port in self.select_ports(&server.port_ref).into_iter() {
-                    // If the port is already matched to a server, then log a warning and skip
-                    // updating it so it doesn't flap between servers.
+                    // If the port is already matched to a server with higher precedence, then
+                    // log a warning and skip updating it so it doesn't flap between servers.
                     if let Some(prior) = matched_ports.get(&port) {
                         tracing::warn!(
                             port = %port,
@@ -897,7 +1551,15 @@ impl Pod {
                         continue;
                     }
 
-                    let s = policy.inbound_server(srvname.clone(), server, authentications);
+                    let s = policy.inbound_server(
+                        srvname.clone(),
+                        server,
+                        port,
+                        &self.port_names,
+                        authentications,
+                        grants,
+                        foreign,
+                    );
                     self.update_server(port, srvname, s);
 
                     matched_ports.insert(port, srvname.clone());
@@ -908,7 +1570,7 @@ impl Pod {
 
         // Reset all remaining ports to the default policy.
         for port in unmatched_ports.into_iter() {
-            self.set_default_server(port, &policy.cluster_info);
+            self.set_default_server(port, &policy.settings, &policy.cluster_info);
         }
     }
 
@@ -954,8 +1616,13 @@ impl Pod {
     }
 
     /// Updates a pod-port to use the given named server.
-    fn set_default_server(&mut self, port: NonZeroU16, config: &ClusterInfo) {
-        let server = Self::default_inbound_server(port, &self.meta.settings, config);
+    fn set_default_server(
+        &mut self,
+        port: NonZeroU16,
+        ns_settings: &pod::Settings,
+        config: &ClusterInfo,
+    ) {
+        let server = Self::default_inbound_server(port, &self.meta.settings, ns_settings, config);
         match self.port_servers.entry(port) {
             Entry::Vacant(entry) => {
                 tracing::debug!(%port, server = %config.default_policy, "Creating default server");
@@ -983,21 +1650,13 @@ impl Pod {
     ///
     /// A named port may refer to an arbitrary number of port numbers.
     fn select_ports(&mut self, port_ref: &Port) -> Vec<NonZeroU16> {
-        match port_ref {
-            Port::Number(p) => Some(*p).into_iter().collect(),
-            Port::Name(name) => self
-                .port_names
-                .get(name)
-                .into_iter()
-                .flatten()
-                .cloned()
-                .collect(),
-        }
+        server::select_ports(port_ref, &self.port_names)
     }
 
     fn port_server_or_default(
         &mut self,
         port: NonZeroU16,
+        ns_settings: &pod::Settings,
         config: &ClusterInfo,
     ) -> &mut PodPortServer {
         match self.port_servers.entry(port) {
@@ -1006,6 +1665,7 @@ impl Pod {
                 let (tx, rx) = watch::channel(Self::default_inbound_server(
                     port,
                     &self.meta.settings,
+                    ns_settings,
                     config,
                 ));
                 entry.insert(PodPortServer { name: None, tx, rx })
@@ -1013,12 +1673,31 @@ impl Pod {
         }
     }
 
+    /// Returns the server for a pod-port, or the default server if the port is
+    /// not indexed.
+    fn port_server(
+        &self,
+        port: NonZeroU16,
+        ns_settings: &pod::Settings,
+        config: &ClusterInfo,
+    ) -> InboundServer {
+        match self.port_servers.get(&port) {
+            Some(ps) => ps.rx.borrow().clone(),
+            None => Self::default_inbound_server(port, &self.meta.settings, ns_settings, config),
+        }
+    }
+
+    /// Builds the default server for a pod-port.
+    ///
+    /// Pod annotations take precedence over namespace annotations, which take
+    /// precedence over the cluster-wide defaults.
     fn default_inbound_server(
         port: NonZeroU16,
         settings: &pod::Settings,
+        ns_settings: &pod::Settings,
         config: &ClusterInfo,
     ) -> InboundServer {
-        let protocol = if settings.opaque_ports.contains(&port) {
+        let protocol = if settings.is_opaque_or(ns_settings, port) {
             ProxyProtocol::Opaque
         } else {
             ProxyProtocol::Detect {
@@ -1026,8 +1705,10 @@ impl Pod {
             }
         };
 
-        let mut policy = settings.default_policy.unwrap_or(config.default_policy);
-        if settings.require_id_ports.contains(&port) {
+        let mut policy = settings
+            .default_policy_or(ns_settings)
+            .unwrap_or(config.default_policy);
+        if settings.requires_id_or(ns_settings, port) {
             if let DefaultPolicy::Allow {
                 ref mut authenticated_only,
                 ..
@@ -1059,17 +1740,25 @@ impl Pod {
             authorizations.insert(
                 AuthorizationRef::Default(policy.to_string()),
                 ClientAuthorization {
+                    action: AuthorizationAction::Allow,
                     authentication,
                     networks,
                 },
             );
         };
+        if policy == DefaultPolicy::Audit {
+            authorizations.insert(
+                AuthorizationRef::Default(policy.to_string()),
+                audit_authorization(),
+            );
+        }
 
         InboundServer {
             reference: ServerRef::Default(policy.to_string()),
             protocol,
             authorizations,
             http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
         }
     }
 }
@@ -1079,7 +1768,31 @@ impl Pod {
 impl PolicyIndex {
     #[inline]
     fn is_empty(&self) -> bool {
-        self.servers.is_empty() && self.server_authorizations.is_empty()
+        self.servers.is_empty()
+            && self.server_authorizations.is_empty()
+            && self.settings == pod::Settings::default()
+    }
+
+    fn update_settings(&mut self, settings: pod::Settings) -> bool {
+        if self.settings == settings {
+            tracing::debug!("No changes");
+            return false;
+        }
+        tracing::debug!("Updating namespace settings");
+        self.settings = settings;
+        true
+    }
+
+    /// Returns the namespace's servers, ordered by precedence.
+    fn servers_by_precedence(&self) -> Vec<(&String, &server::Server)> {
+        let mut servers = self.servers.iter().collect::<Vec<_>>();
+        servers.sort_by(|(a_name, a), (b_name, b)| {
+            server::cmp_precedence(
+                (a_name, a.created_at.as_ref()),
+                (b_name, b.created_at.as_ref()),
+            )
+        });
+        servers
     }
 
     fn update_server(&mut self, name: String, server: server::Server) -> bool {
@@ -1140,17 +1853,51 @@ impl PolicyIndex {
         &self,
         name: String,
         server: &server::Server,
+        port: NonZeroU16,
+        port_names: &HashMap<String, pod::PortSet>,
         authentications: &AuthenticationNsIndex,
+        grants: &ReferenceGrantNsIndex,
+        foreign: &[ForeignHttpRoute],
     ) -> InboundServer {
-        tracing::trace!(%name, ?server, "Creating inbound server");
+        tracing::trace!(%name, %port, ?server, "Creating inbound server");
         let authorizations = self.client_authzs(&name, server, authentications);
-        let routes = self.http_routes(&name, authentications);
+        let mut http_routes =
+            self.http_routes(&name, port, port_names, authentications, grants, foreign);
+        let mut grpc_routes = self.grpc_routes(&name, port, port_names, authentications);
+
+        // Proxies only apply a route's authorizations to its requests, so deny
+        // policies that target the server must also be applied to its routes.
+        let denies = authorizations
+            .iter()
+            .filter(|(_, authz)| {
+                matches!(
+                    authz.action,
+                    AuthorizationAction::Deny | AuthorizationAction::AuditDeny
+                )
+            })
+            .collect::<Vec<_>>();
+        let route_authzs = http_routes
+            .values_mut()
+            .map(|route| &mut route.authorizations)
+            .chain(
+                grpc_routes
+                    .values_mut()
+                    .map(|route| &mut route.authorizations),
+            );
+        for authzs in route_authzs {
+            authzs.extend(
+                denies
+                    .iter()
+                    .map(|(reference, authz)| ((*reference).clone(), (*authz).clone())),
+            );
+        }
 
         InboundServer {
             reference: ServerRef::Server(name),
             authorizations,
             protocol: server.protocol.clone(),
-            http_routes: routes,
+            http_routes,
+            grpc_routes,
         }
     }
 
@@ -1161,6 +1908,7 @@ impl PolicyIndex {
         authentications: &AuthenticationNsIndex,
     ) -> HashMap<AuthorizationRef, ClientAuthorization> {
         let mut authzs = HashMap::default();
+        let mut audited = Vec::new();
         for (name, saz) in self.server_authorizations.iter() {
             if saz.server_selector.selects(server_name, &server.labels) {
                 authzs.insert(
@@ -1185,11 +1933,23 @@ impl PolicyIndex {
                         continue;
                     }
                 }
+                authorization_policy::Target::ServerSelector(selector) => {
+                    if !selector.matches(&server.labels) {
+                        tracing::trace!(
+                            ns = %self.namespace,
+                            authorizationpolicy = %name,
+                            server = %server_name,
+                            "AuthorizationPolicy does not select server",
+                        );
+                        continue;
+                    }
+                }
                 authorization_policy::Target::Namespace => {}
-                authorization_policy::Target::HttpRoute(_) => {
-                    // Policies which target HttpRoutes will be attached to
-                    // the route authorizations and should not be included in
-                    // the server authorizations.
+                authorization_policy::Target::HttpRoute(_)
+                | authorization_policy::Target::GrpcRoute(_) => {
+                    // Policies which target routes will be attached to the
+                    // route authorizations and should not be included in the
+                    // server authorizations.
                     continue;
                 }
             }
@@ -1215,41 +1975,44 @@ impl PolicyIndex {
                 }
             };
 
+            if spec.audit && spec.action == AuthorizationAction::Allow {
+                audited.push(name.clone());
+            }
             let reference = AuthorizationRef::AuthorizationPolicy(name.to_string());
             authzs.insert(reference, authz);
         }
 
+        audit_policies(&mut authzs, audited);
+
         authzs
     }
 
     fn route_client_authzs(
         &self,
-        route_name: &str,
+        route: &authorization_policy::Target,
         authentications: &AuthenticationNsIndex,
     ) -> HashMap<AuthorizationRef, ClientAuthorization> {
         let mut authzs = HashMap::default();
+        let mut audited = Vec::new();
 
         for (name, spec) in &self.authorization_policies {
             // Skip the policy if it doesn't apply to the route.
-            match &spec.target {
-                authorization_policy::Target::HttpRoute(n) if n == route_name => {}
-                _ => {
-                    tracing::trace!(
-                        ns = %self.namespace,
-                        authorizationpolicy = %name,
-                        route = %route_name,
-                        target = ?spec.target,
-                        "AuthorizationPolicy does not target HttpRoute",
-                    );
-                    continue;
-                }
+            if spec.target != *route {
+                tracing::trace!(
+                    ns = %self.namespace,
+                    authorizationpolicy = %name,
+                    ?route,
+                    target = ?spec.target,
+                    "AuthorizationPolicy does not target route",
+                );
+                continue;
             }
 
             tracing::trace!(
                 ns = %self.namespace,
                 authorizationpolicy = %name,
-                route = %route_name,
-                "AuthorizationPolicy targets HttpRoute",
+                ?route,
+                "AuthorizationPolicy targets route",
             );
             tracing::trace!(authns = ?spec.authentications);
 
@@ -1257,7 +2020,7 @@ impl PolicyIndex {
                 Ok(authz) => authz,
                 Err(error) => {
                     tracing::info!(
-                        route = %route_name,
+                        ?route,
                         authorizationpolicy = %name,
                         %error,
                         "Illegal AuthorizationPolicy; ignoring",
@@ -1266,24 +2029,135 @@ impl PolicyIndex {
                 }
             };
 
+            if spec.audit && spec.action == AuthorizationAction::Allow {
+                audited.push(name.clone());
+            }
             let reference = AuthorizationRef::AuthorizationPolicy(name.to_string());
             authzs.insert(reference, authz);
         }
 
+        audit_policies(&mut authzs, audited);
+
         authzs
     }
 
     fn http_routes(
         &self,
         server_name: &str,
+        port: NonZeroU16,
+        port_names: &HashMap<String, pod::PortSet>,
         authentications: &AuthenticationNsIndex,
+        grants: &ReferenceGrantNsIndex,
+        foreign: &[ForeignHttpRoute],
     ) -> HashMap<String, InboundHttpRoute> {
-        self.http_routes
+        let local = self
+            .http_routes
             .iter()
-            .filter(|(_, route)| route.selects_server(server_name))
-            .map(|(name, route)| {
-                let mut route = route.route.clone();
-                route.authorizations = self.route_client_authzs(name, authentications);
+            .filter(|(_, binding)| binding.selects_server_port(None, server_name, port, port_names))
+            .map(|(name, binding)| {
+                let target = authorization_policy::Target::HttpRoute(name.clone());
+                let authzs = self.route_client_authzs(&target, authentications);
+                (name.clone(), binding, authzs)
+            });
+
+        // Routes in other namespaces are keyed by `<namespace>/<name>` so that
+        // they cannot collide with routes in the server's namespace.
+        let foreign = foreign
+            .iter()
+            .filter(|route| {
+                route.binding.selects_server_port(
+                    Some(&self.namespace),
+                    server_name,
+                    port,
+                    port_names,
+                ) && self.permits_foreign_parent(route, server_name, grants)
+            })
+            .map(|route| {
+                (
+                    format!("{}/{}", route.namespace, route.name),
+                    &route.binding,
+                    route.authorizations.clone(),
+                )
+            });
+
+        let mut bindings = local.chain(foreign).collect::<Vec<_>>();
+
+        // Rank routes by age and then by key. Matches with equal precedence
+        // are ordered by their route's rank.
+        bindings.sort_by_cached_key(|(key, binding, _)| {
+            (binding.creation_timestamp.clone(), key.clone())
+        });
+
+        bindings
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (key, binding, authorizations))| {
+                let mut route = binding.route.clone();
+                route.rank = rank;
+                route.authorizations = authorizations;
+                (key, route)
+            })
+            .collect()
+    }
+
+    /// Indicates whether a `ReferenceGrant` in this namespace permits a route
+    /// in another namespace to attach to the named `Server`.
+    fn permits_foreign_parent(
+        &self,
+        route: &ForeignHttpRoute,
+        server_name: &str,
+        grants: &ReferenceGrantNsIndex,
+    ) -> bool {
+        let from = reference_grant::Reference {
+            group: route.binding.group,
+            kind: "HTTPRoute",
+            namespace: &route.namespace,
+            name: &route.name,
+        };
+        let to = reference_grant::Reference {
+            group: "policy.linkerd.io",
+            kind: "Server",
+            namespace: &self.namespace,
+            name: server_name,
+        };
+        let permitted = grants.permits(from, to);
+        if !permitted {
+            tracing::debug!(
+                route.ns = %route.namespace,
+                route.name = %route.name,
+                server = %server_name,
+                "Ignoring cross-namespace parent not permitted by a ReferenceGrant"
+            );
+        }
+        permitted
+    }
+
+    fn grpc_routes(
+        &self,
+        server_name: &str,
+        port: NonZeroU16,
+        port_names: &HashMap<String, pod::PortSet>,
+        authentications: &AuthenticationNsIndex,
+    ) -> HashMap<String, InboundGrpcRoute> {
+        let mut bindings = self
+            .grpc_routes
+            .iter()
+            .filter(|(_, binding)| binding.selects_server_port(server_name, port, port_names))
+            .collect::<Vec<_>>();
+
+        // Rank routes by age and then by name, as with HTTP routes.
+        bindings.sort_by_cached_key(|(name, binding)| {
+            (binding.creation_timestamp.clone(), (*name).clone())
+        });
+
+        bindings
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (name, binding))| {
+                let mut route = binding.route.clone();
+                route.rank = rank;
+                let target = authorization_policy::Target::GrpcRoute(name.clone());
+                route.authorizations = self.route_client_authzs(&target, authentications);
                 (name.clone(), route)
             })
             .collect()
@@ -1337,7 +2211,7 @@ impl PolicyIndex {
                     let id = self.cluster_info.service_account_identity(namespace, name);
                     identities = Some(vec![IdentityMatch::Exact(id)])
                 }
-                _network => {}
+                AuthenticationTarget::Network { .. } => {}
             }
         }
 
@@ -1372,6 +2246,13 @@ impl PolicyIndex {
         }
 
         Ok(ClientAuthorization {
+            // Deny policies in audit mode flag the clients they would
+            // otherwise refuse.
+            action: match spec.action {
+                AuthorizationAction::Deny if spec.audit => AuthorizationAction::AuditDeny,
+                action => action,
+            },
+
             // If MTLS identities are configured, use them. Otherwise, do not require
             // authentication.
             authentication: identities
@@ -1394,8 +2275,8 @@ impl PolicyIndex {
         })
     }
 
-    fn update_http_route(&mut self, name: String, route: InboundRouteBinding) -> bool {
-        match self.http_routes.entry(name) {
+    fn update_route<B: RouteBinding>(&mut self, name: String, route: B) -> bool {
+        match B::routes(self).entry(name) {
             Entry::Vacant(entry) => {
                 entry.insert(route);
             }
@@ -1410,6 +2291,46 @@ impl PolicyIndex {
     }
 }
 
+// === impl RouteBinding ===
+
+impl RouteBinding for InboundRouteBinding {
+    fn routes(policy: &mut PolicyIndex) -> &mut HashMap<String, Self> {
+        &mut policy.http_routes
+    }
+}
+
+impl RouteBinding for InboundGrpcRouteBinding {
+    fn routes(policy: &mut PolicyIndex) -> &mut HashMap<String, Self> {
+        &mut policy.grpc_routes
+    }
+}
+
+// === impl BindRoute ===
+
+impl BindRoute<InboundRouteBinding> for k8s_gateway_api::HttpRoute {
+    const KIND: &'static str = "HTTPRoute";
+
+    fn bind(self) -> Result<InboundRouteBinding> {
+        InboundRouteBinding::try_from_gateway(self)
+    }
+}
+
+impl BindRoute<InboundRouteBinding> for k8s::policy::HttpRoute {
+    const KIND: &'static str = "HTTPRoute";
+
+    fn bind(self) -> Result<InboundRouteBinding> {
+        InboundRouteBinding::try_from_policy(self)
+    }
+}
+
+impl BindRoute<InboundGrpcRouteBinding> for k8s::gateway::GrpcRoute {
+    const KIND: &'static str = "GRPCRoute";
+
+    fn bind(self) -> Result<InboundGrpcRouteBinding> {
+        InboundGrpcRouteBinding::try_from(self)
+    }
+}
+
 // === impl AuthenticationNsIndex ===
 
 impl AuthenticationNsIndex {
@@ -1456,6 +2377,58 @@ impl AuthenticationNsIndex {
     }
 }
 
+// === impl ReferenceGrantNsIndex ===
+
+impl ReferenceGrantNsIndex {
+    pub(crate) fn update(
+        &mut self,
+        namespace: String,
+        name: String,
+        spec: reference_grant::Spec,
+    ) -> bool {
+        match self.by_ns.entry(namespace).or_default().entry(name) {
+            Entry::Vacant(entry) => {
+                entry.insert(spec);
+            }
+            Entry::Occupied(mut entry) => {
+                if *entry.get() == spec {
+                    return false;
+                }
+                entry.insert(spec);
+            }
+        }
+
+        true
+    }
+
+    pub(crate) fn delete(&mut self, namespace: String, name: &str) -> bool {
+        if let Entry::Occupied(mut ns) = self.by_ns.entry(namespace) {
+            let removed = ns.get_mut().remove(name).is_some();
+            if ns.get().is_empty() {
+                ns.remove();
+            }
+            return removed;
+        }
+        false
+    }
+
+    /// Indicates whether `from` may reference `to`. References within a
+    /// namespace are always permitted; otherwise a grant in `to`'s namespace
+    /// must permit the reference.
+    pub(crate) fn permits(
+        &self,
+        from: reference_grant::Reference<'_>,
+        to: reference_grant::Reference<'_>,
+    ) -> bool {
+        if from.namespace == to.namespace {
+            return true;
+        }
+        self.by_ns
+            .get(to.namespace)
+            .map_or(false, |grants| grants.values().any(|g| g.permits(from, to)))
+    }
+}
+
 // === impl AuthenticationIndex ===
 
 impl AuthenticationIndex {
@@ -1475,3 +2448,60 @@ impl<T> Default for NsUpdate<T> {
         }
     }
 }
+
+/// Permits all clients on all networks, flagging them as unauthorized.
+fn audit_authorization() -> ClientAuthorization {
+    ClientAuthorization {
+        action: AuthorizationAction::Audit,
+        authentication: ClientAuthentication::Unauthenticated,
+        networks: vec![
+            "0.0.0.0/0".parse::<IpNet>().unwrap().into(),
+            "::/0".parse::<IpNet>().unwrap().into(),
+        ],
+    }
+}
+
+/// Flags the clients that allow policies in audit mode would refuse, on
+/// behalf of each audited policy.
+///
+/// Audited policies only decide which clients are refused when no enforced
+/// authorization permits clients. Otherwise, the enforced authorizations
+/// continue to refuse all other clients.
+fn audit_policies(
+    authzs: &mut HashMap<AuthorizationRef, ClientAuthorization>,
+    audited: Vec<String>,
+) {
+    let enforced = authzs.iter().any(|(reference, authz)| {
+        authz.action == AuthorizationAction::Allow
+            && !matches!(reference, AuthorizationRef::AuthorizationPolicy(name) if audited.contains(name))
+    });
+    if enforced {
+        return;
+    }
+    for name in audited.into_iter() {
+        authzs.insert(AuthorizationRef::AuditedPolicy(name), audit_authorization());
+    }
+}
+
+/// Reads a node's pod networks from its `spec.podCIDRs`, falling back to the
+/// legacy `spec.podCIDR` field.
+fn node_pod_networks(node: &k8s::Node) -> Vec<IpNet> {
+    let spec = match node.spec.as_ref() {
+        Some(spec) => spec,
+        None => return vec![],
+    };
+    let cidrs = match spec.pod_cidrs.as_ref() {
+        Some(cidrs) if !cidrs.is_empty() => cidrs.iter().collect::<Vec<_>>(),
+        _ => spec.pod_cidr.iter().collect(),
+    };
+    cidrs
+        .into_iter()
+        .filter_map(|cidr| match cidr.parse::<IpNet>() {
+            Ok(net) => Some(net),
+            Err(error) => {
+                tracing::warn!(%cidr, %error, "Ignoring invalid pod CIDR");
+                None
+            }
+        })
+        .collect()
+}
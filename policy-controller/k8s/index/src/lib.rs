@@ -1,11 +1,15 @@
 //! The policy controller serves discovery requests from inbound proxies, indicating how the proxy
 //! should admit connections into a Pod. It watches the following cluster resources:
 //!
-//! - A `Namespace` may be annotated with a default-allow policy that applies to all pods in the
-//!   namespace (unless they are annotated with a default policy).
+//! - A `Namespace` may be annotated with a default-allow policy, opaque ports, and ports that
+//!   require identity. These settings apply to all pods in the namespace unless the pods are
+//!   annotated with their own settings.
 //! - Each `Pod` enumerate its ports. We maintain an index of each pod's ports, linked to `Server`
 //!   objects.
-//! - Each `Server` selects over pods in the same namespace.
+//! - Each `ExternalWorkload` describes a meshed workload that runs outside of Kubernetes (e.g. a
+//!   VM). External workloads are indexed like pods: they enumerate their ports and are selected by
+//!   `Server` pod selectors.
+//! - Each `Server` selects over pods (and external workloads) in the same namespace.
 //! - Each `ServerAuthorization` selects over `Server` instances in the same namespace.  When a
 //!   `ServerAuthorization` is updated, we find all of the `Server` instances it selects and update
 //!   their authorizations and publishes these updates on the server's broadcast channel.
@@ -19,25 +23,33 @@
 //! The Pod, Server, and ServerAuthorization indices are all scoped within a namespace index, as
 //! these resources cannot reference resources in other namespaces. This scoping helps to narrow the
 //! search space when processing updates and linking resources.
+//!
+//! The `outbound` index separately tracks `Service`s and the HTTPRoutes that reference them as
+//! parents, serving discovery requests from outbound proxies for a Service's authority and port.
 
 #![deny(warnings, rust_2018_idioms)]
 #![forbid(unsafe_code)]
 
 pub mod authorization_policy;
 mod defaults;
+mod grpc_route;
 mod http_route;
 mod index;
 mod meshtls_authentication;
+mod metrics;
 mod network_authentication;
+pub mod outbound;
 mod pod;
+mod reference_grant;
 mod server;
 mod server_authorization;
+pub mod status;
 
 #[cfg(test)]
 mod tests;
 
 use linkerd_policy_controller_core::IpNet;
-use std::time;
+use std::{num::NonZeroU16, time};
 
 pub use self::{
     defaults::DefaultPolicy,
@@ -49,15 +61,26 @@ pub use self::{
 pub struct ClusterInfo {
     /// Networks including PodIPs in this cluster.
     ///
-    /// Unfortunately, there's no way to discover this at runtime.
+    /// When `Node` resources are indexed, these networks are replaced by the
+    /// nodes' pod networks (and the `service_networks`) once any node reports
+    /// a pod CIDR.
     pub networks: Vec<IpNet>,
 
+    /// Networks including Service cluster IPs in this cluster.
+    ///
+    /// These are only used when cluster networks are discovered from `Node`
+    /// resources, since nodes do not report the service CIDR.
+    pub service_networks: Vec<IpNet>,
+
     /// The namespace where the linkerd control plane is deployed
     pub control_plane_ns: String,
 
     /// The cluster's mesh identity trust domain.
     pub identity_domain: String,
 
+    /// The cluster's DNS domain, used to build Service authorities.
+    pub dns_domain: String,
+
     /// The cluster-wide default policy.
     pub default_policy: DefaultPolicy,
 
@@ -66,6 +89,10 @@ pub struct ClusterInfo {
 }
 
 impl ClusterInfo {
+    fn service_authority(&self, ns: &str, svc: &str, port: NonZeroU16) -> String {
+        format!("{}.{}.svc.{}:{}", svc, ns, self.dns_domain, port)
+    }
+
     fn service_account_identity(&self, ns: &str, sa: &str) -> String {
         format!(
             "{}.{}.serviceaccount.identity.{}.{}",
@@ -0,0 +1,140 @@
+//! Prometheus metrics that describe the index's state and the cost of
+//! reindexing.
+//!
+//! Resource and watch gauges are computed from the index when metrics are
+//! scraped (see `Index::refresh_metrics`), while counters and histograms are
+//! updated as the index processes updates.
+
+use ahash::AHashSet as HashSet;
+use parking_lot::Mutex;
+use prometheus_client::{
+    encoding::text::Encode,
+    metrics::{
+        counter::Counter,
+        family::Family,
+        gauge::Gauge,
+        histogram::{exponential_buckets, Histogram},
+    },
+    registry::Registry,
+};
+use std::{sync::Arc, time};
+
+#[derive(Clone)]
+pub(crate) struct IndexMetrics {
+    resources: Family<ResourceLabels, Gauge>,
+    pod_port_watches: Gauge,
+    rejected: Family<RejectedLabels, Counter>,
+    namespace_reindex_duration: Histogram,
+    reindex_all_duration: Histogram,
+
+    /// The resource label sets that were last reported, so that gauges for
+    /// namespaces (or kinds) that are no longer indexed may be reset.
+    reported: Arc<Mutex<HashSet<ResourceLabels>>>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Encode)]
+pub(crate) struct ResourceLabels {
+    kind: &'static str,
+    namespace: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Encode)]
+struct RejectedLabels {
+    kind: &'static str,
+    reason: &'static str,
+}
+
+// === impl IndexMetrics ===
+
+impl Default for IndexMetrics {
+    fn default() -> Self {
+        Self {
+            resources: Family::default(),
+            pod_port_watches: Gauge::default(),
+            rejected: Family::default(),
+            // 100us to ~1.6s.
+            namespace_reindex_duration: Histogram::new(exponential_buckets(0.0001, 4.0, 8)),
+            // 1ms to ~16s.
+            reindex_all_duration: Histogram::new(exponential_buckets(0.001, 4.0, 8)),
+            reported: Default::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for IndexMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexMetrics").finish_non_exhaustive()
+    }
+}
+
+impl IndexMetrics {
+    pub(crate) fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "resources",
+            "The number of resources in the index, by kind and namespace",
+            Box::new(self.resources.clone()),
+        );
+        registry.register(
+            "pod_port_watches",
+            "The number of active watches on pod and external workload ports",
+            Box::new(self.pod_port_watches.clone()),
+        );
+        registry.register(
+            "rejected_resources",
+            "The number of resource updates that were illegal or ignored, by kind and reason",
+            Box::new(self.rejected.clone()),
+        );
+        registry.register(
+            "namespace_reindex_duration_seconds",
+            "The time taken to reindex all workloads in a namespace",
+            Box::new(self.namespace_reindex_duration.clone()),
+        );
+        registry.register(
+            "reindex_all_duration_seconds",
+            "The time taken to reindex all namespaces",
+            Box::new(self.reindex_all_duration.clone()),
+        );
+    }
+
+    /// Records a resource update that was not indexed.
+    pub(crate) fn rejected(&self, kind: &'static str, reason: &'static str) {
+        self.rejected
+            .get_or_create(&RejectedLabels { kind, reason })
+            .inc();
+    }
+
+    pub(crate) fn observe_namespace_reindex(&self, started: time::Instant) {
+        self.namespace_reindex_duration
+            .observe(started.elapsed().as_secs_f64());
+    }
+
+    pub(crate) fn observe_reindex_all(&self, started: time::Instant) {
+        self.reindex_all_duration
+            .observe(started.elapsed().as_secs_f64());
+    }
+
+    /// Sets the resource gauges to the given counts. Label sets that were
+    /// previously reported but are not included in `counts` are reset to zero.
+    pub(crate) fn set_resources(
+        &self,
+        counts: impl IntoIterator<Item = (&'static str, String, usize)>,
+        pod_port_watches: usize,
+    ) {
+        let mut reported = self.reported.lock();
+        let mut stale = std::mem::take(&mut *reported);
+        for (kind, namespace, count) in counts {
+            if count == 0 {
+                continue;
+            }
+            let labels = ResourceLabels { kind, namespace };
+            self.resources.get_or_create(&labels).set(count as _);
+            stale.remove(&labels);
+            reported.insert(labels);
+        }
+        for labels in stale {
+            self.resources.get_or_create(&labels).set(0);
+        }
+
+        self.pod_port_watches.set(pod_port_watches as _);
+    }
+}
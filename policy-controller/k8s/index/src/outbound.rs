@@ -0,0 +1,653 @@
+//! Keeps track of `Service` resources and the HTTPRoutes that reference them as
+//! parents to provide a dynamic outbound policy for Service ports.
+//!
+//! The `Index` type exposes a single public method: `Index::service_policy_rx`,
+//! which is used to lookup Service ports (i.e. by the gRPC API). Otherwise, it
+//! implements `kubert::index::IndexNamespacedResource` for the indexed
+//! kubernetes resources.
+//!
+//! Only routes in the Service's own namespace are considered, and backends must
+//! be Services in the route's namespace.
+
+use crate::{
+    http_route::{convert, InboundRouteBinding},
+    ClusterInfo,
+};
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
+use anyhow::{anyhow, bail, Result};
+use k8s_gateway_api as api;
+use linkerd_policy_controller_core::{
+    http_route::{HostMatch, HttpRouteMatch},
+    outbound::{
+        Backend, Filter, OutboundHttpRoute, OutboundHttpRouteRule, OutboundPolicy, WeightedService,
+    },
+};
+use linkerd_policy_controller_k8s_api::{
+    self as k8s, policy::httproute as policy, ResourceExt, Time,
+};
+use parking_lot::RwLock;
+use std::{num::NonZeroU16, sync::Arc, time};
+use tokio::sync::watch;
+
+pub type SharedIndex = Arc<RwLock<Index>>;
+
+/// Holds all outbound indexing state.
+#[derive(Debug)]
+pub struct Index {
+    cluster_info: Arc<ClusterInfo>,
+    namespaces: HashMap<String, Namespace>,
+}
+
+#[derive(Debug, Default)]
+struct Namespace {
+    /// The ports of each Service in the namespace.
+    services: HashMap<String, HashSet<NonZeroU16>>,
+
+    http_routes: HashMap<String, HttpRoute>,
+
+    /// Policy watches for Service ports that have been looked up.
+    watches: HashMap<(String, NonZeroU16), PolicyWatch>,
+}
+
+#[derive(Debug)]
+struct PolicyWatch {
+    tx: watch::Sender<OutboundPolicy>,
+    rx: watch::Receiver<OutboundPolicy>,
+}
+
+/// An HTTPRoute that has at least one Service parent.
+///
+/// Backends are not resolved until a policy is computed for a Service port so
+/// that routes are updated as backend Services are created and deleted.
+#[derive(Clone, Debug, PartialEq)]
+struct HttpRoute {
+    parents: Vec<ParentRef>,
+    hostnames: Vec<HostMatch>,
+    rules: Vec<Rule>,
+
+    /// Used to order routes with equal precedence, oldest first.
+    creation_timestamp: Option<Time>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ParentRef {
+    name: String,
+    port: Option<NonZeroU16>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Rule {
+    matches: Vec<HttpRouteMatch>,
+    filters: Vec<Filter>,
+    backends: Vec<BackendRef>,
+    request_timeout: Option<time::Duration>,
+    backend_request_timeout: Option<time::Duration>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct BackendRef {
+    weight: u32,
+    name: String,
+    port: u16,
+    filters: Vec<Filter>,
+}
+
+// === impl Index ===
+
+impl Index {
+    pub fn shared(cluster_info: impl Into<Arc<ClusterInfo>>) -> SharedIndex {
+        Arc::new(RwLock::new(Self {
+            cluster_info: cluster_info.into(),
+            namespaces: HashMap::default(),
+        }))
+    }
+
+    /// Obtains a Service port's policy receiver.
+    ///
+    /// An error is returned if the Service is not found.
+    pub fn service_policy_rx(
+        &mut self,
+        namespace: &str,
+        service: &str,
+        port: NonZeroU16,
+    ) -> Result<watch::Receiver<OutboundPolicy>> {
+        let ns = self
+            .namespaces
+            .get_mut(namespace)
+            .filter(|ns| ns.services.contains_key(service))
+            .ok_or_else(|| anyhow!("service {}.{} not found", service, namespace))?;
+
+        let key = (service.to_string(), port);
+        if let Some(watch) = ns.watches.get(&key) {
+            return Ok(watch.rx.clone());
+        }
+
+        let policy = ns.policy(namespace, service, port, &self.cluster_info);
+        let (tx, rx) = watch::channel(policy);
+        ns.watches.insert(key, PolicyWatch { tx, rx: rx.clone() });
+        Ok(rx)
+    }
+
+    fn apply_route<R>(&mut self, route: R)
+    where
+        R: ResourceExt,
+        HttpRoute: TryFrom<R>,
+        <HttpRoute as TryFrom<R>>::Error: std::fmt::Display,
+    {
+        let ns = route.namespace().expect("HttpRoute must have a namespace");
+        let name = route.name_unchecked();
+
+        let route = match HttpRoute::try_from(route) {
+            Ok(route) => route,
+            Err(error) => {
+                tracing::debug!(%ns, %name, %error, "Ignoring HTTPRoute");
+                // The route may have previously been valid.
+                self.delete_route(ns, name);
+                return;
+            }
+        };
+
+        let namespace = self.namespaces.entry(ns.clone()).or_default();
+        if namespace.http_routes.get(&name) == Some(&route) {
+            return;
+        }
+        namespace.http_routes.insert(name, route);
+        namespace.reindex(&ns, &self.cluster_info);
+    }
+
+    fn delete_route(&mut self, ns: String, name: String) {
+        if let Some(namespace) = self.namespaces.get_mut(&ns) {
+            if namespace.http_routes.remove(&name).is_some() {
+                namespace.reindex(&ns, &self.cluster_info);
+            }
+            if namespace.is_empty() {
+                self.namespaces.remove(&ns);
+            }
+        }
+    }
+
+    fn reset_routes<R>(&mut self, routes: Vec<R>, deleted: HashMap<String, HashSet<String>>)
+    where
+        R: ResourceExt,
+        HttpRoute: TryFrom<R>,
+        <HttpRoute as TryFrom<R>>::Error: std::fmt::Display,
+    {
+        for route in routes.into_iter() {
+            self.apply_route(route);
+        }
+        for (ns, names) in deleted.into_iter() {
+            for name in names.into_iter() {
+                self.delete_route(ns.clone(), name);
+            }
+        }
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::Service> for Index {
+    fn apply(&mut self, service: k8s::Service) {
+        let ns = service.namespace().expect("Service must have a namespace");
+        let name = service.name_unchecked();
+        let ports = service
+            .spec
+            .into_iter()
+            .flat_map(|spec| spec.ports.into_iter().flatten())
+            .filter_map(|sp| u16::try_from(sp.port).ok().and_then(NonZeroU16::new))
+            .collect::<HashSet<_>>();
+
+        let namespace = self.namespaces.entry(ns.clone()).or_default();
+        if namespace.services.get(&name) == Some(&ports) {
+            return;
+        }
+        namespace.services.insert(name, ports);
+
+        // Services may be referenced as backends by other Services' routes.
+        namespace.reindex(&ns, &self.cluster_info);
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        if let Some(namespace) = self.namespaces.get_mut(&ns) {
+            if namespace.services.remove(&name).is_none() {
+                return;
+            }
+
+            // Drop the watches for the Service's ports so that streams are
+            // closed.
+            namespace.watches.retain(|(svc, _), _| *svc != name);
+            namespace.reindex(&ns, &self.cluster_info);
+
+            if namespace.is_empty() {
+                self.namespaces.remove(&ns);
+            }
+        }
+    }
+
+    fn reset(&mut self, services: Vec<k8s::Service>, deleted: HashMap<String, HashSet<String>>) {
+        for service in services.into_iter() {
+            self.apply(service);
+        }
+        for (ns, names) in deleted.into_iter() {
+            for name in names.into_iter() {
+                self.delete(ns.clone(), name);
+            }
+        }
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<api::HttpRoute> for Index {
+    fn apply(&mut self, route: api::HttpRoute) {
+        self.apply_route(route)
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        self.delete_route(ns, name)
+    }
+
+    fn reset(&mut self, routes: Vec<api::HttpRoute>, deleted: HashMap<String, HashSet<String>>) {
+        self.reset_routes(routes, deleted)
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<policy::HttpRoute> for Index {
+    fn apply(&mut self, route: policy::HttpRoute) {
+        self.apply_route(route)
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        self.delete_route(ns, name)
+    }
+
+    fn reset(&mut self, routes: Vec<policy::HttpRoute>, deleted: HashMap<String, HashSet<String>>) {
+        self.reset_routes(routes, deleted)
+    }
+}
+
+// === impl Namespace ===
+
+impl Namespace {
+    fn is_empty(&self) -> bool {
+        self.services.is_empty() && self.http_routes.is_empty() && self.watches.is_empty()
+    }
+
+    /// Publishes updated policies for all watched Service ports in the
+    /// namespace.
+    fn reindex(&mut self, ns: &str, cluster_info: &ClusterInfo) {
+        for ((service, port), watch) in self.watches.iter() {
+            let policy = self.policy(ns, service, *port, cluster_info);
+            if *watch.rx.borrow() == policy {
+                tracing::trace!(%ns, %service, %port, "Skipped redundant policy update");
+                continue;
+            }
+            tracing::debug!(%ns, %service, %port, "Updating policy");
+            watch
+                .tx
+                .send(policy)
+                .expect("a receiver is held by the index");
+        }
+    }
+
+    fn policy(
+        &self,
+        ns: &str,
+        service: &str,
+        port: NonZeroU16,
+        cluster_info: &ClusterInfo,
+    ) -> OutboundPolicy {
+        let mut routes = self
+            .http_routes
+            .iter()
+            .filter(|(_, route)| route.selects_service_port(service, port))
+            .collect::<Vec<_>>();
+
+        // Rank routes by age and then by name. Matches with equal precedence
+        // are ordered by their route's rank.
+        routes.sort_by_cached_key(|(name, route)| (route.creation_timestamp.clone(), *name));
+
+        let http_routes = routes
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (name, route))| {
+                let route = route.outbound(ns, service, port, rank, &self.services, cluster_info);
+                (name.clone(), route)
+            })
+            .collect();
+
+        OutboundPolicy {
+            http_routes,
+            authority: cluster_info.service_authority(ns, service, port),
+            name: service.to_string(),
+            namespace: ns.to_string(),
+            port,
+        }
+    }
+}
+
+// === impl HttpRoute ===
+
+impl TryFrom<api::HttpRoute> for HttpRoute {
+    type Error = anyhow::Error;
+
+    fn try_from(route: api::HttpRoute) -> Result<Self> {
+        let creation_timestamp = route.metadata.creation_timestamp.clone();
+        let parents = ParentRef::collect_from(
+            route.metadata.namespace.as_deref(),
+            route.spec.inner.parent_refs,
+        )?;
+        let hostnames = route
+            .spec
+            .hostnames
+            .into_iter()
+            .flatten()
+            .map(convert::http_match)
+            .collect();
+        let rules = route
+            .spec
+            .rules
+            .into_iter()
+            .flatten()
+            .map(
+                |api::HttpRouteRule {
+                     matches,
+                     filters,
+                     backend_refs,
+                 }| {
+                    Rule::try_new(
+                        matches,
+                        filters.into_iter().flatten().map(try_gateway_filter),
+                        backend_refs,
+                        None,
+                    )
+                },
+            )
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            parents,
+            hostnames,
+            rules,
+            creation_timestamp,
+        })
+    }
+}
+
+impl TryFrom<policy::HttpRoute> for HttpRoute {
+    type Error = anyhow::Error;
+
+    fn try_from(route: policy::HttpRoute) -> Result<Self> {
+        let creation_timestamp = route.metadata.creation_timestamp.clone();
+        let parents = ParentRef::collect_from(
+            route.metadata.namespace.as_deref(),
+            route.spec.inner.parent_refs,
+        )?;
+        let hostnames = route
+            .spec
+            .hostnames
+            .into_iter()
+            .flatten()
+            .map(convert::http_match)
+            .collect();
+        let rules = route
+            .spec
+            .rules
+            .into_iter()
+            .flatten()
+            .map(
+                |policy::HttpRouteRule {
+                     matches,
+                     filters,
+                     backend_refs,
+                     timeouts,
+                 }| {
+                    Rule::try_new(
+                        matches,
+                        filters.into_iter().flatten().map(try_policy_filter),
+                        backend_refs,
+                        timeouts,
+                    )
+                },
+            )
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            parents,
+            hostnames,
+            rules,
+            creation_timestamp,
+        })
+    }
+}
+
+impl HttpRoute {
+    fn selects_service_port(&self, service: &str, port: NonZeroU16) -> bool {
+        self.parents
+            .iter()
+            .any(|p| p.name == service && p.port.map_or(true, |p| p == port))
+    }
+
+    fn outbound(
+        &self,
+        ns: &str,
+        service: &str,
+        port: NonZeroU16,
+        rank: usize,
+        services: &HashMap<String, HashSet<NonZeroU16>>,
+        cluster_info: &ClusterInfo,
+    ) -> OutboundHttpRoute {
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let backends = if rule.backends.is_empty() {
+                    // Requests are sent to the parent Service when no backends
+                    // are specified.
+                    vec![Backend::Service(WeightedService {
+                        weight: 1,
+                        authority: cluster_info.service_authority(ns, service, port),
+                        name: service.to_string(),
+                        namespace: ns.to_string(),
+                        port,
+                        filters: vec![],
+                    })]
+                } else {
+                    rule.backends
+                        .iter()
+                        .map(|backend| backend.resolve(ns, services, cluster_info))
+                        .collect()
+                };
+
+                OutboundHttpRouteRule {
+                    matches: rule.matches.clone(),
+                    filters: rule.filters.clone(),
+                    backends,
+                    request_timeout: rule.request_timeout,
+                    backend_request_timeout: rule.backend_request_timeout,
+                }
+            })
+            .collect();
+
+        OutboundHttpRoute {
+            hostnames: self.hostnames.clone(),
+            rules,
+            rank,
+        }
+    }
+}
+
+// === impl ParentRef ===
+
+impl ParentRef {
+    fn collect_from(
+        route_ns: Option<&str>,
+        parent_refs: Option<Vec<api::ParentReference>>,
+    ) -> Result<Vec<Self>> {
+        let parents = parent_refs
+            .into_iter()
+            .flatten()
+            .filter_map(|parent_ref| Self::from_parent_ref(route_ns, parent_ref))
+            .collect::<Vec<_>>();
+
+        if parents.is_empty() {
+            bail!("HTTPRoute resource does not reference a Service in its namespace");
+        }
+
+        Ok(parents)
+    }
+
+    /// Converts a parent reference, returning `None` if the parent is not a
+    /// `Service` in the route's namespace.
+    fn from_parent_ref(
+        route_ns: Option<&str>,
+        api::ParentReference {
+            group,
+            kind,
+            namespace,
+            name,
+            section_name: _,
+            port,
+        }: api::ParentReference,
+    ) -> Option<Self> {
+        let is_core = group
+            .as_deref()
+            .map_or(true, |g| g.is_empty() || g == "core");
+        let is_service = kind
+            .as_deref()
+            .map_or(false, |k| k.eq_ignore_ascii_case("service"));
+        if !is_core || !is_service || name.is_empty() {
+            return None;
+        }
+
+        if namespace.is_some() && namespace.as_deref() != route_ns {
+            return None;
+        }
+
+        Some(Self {
+            name,
+            port: port.and_then(NonZeroU16::new),
+        })
+    }
+}
+
+// === impl Rule ===
+
+impl Rule {
+    fn try_new(
+        matches: Option<Vec<api::HttpRouteMatch>>,
+        filters: impl Iterator<Item = Result<Filter>>,
+        backend_refs: Option<Vec<api::HttpBackendRef>>,
+        timeouts: Option<policy::HttpRouteTimeouts>,
+    ) -> Result<Self> {
+        let matches = matches
+            .into_iter()
+            .flatten()
+            .map(InboundRouteBinding::try_match)
+            .collect::<Result<_>>()?;
+
+        let filters = filters.collect::<Result<_>>()?;
+
+        let backends = backend_refs
+            .into_iter()
+            .flatten()
+            .filter_map(
+                |api::HttpBackendRef {
+                     backend_ref,
+                     filters,
+                 }| {
+                    let api::BackendRef { weight, name, port } = backend_ref?;
+                    let filters = filters
+                        .into_iter()
+                        .flatten()
+                        .map(try_gateway_filter)
+                        .collect::<Result<_>>();
+                    Some(filters.map(|filters| BackendRef {
+                        weight: weight.map(u32::from).unwrap_or(1),
+                        name,
+                        port,
+                        filters,
+                    }))
+                },
+            )
+            .collect::<Result<_>>()?;
+
+        let timeouts = timeouts.unwrap_or_default();
+        let request_timeout = timeouts.request_timeout()?;
+        let backend_request_timeout = timeouts.backend_request_timeout()?;
+
+        Ok(Self {
+            matches,
+            filters,
+            backends,
+            request_timeout,
+            backend_request_timeout,
+        })
+    }
+}
+
+// === impl BackendRef ===
+
+impl BackendRef {
+    fn resolve(
+        &self,
+        ns: &str,
+        services: &HashMap<String, HashSet<NonZeroU16>>,
+        cluster_info: &ClusterInfo,
+    ) -> Backend {
+        let invalid = |message: String| Backend::Invalid {
+            weight: self.weight,
+            message,
+        };
+
+        let port = match NonZeroU16::new(self.port) {
+            Some(port) => port,
+            None => return invalid(format!("backend {} does not specify a port", self.name)),
+        };
+
+        match services.get(&self.name) {
+            None => invalid(format!("Service {}.{} not found", self.name, ns)),
+            Some(ports) if !ports.contains(&port) => invalid(format!(
+                "Service {}.{} does not have port {}",
+                self.name, ns, port
+            )),
+            Some(_) => Backend::Service(WeightedService {
+                weight: self.weight,
+                authority: cluster_info.service_authority(ns, &self.name, port),
+                name: self.name.clone(),
+                namespace: ns.to_string(),
+                port,
+                filters: self.filters.clone(),
+            }),
+        }
+    }
+}
+
+fn try_gateway_filter(filter: api::HttpRouteFilter) -> Result<Filter> {
+    let filter = match filter {
+        api::HttpRouteFilter::RequestHeaderModifier {
+            request_header_modifier,
+        } => Filter::RequestHeaderModifier(convert::req_header_modifier(request_header_modifier)?),
+
+        api::HttpRouteFilter::RequestRedirect { request_redirect } => {
+            Filter::RequestRedirect(convert::req_redirect(request_redirect)?)
+        }
+
+        api::HttpRouteFilter::RequestMirror { .. } => {
+            bail!("RequestMirror filter is not supported")
+        }
+        api::HttpRouteFilter::URLRewrite { .. } => {
+            bail!("URLRewrite filter is not supported")
+        }
+        api::HttpRouteFilter::ExtensionRef { .. } => {
+            bail!("ExtensionRef filter is not supported")
+        }
+    };
+    Ok(filter)
+}
+
+fn try_policy_filter(filter: policy::HttpRouteFilter) -> Result<Filter> {
+    let filter = match filter {
+        policy::HttpRouteFilter::RequestHeaderModifier {
+            request_header_modifier,
+        } => Filter::RequestHeaderModifier(convert::req_header_modifier(request_header_modifier)?),
+
+        policy::HttpRouteFilter::RequestRedirect { request_redirect } => {
+            Filter::RequestRedirect(convert::req_redirect(request_redirect)?)
+        }
+    };
+    Ok(filter)
+}
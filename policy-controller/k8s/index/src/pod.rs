@@ -1,6 +1,6 @@
 use crate::DefaultPolicy;
 use ahash::AHashMap as HashMap;
-use anyhow::{bail, Context, Result};
+use anyhow::{Context, Result};
 use linkerd_policy_controller_k8s_api as k8s;
 use std::num::NonZeroU16;
 
@@ -15,10 +15,13 @@ pub(crate) struct Meta {
 }
 
 /// Per-pod settings, as configured by the pod's annotations.
+///
+/// Namespaces may be annotated with the same settings, in which case they
+/// apply to all pods in the namespace that do not override them.
 #[derive(Debug, Default, PartialEq)]
 pub(crate) struct Settings {
-    pub require_id_ports: PortSet,
-    pub opaque_ports: PortSet,
+    pub require_id_ports: Option<PortSet>,
+    pub opaque_ports: Option<PortSet>,
     pub default_policy: Option<DefaultPolicy>,
 }
 
@@ -68,6 +71,21 @@ pub(crate) fn tcp_port_names(spec: Option<k8s::PodSpec>) -> HashMap<String, Port
     port_names
 }
 
+/// Gets the set of named TCP ports from an external workload spec.
+pub(crate) fn external_workload_port_names(
+    spec: &k8s::workload::ExternalWorkloadSpec,
+) -> HashMap<String, PortSet> {
+    let mut port_names = HashMap::<String, PortSet>::default();
+    for port in spec.ports.iter().flatten() {
+        if let None | Some("TCP") = port.protocol.as_deref() {
+            if let Some(name) = port.name.clone() {
+                port_names.entry(name).or_default().insert(port.port);
+            }
+        }
+    }
+    port_names
+}
+
 impl Meta {
     pub(crate) fn from_metadata(meta: k8s::ObjectMeta) -> Self {
         let settings = Settings::from_metadata(&meta);
@@ -80,11 +98,11 @@ impl Meta {
 }
 
 impl Settings {
-    /// Reads pod settings from the pod metadata including:
+    /// Reads pod (or namespace) settings from the resource metadata including:
     ///
     /// - Opaque ports
     /// - Ports that require identity
-    /// - The pod's default policy
+    /// - The default policy
     pub(crate) fn from_metadata(meta: &k8s::ObjectMeta) -> Self {
         let anns = match meta.annotations.as_ref() {
             None => return Self::default(),
@@ -108,6 +126,32 @@ impl Settings {
             require_id_ports,
         }
     }
+
+    /// Returns the default policy, falling back to the namespace's settings
+    /// when the pod does not configure one.
+    pub(crate) fn default_policy_or(&self, ns: &Settings) -> Option<DefaultPolicy> {
+        self.default_policy.or(ns.default_policy)
+    }
+
+    /// Indicates whether the port is opaque, falling back to the namespace's
+    /// settings when the pod does not configure opaque ports.
+    pub(crate) fn is_opaque_or(&self, ns: &Settings, port: NonZeroU16) -> bool {
+        self.opaque_ports
+            .as_ref()
+            .or(ns.opaque_ports.as_ref())
+            .map(|ports| ports.contains(&port))
+            .unwrap_or(false)
+    }
+
+    /// Indicates whether the port requires identity, falling back to the
+    /// namespace's settings when the pod does not configure these ports.
+    pub(crate) fn requires_id_or(&self, ns: &Settings, port: NonZeroU16) -> bool {
+        self.require_id_ports
+            .as_ref()
+            .or(ns.require_id_ports.as_ref())
+            .map(|ports| ports.contains(&port))
+            .unwrap_or(false)
+    }
 }
 
 /// Attempts to read a default policy override from an annotation map.
@@ -123,20 +167,19 @@ fn default_policy(
 }
 
 /// Reads `annotation` from the provided set of annotations, parsing it as a port set.  If the
-/// annotation is not set or is invalid, the empty set is returned.
+/// annotation is not set or is invalid, `None` is returned.
 fn ports_annotation(
     annotations: &std::collections::BTreeMap<String, String>,
     annotation: &str,
-) -> PortSet {
-    annotations
-        .get(annotation)
-        .map(|spec| {
-            parse_portset(spec).unwrap_or_else(|error| {
-                tracing::info!(%spec, %error, %annotation, "Invalid ports list");
-                Default::default()
-            })
-        })
-        .unwrap_or_default()
+) -> Option<PortSet> {
+    let spec = annotations.get(annotation)?;
+    match parse_portset(spec) {
+        Ok(ports) => Some(ports),
+        Err(error) => {
+            tracing::info!(%spec, %error, %annotation, "Invalid ports list");
+            None
+        }
+    }
 }
 
 /// Read a comma-separated of ports or port ranges from the given string.
@@ -151,15 +194,11 @@ fn parse_portset(s: &str) -> Result<PortSet> {
                     ports.insert(port);
                 }
             }
-            Some((floor, ceil)) => {
-                let floor = floor.trim().parse::<NonZeroU16>().context("parsing port")?;
-                let ceil = ceil.trim().parse::<NonZeroU16>().context("parsing port")?;
-                if floor > ceil {
-                    bail!("Port range must be increasing");
-                }
-                ports.extend(
-                    (u16::from(floor)..=u16::from(ceil)).map(|p| NonZeroU16::try_from(p).unwrap()),
-                );
+            Some(_) => {
+                let range = spec
+                    .parse::<k8s::policy::server::PortRange>()
+                    .context("parsing port range")?;
+                ports.extend(range.iter());
             }
         }
     }
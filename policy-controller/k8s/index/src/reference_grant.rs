@@ -0,0 +1,47 @@
+use linkerd_policy_controller_k8s_api::gateway::referencegrant as k8s;
+
+/// The rules of a `ReferenceGrant`, which permit resources in other namespaces
+/// to reference resources in the grant's namespace.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Spec {
+    from: Vec<k8s::ReferenceGrantFrom>,
+    to: Vec<k8s::ReferenceGrantTo>,
+}
+
+/// Identifies one side of a cross-namespace reference.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Reference<'a> {
+    pub group: &'a str,
+    pub kind: &'a str,
+    pub namespace: &'a str,
+    pub name: &'a str,
+}
+
+// === impl Spec ===
+
+impl Spec {
+    pub(crate) fn from_resource(grant: k8s::ReferenceGrant) -> Self {
+        Self {
+            from: grant.spec.from,
+            to: grant.spec.to,
+        }
+    }
+
+    /// Indicates whether this grant permits `from` to reference `to`. The
+    /// caller is responsible for ensuring that `to` is in the grant's
+    /// namespace.
+    pub(crate) fn permits(&self, from: Reference<'_>, to: Reference<'_>) -> bool {
+        let from_ok = self.from.iter().any(|f| {
+            f.namespace == from.namespace
+                && f.group.eq_ignore_ascii_case(from.group)
+                && f.kind.eq_ignore_ascii_case(from.kind)
+        });
+
+        from_ok
+            && self.to.iter().any(|t| {
+                t.group.eq_ignore_ascii_case(to.group)
+                    && t.kind.eq_ignore_ascii_case(to.kind)
+                    && t.name.as_deref().map_or(true, |n| n == to.name)
+            })
+    }
+}
@@ -1,6 +1,12 @@
-use crate::ClusterInfo;
+use crate::{pod, ClusterInfo};
+use ahash::AHashMap as HashMap;
 use linkerd_policy_controller_core::ProxyProtocol;
-use linkerd_policy_controller_k8s_api::{self as k8s, policy::server::Port};
+use linkerd_policy_controller_k8s_api::{
+    self as k8s,
+    chrono::{DateTime, Utc},
+    policy::server::Port,
+};
+use std::{cmp::Ordering, num::NonZeroU16};
 
 /// The parts of a `Server` resource that can change.
 #[derive(Debug, PartialEq)]
@@ -9,11 +15,13 @@ pub(crate) struct Server {
     pub pod_selector: k8s::labels::Selector,
     pub port_ref: Port,
     pub protocol: ProxyProtocol,
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 impl Server {
     pub(crate) fn from_resource(srv: k8s::policy::Server, cluster: &ClusterInfo) -> Self {
         Self {
+            created_at: srv.metadata.creation_timestamp.map(|k8s::Time(t)| t),
             labels: srv.metadata.labels.into(),
             pod_selector: srv.spec.pod_selector,
             port_ref: srv.spec.port,
@@ -32,8 +40,39 @@ fn proxy_protocol(
         },
         Some(k8s::policy::server::ProxyProtocol::Http1) => ProxyProtocol::Http1,
         Some(k8s::policy::server::ProxyProtocol::Http2) => ProxyProtocol::Http2,
-        Some(k8s::policy::server::ProxyProtocol::Grpc) => ProxyProtocol::Http2,
+        Some(k8s::policy::server::ProxyProtocol::Grpc) => ProxyProtocol::Grpc,
         Some(k8s::policy::server::ProxyProtocol::Opaque) => ProxyProtocol::Opaque,
         Some(k8s::policy::server::ProxyProtocol::Tls) => ProxyProtocol::Tls,
     }
 }
+
+/// Orders servers by precedence when multiple servers select the same pod
+/// port: the oldest server wins and ties are broken by name.
+///
+/// Servers without a creation timestamp (i.e. that have not been persisted)
+/// are ordered first.
+pub(crate) fn cmp_precedence(
+    (a_name, a_created_at): (&str, Option<&DateTime<Utc>>),
+    (b_name, b_created_at): (&str, Option<&DateTime<Utc>>),
+) -> Ordering {
+    a_created_at
+        .cmp(&b_created_at)
+        .then_with(|| a_name.cmp(b_name))
+}
+
+/// Returns the pod ports referenced by a server's port.
+pub(crate) fn select_ports(
+    port_ref: &Port,
+    port_names: &HashMap<String, pod::PortSet>,
+) -> Vec<NonZeroU16> {
+    match port_ref {
+        Port::Number(p) => Some(*p).into_iter().collect(),
+        Port::Range(range) => range.iter().collect(),
+        Port::Name(name) => port_names
+            .get(name)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect(),
+    }
+}
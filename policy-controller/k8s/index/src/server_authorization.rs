@@ -1,7 +1,7 @@
 use crate::ClusterInfo;
 use anyhow::Result;
 use linkerd_policy_controller_core::{
-    ClientAuthentication, ClientAuthorization, IdentityMatch, NetworkMatch,
+    AuthorizationAction, ClientAuthentication, ClientAuthorization, IdentityMatch, NetworkMatch,
 };
 use linkerd_policy_controller_k8s_api::{self as k8s, policy::server_authorization::MeshTls};
 
@@ -83,6 +83,7 @@ fn client_authz(
     };
 
     Ok(ClientAuthorization {
+        action: AuthorizationAction::Allow,
         networks,
         authentication,
     })
@@ -0,0 +1,1257 @@
+//! Computes the status of policy resources so that it can be written back to
+//! the Kubernetes API.
+//!
+//! The status index watches the same resources as the policy index, but it is
+//! only concerned with whether resources were accepted by the controller and
+//! whether they conflict with one another. When
+//! a resource's desired status differs from the status observed on the
+//! resource, a status [`Update`] is published. Updates are applied by a
+//! separate controller so that only a single replica writes statuses.
+
+use crate::{
+    authorization_policy::{self, AuthenticationTarget, Target},
+    grpc_route::InboundGrpcRouteBinding,
+    http_route::{InboundParentRef, InboundRouteBinding},
+    index::ReferenceGrantNsIndex,
+    pod, reference_grant, server,
+};
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
+use linkerd_policy_controller_k8s_api::{
+    self as k8s,
+    chrono::{DateTime, Utc},
+    policy::{httproute as policy, server::Port},
+    Condition, ResourceExt, Time,
+};
+use parking_lot::RwLock;
+use std::{collections::hash_map::Entry, num::NonZeroU16, sync::Arc};
+use tokio::sync::mpsc;
+use tracing::info_span;
+
+/// The controller name used in route parent statuses.
+pub const POLICY_CONTROLLER_NAME: &str = "linkerd.io/policy-controller";
+
+pub mod conditions {
+    pub const ACCEPTED: &str = "Accepted";
+    pub const RESOLVED_REFS: &str = "ResolvedRefs";
+    pub const TARGET_FOUND: &str = "TargetFound";
+    pub const CONFLICTED: &str = "Conflicted";
+}
+
+pub mod reasons {
+    pub const ACCEPTED: &str = "Accepted";
+    pub const RESOLVED_REFS: &str = "ResolvedRefs";
+    pub const TARGET_FOUND: &str = "TargetFound";
+    pub const NO_CONFLICTS: &str = "NoConflicts";
+    pub const PORT_CONFLICT: &str = "PortConflict";
+    pub const NO_MATCHING_PARENT: &str = "NoMatchingParent";
+    pub const NO_MATCHING_TARGET: &str = "NoMatchingTarget";
+    pub const NOT_ALLOWED_BY_PARENT: &str = "NotAllowedByParent";
+    pub const REF_NOT_FOUND: &str = "RefNotFound";
+    pub const REF_NOT_PERMITTED: &str = "RefNotPermitted";
+    pub const UNSUPPORTED_VALUE: &str = "UnsupportedValue";
+}
+
+pub type SharedIndex = Arc<RwLock<Index>>;
+
+/// Identifies the kind of a resource whose status is written by the controller.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// A `gateway.networking.k8s.io` HTTPRoute.
+    GatewayHttpRoute,
+
+    /// A `policy.linkerd.io` HTTPRoute.
+    LinkerdHttpRoute,
+
+    /// A `gateway.networking.k8s.io` GRPCRoute.
+    GrpcRoute,
+
+    /// A `policy.linkerd.io` AuthorizationPolicy.
+    AuthorizationPolicy,
+
+    /// A `policy.linkerd.io` Server.
+    Server,
+}
+
+/// Identifies a resource whose status is written by the controller.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Id {
+    pub kind: Kind,
+    pub namespace: String,
+    pub name: String,
+}
+
+/// A status update for a single resource.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Update {
+    pub id: Id,
+
+    /// A merge patch to apply to the resource's status subresource. Unset when
+    /// the resource has been deleted and any pending update should be dropped.
+    pub patch: Option<serde_json::Value>,
+}
+
+/// Tracks the resources needed to compute statuses, publishing updates as
+/// resources change.
+#[derive(Debug)]
+pub struct Index {
+    updates: mpsc::UnboundedSender<Update>,
+    namespaces: HashMap<String, Namespace>,
+
+    /// Grants that permit routes to reference parent `Server`s in other
+    /// namespaces.
+    reference_grants: ReferenceGrantNsIndex,
+
+    /// Indicates whether the initial set of pods has been indexed. Server
+    /// conflicts cannot be determined until all pods are known, so server
+    /// statuses are not published until then.
+    pods_synced: bool,
+}
+
+/// Holds the status-relevant state of a single namespace.
+#[derive(Debug, Default)]
+struct Namespace {
+    pods: HashMap<String, Workload>,
+
+    external_workloads: HashMap<String, Workload>,
+
+    servers: HashMap<String, Server>,
+
+    http_routes: HashMap<(Kind, String), HttpRoute>,
+
+    authorization_policies: HashMap<String, AuthorizationPolicy>,
+
+    /// The names of all `MeshTLSAuthentication`s in the namespace.
+    meshtls_authentications: HashSet<String>,
+
+    /// The names of all `NetworkAuthentication`s in the namespace.
+    network_authentications: HashSet<String>,
+}
+
+/// The parts of a pod or external workload that determine which servers
+/// select its ports.
+#[derive(Debug, PartialEq)]
+struct Workload {
+    labels: k8s::Labels,
+    port_names: HashMap<String, pod::PortSet>,
+}
+
+/// The status-relevant parts of a Server resource.
+#[derive(Debug)]
+struct Server {
+    generation: Option<i64>,
+    created_at: Option<DateTime<Utc>>,
+    pod_selector: k8s::labels::Selector,
+    port_ref: Port,
+
+    /// The server's conditions, as observed on the resource.
+    conditions: Vec<Condition>,
+}
+
+/// Describes a workload port that is selected by a server with higher
+/// precedence.
+#[derive(Debug)]
+struct ServerConflict<'n> {
+    /// Describes the kind of workload, i.e. "pod" or "external workload".
+    kind: &'static str,
+    workload: &'n str,
+    port: NonZeroU16,
+    winner: &'n str,
+}
+
+/// The status-relevant parts of an HTTPRoute or GRPCRoute resource.
+#[derive(Debug)]
+struct HttpRoute {
+    generation: Option<i64>,
+
+    /// The route's parent references that target `Server`s. Parents that are
+    /// not `Server`s are managed by other controllers.
+    parents: Vec<k8s_gateway_api::ParentReference>,
+
+    /// Indicates whether the route's rules could be converted, i.e. whether
+    /// the route is supported by the controller.
+    rules: Result<(), String>,
+
+    /// The route's parent statuses, as observed on the resource.
+    statuses: Vec<k8s_gateway_api::RouteParentStatus>,
+}
+
+/// The status-relevant parts of an AuthorizationPolicy resource.
+#[derive(Debug)]
+struct AuthorizationPolicy {
+    generation: Option<i64>,
+
+    /// The policy's target and authentications, if the spec is supported.
+    spec: Result<authorization_policy::Spec, String>,
+
+    /// The policy's conditions, as observed on the resource.
+    conditions: Vec<Condition>,
+}
+
+// === impl Index ===
+
+impl Index {
+    pub fn shared(updates: mpsc::UnboundedSender<Update>) -> SharedIndex {
+        Arc::new(RwLock::new(Self {
+            updates,
+            namespaces: HashMap::default(),
+            reference_grants: ReferenceGrantNsIndex::default(),
+            pods_synced: false,
+        }))
+    }
+
+    fn apply_route(&mut self, kind: Kind, ns: String, name: String, route: HttpRoute) {
+        let _span = info_span!("apply", %ns, %name, ?kind).entered();
+
+        let patch = self.http_route_patch(kind, &ns, &name, &route);
+        let created = self
+            .namespaces
+            .entry(ns.clone())
+            .or_default()
+            .http_routes
+            .insert((kind, name.clone()), route)
+            .is_none();
+
+        if let Some(patch) = patch {
+            self.send(
+                Id {
+                    kind,
+                    namespace: ns.clone(),
+                    name,
+                },
+                Some(patch),
+            );
+        }
+
+        // Policies may target the new route.
+        if created {
+            self.reconcile_policies(&ns);
+        }
+    }
+
+    fn delete_route(&mut self, kind: Kind, ns: String, name: String) {
+        let _span = info_span!("delete", %ns, %name, ?kind).entered();
+
+        if let Entry::Occupied(mut entry) = self.namespaces.entry(ns.clone()) {
+            if entry
+                .get_mut()
+                .http_routes
+                .remove(&(kind, name.clone()))
+                .is_none()
+            {
+                return;
+            }
+            if entry.get().is_empty() {
+                entry.remove();
+            }
+        }
+
+        self.send(
+            Id {
+                kind,
+                namespace: ns.clone(),
+                name,
+            },
+            None,
+        );
+        self.reconcile_policies(&ns);
+    }
+
+    fn apply_policy(&mut self, ns: String, name: String, policy: AuthorizationPolicy) {
+        let _span = info_span!("apply", %ns, %name).entered();
+
+        let patch = self.authorization_policy_patch(&ns, &policy);
+        self.namespaces
+            .entry(ns.clone())
+            .or_default()
+            .authorization_policies
+            .insert(name.clone(), policy);
+
+        if let Some(patch) = patch {
+            self.send(
+                Id {
+                    kind: Kind::AuthorizationPolicy,
+                    namespace: ns,
+                    name,
+                },
+                Some(patch),
+            );
+        }
+    }
+
+    fn delete_policy(&mut self, ns: String, name: String) {
+        let _span = info_span!("delete", %ns, %name).entered();
+
+        match self.namespaces.entry(ns.clone()) {
+            Entry::Occupied(mut entry) => {
+                if entry
+                    .get_mut()
+                    .authorization_policies
+                    .remove(&name)
+                    .is_none()
+                {
+                    return;
+                }
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+            }
+            Entry::Vacant(_) => return,
+        }
+
+        self.send(
+            Id {
+                kind: Kind::AuthorizationPolicy,
+                namespace: ns,
+                name,
+            },
+            None,
+        );
+    }
+
+    /// Updates the set of authentications in a namespace, returning true if
+    /// the set changed.
+    fn update_authentication(
+        &mut self,
+        ns: String,
+        update: impl FnOnce(&mut Namespace) -> bool,
+    ) -> bool {
+        match self.namespaces.entry(ns) {
+            Entry::Occupied(mut entry) => {
+                let changed = update(entry.get_mut());
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+                changed
+            }
+            Entry::Vacant(entry) => {
+                let mut namespace = Namespace::default();
+                if update(&mut namespace) {
+                    entry.insert(namespace);
+                    return true;
+                }
+                false
+            }
+        }
+    }
+
+    /// Recomputes the statuses of all routes in the namespace, publishing
+    /// updates for routes whose status has changed.
+    fn reconcile_ns(&mut self, ns: &str) {
+        let namespace = match self.namespaces.get(ns) {
+            Some(namespace) => namespace,
+            None => return,
+        };
+
+        let patches = namespace
+            .http_routes
+            .iter()
+            .filter_map(|((kind, name), route)| {
+                let patch = self.http_route_patch(*kind, ns, name, route)?;
+                Some((*kind, name.clone(), patch))
+            })
+            .collect::<Vec<_>>();
+
+        for (kind, name, patch) in patches.into_iter() {
+            self.send(
+                Id {
+                    kind,
+                    namespace: ns.to_string(),
+                    name,
+                },
+                Some(patch),
+            );
+        }
+
+        self.reconcile_policies(ns);
+    }
+
+    /// Recomputes the statuses of all authorization policies in the
+    /// namespace, publishing updates for policies whose status has changed.
+    fn reconcile_policies(&self, ns: &str) {
+        let namespace = match self.namespaces.get(ns) {
+            Some(namespace) => namespace,
+            None => return,
+        };
+
+        for (name, policy) in namespace.authorization_policies.iter() {
+            if let Some(patch) = self.authorization_policy_patch(ns, policy) {
+                self.send(
+                    Id {
+                        kind: Kind::AuthorizationPolicy,
+                        namespace: ns.to_string(),
+                        name: name.clone(),
+                    },
+                    Some(patch),
+                );
+            }
+        }
+    }
+
+    /// Recomputes the statuses of authorization policies in all namespaces.
+    ///
+    /// Routes may reference parent servers in other namespaces, so changes to
+    /// servers and grants may affect routes in any namespace.
+    fn reconcile_all(&mut self) {
+        let namespaces = self.namespaces.keys().cloned().collect::<Vec<_>>();
+        for ns in namespaces.iter() {
+            self.reconcile_ns(ns);
+        }
+    }
+
+    /// Policies may reference authentications in other namespaces, so changes
+    /// to authentications may affect policies in any namespace.
+    fn reconcile_all_policies(&self) {
+        for ns in self.namespaces.keys() {
+            self.reconcile_policies(ns);
+        }
+    }
+
+    /// Recomputes the statuses of all servers in the namespace, publishing
+    /// updates for servers whose status has changed.
+    fn reconcile_servers(&self, ns: &str) {
+        if !self.pods_synced {
+            return;
+        }
+
+        let namespace = match self.namespaces.get(ns) {
+            Some(namespace) => namespace,
+            None => return,
+        };
+
+        let conflicts = namespace.server_conflicts();
+        for (name, server) in namespace.servers.iter() {
+            let conflicts = conflicts.get(name.as_str()).map(Vec::as_slice);
+            if let Some(patch) = server.patch(conflicts.unwrap_or_default()) {
+                self.send(
+                    Id {
+                        kind: Kind::Server,
+                        namespace: ns.to_string(),
+                        name: name.clone(),
+                    },
+                    Some(patch),
+                );
+            }
+        }
+    }
+
+    /// Updates a pod or external workload in the `workloads` of its
+    /// namespace, returning true if it changed.
+    fn apply_workload(
+        &mut self,
+        ns: String,
+        name: String,
+        workload: Workload,
+        workloads: fn(&mut Namespace) -> &mut HashMap<String, Workload>,
+    ) -> bool {
+        let workloads = workloads(self.namespaces.entry(ns).or_default());
+        if workloads.get(&name) == Some(&workload) {
+            return false;
+        }
+        workloads.insert(name, workload);
+        true
+    }
+
+    fn delete_workload(
+        &mut self,
+        ns: String,
+        name: &str,
+        workloads: fn(&mut Namespace) -> &mut HashMap<String, Workload>,
+    ) -> bool {
+        match self.namespaces.entry(ns) {
+            Entry::Occupied(mut entry) => {
+                let removed = workloads(entry.get_mut()).remove(name).is_some();
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+                removed
+            }
+            Entry::Vacant(_) => false,
+        }
+    }
+
+    /// Returns a status patch for the policy if its desired conditions differ
+    /// from its observed conditions.
+    fn authorization_policy_patch(
+        &self,
+        ns: &str,
+        policy: &AuthorizationPolicy,
+    ) -> Option<serde_json::Value> {
+        let desired = match policy.spec.as_ref() {
+            Err(error) => vec![policy.condition(
+                conditions::ACCEPTED,
+                (false, reasons::UNSUPPORTED_VALUE, error.clone()),
+            )],
+
+            Ok(spec) => {
+                let missing = spec
+                    .authentications
+                    .iter()
+                    .filter_map(|authn| self.missing_authentication(ns, authn))
+                    .collect::<Vec<_>>();
+                let (accepted, resolved) = if missing.is_empty() {
+                    (
+                        (true, reasons::ACCEPTED, String::new()),
+                        (true, reasons::RESOLVED_REFS, String::new()),
+                    )
+                } else {
+                    let message = missing.join("; ");
+                    (
+                        (false, reasons::REF_NOT_FOUND, message.clone()),
+                        (false, reasons::REF_NOT_FOUND, message),
+                    )
+                };
+
+                let target = match self.missing_target(ns, &spec.target) {
+                    None => (true, reasons::TARGET_FOUND, String::new()),
+                    Some(message) => (false, reasons::NO_MATCHING_TARGET, message),
+                };
+
+                vec![
+                    policy.condition(conditions::ACCEPTED, accepted),
+                    policy.condition(conditions::RESOLVED_REFS, resolved),
+                    policy.condition(conditions::TARGET_FOUND, target),
+                ]
+            }
+        };
+
+        if desired.len() == policy.conditions.len()
+            && desired.iter().all(|c| policy.conditions.contains(c))
+        {
+            tracing::trace!("Status is up-to-date");
+            return None;
+        }
+
+        let status = k8s::policy::AuthorizationPolicyStatus {
+            conditions: desired,
+        };
+        Some(serde_json::json!({ "status": status }))
+    }
+
+    /// Returns a message describing the authentication if it cannot be found.
+    fn missing_authentication(&self, ns: &str, authn: &AuthenticationTarget) -> Option<String> {
+        let (kind, namespace, name, found) = match authn {
+            AuthenticationTarget::MeshTLS { namespace, name } => {
+                let namespace = namespace.as_deref().unwrap_or(ns);
+                let found = self
+                    .namespaces
+                    .get(namespace)
+                    .map(|ns| ns.meshtls_authentications.contains(name))
+                    .unwrap_or(false);
+                ("MeshTLSAuthentication", namespace, name, found)
+            }
+            AuthenticationTarget::Network { namespace, name } => {
+                let namespace = namespace.as_deref().unwrap_or(ns);
+                let found = self
+                    .namespaces
+                    .get(namespace)
+                    .map(|ns| ns.network_authentications.contains(name))
+                    .unwrap_or(false);
+                ("NetworkAuthentication", namespace, name, found)
+            }
+            // ServiceAccount references are resolved to identities without
+            // looking up the ServiceAccount.
+            AuthenticationTarget::ServiceAccount { .. } => return None,
+        };
+        if found {
+            return None;
+        }
+        Some(format!(
+            "could not find {} {} in namespace {}",
+            kind, name, namespace
+        ))
+    }
+
+    /// Returns a message describing the policy's target if it cannot be found.
+    fn missing_target(&self, ns: &str, target: &Target) -> Option<String> {
+        let namespace = self.namespaces.get(ns);
+        match target {
+            // Like a `ServerAuthorization`, a policy may select servers that
+            // do not (yet) exist.
+            Target::Namespace | Target::ServerSelector(_) => None,
+            Target::Server(name) => {
+                if namespace.map(|ns| ns.servers.contains_key(name)) == Some(true) {
+                    return None;
+                }
+                Some(format!("Server {} not found", name))
+            }
+            Target::HttpRoute(name) => {
+                let found = namespace
+                    .map(|ns| {
+                        ns.http_routes
+                            .contains_key(&(Kind::GatewayHttpRoute, name.clone()))
+                            || ns
+                                .http_routes
+                                .contains_key(&(Kind::LinkerdHttpRoute, name.clone()))
+                    })
+                    .unwrap_or(false);
+                if found {
+                    return None;
+                }
+                Some(format!("HTTPRoute {} not found", name))
+            }
+            Target::GrpcRoute(name) => {
+                let found = namespace
+                    .map(|ns| {
+                        ns.http_routes
+                            .contains_key(&(Kind::GrpcRoute, name.clone()))
+                    })
+                    .unwrap_or(false);
+                if found {
+                    return None;
+                }
+                Some(format!("GRPCRoute {} not found", name))
+            }
+        }
+    }
+
+    /// Returns a status patch for the route if its desired status differs from
+    /// its observed status.
+    fn http_route_patch(
+        &self,
+        kind: Kind,
+        ns: &str,
+        name: &str,
+        route: &HttpRoute,
+    ) -> Option<serde_json::Value> {
+        let desired = route
+            .parents
+            .iter()
+            .map(|parent_ref| k8s_gateway_api::RouteParentStatus {
+                parent_ref: parent_ref.clone(),
+                controller_name: POLICY_CONTROLLER_NAME.to_string(),
+                conditions: self.parent_conditions(kind, ns, name, route, parent_ref),
+            })
+            .collect::<Vec<_>>();
+
+        let observed = route
+            .statuses
+            .iter()
+            .filter(|s| s.controller_name == POLICY_CONTROLLER_NAME)
+            .cloned()
+            .collect::<Vec<_>>();
+        if desired.len() == observed.len() && desired.iter().all(|s| observed.contains(s)) {
+            tracing::trace!("Status is up-to-date");
+            return None;
+        }
+
+        // Merge patches replace lists wholesale, so we have to preserve the
+        // statuses written by other controllers.
+        let parents = route
+            .statuses
+            .iter()
+            .filter(|s| s.controller_name != POLICY_CONTROLLER_NAME)
+            .cloned()
+            .chain(desired)
+            .collect();
+        let status = k8s_gateway_api::RouteStatus { parents };
+        Some(serde_json::json!({ "status": status }))
+    }
+
+    fn parent_conditions(
+        &self,
+        kind: Kind,
+        ns: &str,
+        name: &str,
+        route: &HttpRoute,
+        parent_ref: &k8s_gateway_api::ParentReference,
+    ) -> Vec<Condition> {
+        let (accepted, resolved) =
+            match InboundParentRef::from_parent_ref(Some(ns), parent_ref.clone()) {
+                // Parents that aren't servers have already been filtered.
+                None => return vec![],
+
+                Some(Err(error)) => (
+                    (false, reasons::UNSUPPORTED_VALUE, error.to_string()),
+                    (true, reasons::RESOLVED_REFS, String::new()),
+                ),
+
+                Some(Ok(InboundParentRef::Server {
+                    namespace: Some(server_ns),
+                    name: server,
+                    ..
+                })) if !self.permits_foreign_parent(kind, ns, name, &server_ns, &server) => {
+                    let message = format!(
+                        "not permitted to reference Server {} in namespace {}",
+                        server, server_ns
+                    );
+                    (
+                        (false, reasons::NOT_ALLOWED_BY_PARENT, message.clone()),
+                        (false, reasons::REF_NOT_PERMITTED, message),
+                    )
+                }
+
+                Some(Ok(InboundParentRef::Server {
+                    name, namespace, ..
+                })) => {
+                    let server_ns = namespace.as_deref().unwrap_or(ns);
+                    let found = self
+                        .namespaces
+                        .get(server_ns)
+                        .map_or(false, |n| n.servers.contains_key(&name));
+                    let accepted = if !found {
+                        (
+                            false,
+                            reasons::NO_MATCHING_PARENT,
+                            format!("Server {} not found", name),
+                        )
+                    } else if let Err(error) = route.rules.as_ref() {
+                        (false, reasons::UNSUPPORTED_VALUE, error.clone())
+                    } else {
+                        (true, reasons::ACCEPTED, String::new())
+                    };
+                    (accepted, (true, reasons::RESOLVED_REFS, String::new()))
+                }
+            };
+
+        vec![
+            route.condition(conditions::ACCEPTED, accepted, parent_ref),
+            route.condition(conditions::RESOLVED_REFS, resolved, parent_ref),
+        ]
+    }
+
+    /// Indicates whether a route may reference a parent `Server` in another
+    /// namespace. Only HTTPRoutes may reference servers in other namespaces,
+    /// and a `ReferenceGrant` in the server's namespace must permit it.
+    fn permits_foreign_parent(
+        &self,
+        kind: Kind,
+        ns: &str,
+        name: &str,
+        server_ns: &str,
+        server: &str,
+    ) -> bool {
+        let group = match kind {
+            Kind::GatewayHttpRoute => "gateway.networking.k8s.io",
+            Kind::LinkerdHttpRoute => "policy.linkerd.io",
+            _ => return false,
+        };
+        let from = reference_grant::Reference {
+            group,
+            kind: "HTTPRoute",
+            namespace: ns,
+            name,
+        };
+        let to = reference_grant::Reference {
+            group: "policy.linkerd.io",
+            kind: "Server",
+            namespace: server_ns,
+            name: server,
+        };
+        self.reference_grants.permits(from, to)
+    }
+
+    fn send(&self, id: Id, patch: Option<serde_json::Value>) {
+        tracing::debug!(?id, "Publishing status update");
+        tracing::trace!(?patch);
+        if self.updates.send(Update { id, patch }).is_err() {
+            tracing::warn!("Status controller has stopped; dropping update");
+        }
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s_gateway_api::HttpRoute> for Index {
+    fn apply(&mut self, route: k8s_gateway_api::HttpRoute) {
+        let ns = route.namespace().expect("HttpRoute must have a namespace");
+        let name = route.name_unchecked();
+        let generation = route.metadata.generation;
+        let rules = InboundRouteBinding::try_gateway_route(route.spec.hostnames, route.spec.rules)
+            .map(|_| ())
+            .map_err(|e| e.to_string());
+        let route = HttpRoute::new(
+            generation,
+            Some(&ns),
+            route.spec.inner.parent_refs,
+            rules,
+            route.status.map(|s| s.inner.parents),
+        );
+        self.apply_route(Kind::GatewayHttpRoute, ns, name, route)
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        self.delete_route(Kind::GatewayHttpRoute, ns, name)
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::policy::HttpRoute> for Index {
+    fn apply(&mut self, route: k8s::policy::HttpRoute) {
+        let ns = route.namespace().expect("HttpRoute must have a namespace");
+        let name = route.name_unchecked();
+        let generation = route.metadata.generation;
+        let rules = InboundRouteBinding::try_policy_route(route.spec.hostnames, route.spec.rules)
+            .map(|_| ())
+            .map_err(|e| e.to_string());
+        let route = HttpRoute::new(
+            generation,
+            Some(&ns),
+            route.spec.inner.parent_refs,
+            rules,
+            route
+                .status
+                .map(|policy::HttpRouteStatus { inner }| inner.parents),
+        );
+        self.apply_route(Kind::LinkerdHttpRoute, ns, name, route)
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        self.delete_route(Kind::LinkerdHttpRoute, ns, name)
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::gateway::GrpcRoute> for Index {
+    fn apply(&mut self, route: k8s::gateway::GrpcRoute) {
+        let ns = route.namespace().expect("GrpcRoute must have a namespace");
+        let name = route.name_unchecked();
+        let generation = route.metadata.generation;
+        let rules = InboundGrpcRouteBinding::check_parents(
+            Some(&ns),
+            route.spec.inner.parent_refs.iter().flatten(),
+        )
+        .and_then(|()| InboundGrpcRouteBinding::try_route(route.spec.hostnames, route.spec.rules))
+        .map(|_| ())
+        .map_err(|e| e.to_string());
+        let route = HttpRoute::new(
+            generation,
+            Some(&ns),
+            route.spec.inner.parent_refs,
+            rules,
+            route.status.map(|s| s.inner.parents),
+        );
+        self.apply_route(Kind::GrpcRoute, ns, name, route)
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        self.delete_route(Kind::GrpcRoute, ns, name)
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::Pod> for Index {
+    fn apply(&mut self, pod: k8s::Pod) {
+        let ns = pod.namespace().expect("pod must be namespaced");
+        let name = pod.name_unchecked();
+        let _span = info_span!("apply", %ns, %name).entered();
+
+        let pod = Workload {
+            port_names: pod::tcp_port_names(pod.spec),
+            labels: pod.metadata.labels.into(),
+        };
+        if self.apply_workload(ns.clone(), name, pod, |ns| &mut ns.pods) {
+            self.reconcile_servers(&ns);
+        }
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        let _span = info_span!("delete", %ns, %name).entered();
+
+        if self.delete_workload(ns.clone(), &name, |ns| &mut ns.pods) {
+            self.reconcile_servers(&ns);
+        }
+    }
+
+    fn reset(&mut self, pods: Vec<k8s::Pod>, deleted: HashMap<String, HashSet<String>>) {
+        let _span = info_span!("reset").entered();
+
+        // Index all pods before recomputing server statuses so that conflicts
+        // are only computed once per namespace.
+        let mut changed = HashSet::<String>::default();
+        for pod in pods.into_iter() {
+            let ns = pod.namespace().expect("pod must be namespaced");
+            let name = pod.name_unchecked();
+            let pod = Workload {
+                port_names: pod::tcp_port_names(pod.spec),
+                labels: pod.metadata.labels.into(),
+            };
+            if self.apply_workload(ns.clone(), name, pod, |ns| &mut ns.pods) {
+                changed.insert(ns);
+            }
+        }
+        for (ns, names) in deleted.into_iter() {
+            for name in names.into_iter() {
+                if self.delete_workload(ns.clone(), &name, |ns| &mut ns.pods) {
+                    changed.insert(ns.clone());
+                }
+            }
+        }
+
+        if !self.pods_synced {
+            self.pods_synced = true;
+            for ns in self.namespaces.keys() {
+                self.reconcile_servers(ns);
+            }
+            return;
+        }
+
+        for ns in changed.iter() {
+            self.reconcile_servers(ns);
+        }
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::workload::ExternalWorkload> for Index {
+    fn apply(&mut self, workload: k8s::workload::ExternalWorkload) {
+        let ns = workload
+            .namespace()
+            .expect("ExternalWorkload must be namespaced");
+        let name = workload.name_unchecked();
+        let _span = info_span!("apply", %ns, %name).entered();
+
+        let workload = Workload {
+            port_names: pod::external_workload_port_names(&workload.spec),
+            labels: workload.metadata.labels.into(),
+        };
+        if self.apply_workload(ns.clone(), name, workload, |ns| &mut ns.external_workloads) {
+            self.reconcile_servers(&ns);
+        }
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        let _span = info_span!("delete", %ns, %name).entered();
+
+        if self.delete_workload(ns.clone(), &name, |ns| &mut ns.external_workloads) {
+            self.reconcile_servers(&ns);
+        }
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::policy::Server> for Index {
+    fn apply(&mut self, srv: k8s::policy::Server) {
+        let ns = srv.namespace().expect("server must be namespaced");
+        let name = srv.name_unchecked();
+        let _span = info_span!("apply", %ns, %name).entered();
+
+        let server = Server {
+            generation: srv.metadata.generation,
+            created_at: srv.metadata.creation_timestamp.map(|k8s::Time(t)| t),
+            pod_selector: srv.spec.pod_selector,
+            port_ref: srv.spec.port,
+            conditions: srv.status.map(|s| s.conditions).unwrap_or_default(),
+        };
+        let created = self
+            .namespaces
+            .entry(ns.clone())
+            .or_default()
+            .servers
+            .insert(name, server)
+            .is_none();
+
+        // Routes and policies only depend on the existence of servers.
+        if created {
+            self.reconcile_all();
+        }
+        self.reconcile_servers(&ns);
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        let _span = info_span!("delete", %ns, %name).entered();
+
+        let removed = match self.namespaces.entry(ns.clone()) {
+            Entry::Occupied(mut entry) => {
+                let removed = entry.get_mut().servers.remove(&name).is_some();
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+                removed
+            }
+            Entry::Vacant(_) => false,
+        };
+
+        if removed {
+            self.send(
+                Id {
+                    kind: Kind::Server,
+                    namespace: ns.clone(),
+                    name,
+                },
+                None,
+            );
+            self.reconcile_all();
+            self.reconcile_servers(&ns);
+        }
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::policy::AuthorizationPolicy> for Index {
+    fn apply(&mut self, policy: k8s::policy::AuthorizationPolicy) {
+        let ns = policy
+            .namespace()
+            .expect("AuthorizationPolicy must have a namespace");
+        let name = policy.name_unchecked();
+        let policy = AuthorizationPolicy {
+            generation: policy.metadata.generation,
+            spec: authorization_policy::Spec::try_from(policy.spec).map_err(|e| e.to_string()),
+            conditions: policy.status.map(|s| s.conditions).unwrap_or_default(),
+        };
+        self.apply_policy(ns, name, policy)
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        self.delete_policy(ns, name)
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::policy::MeshTLSAuthentication> for Index {
+    fn apply(&mut self, authn: k8s::policy::MeshTLSAuthentication) {
+        let ns = authn
+            .namespace()
+            .expect("MeshTLSAuthentication must have a namespace");
+        let name = authn.name_unchecked();
+        let _span = info_span!("apply", %ns, %name).entered();
+
+        if self.update_authentication(ns, |ns| ns.meshtls_authentications.insert(name)) {
+            self.reconcile_all_policies();
+        }
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        let _span = info_span!("delete", %ns, %name).entered();
+
+        if self.update_authentication(ns, |ns| ns.meshtls_authentications.remove(&name)) {
+            self.reconcile_all_policies();
+        }
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::policy::NetworkAuthentication> for Index {
+    fn apply(&mut self, authn: k8s::policy::NetworkAuthentication) {
+        let ns = authn
+            .namespace()
+            .expect("NetworkAuthentication must have a namespace");
+        let name = authn.name_unchecked();
+        let _span = info_span!("apply", %ns, %name).entered();
+
+        if self.update_authentication(ns, |ns| ns.network_authentications.insert(name)) {
+            self.reconcile_all_policies();
+        }
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        let _span = info_span!("delete", %ns, %name).entered();
+
+        if self.update_authentication(ns, |ns| ns.network_authentications.remove(&name)) {
+            self.reconcile_all_policies();
+        }
+    }
+}
+
+impl kubert::index::IndexNamespacedResource<k8s::gateway::ReferenceGrant> for Index {
+    fn apply(&mut self, grant: k8s::gateway::ReferenceGrant) {
+        let ns = grant
+            .namespace()
+            .expect("ReferenceGrant must have a namespace");
+        let name = grant.name_unchecked();
+        let _span = info_span!("apply", %ns, %name).entered();
+
+        let spec = reference_grant::Spec::from_resource(grant);
+        if self.reference_grants.update(ns, name, spec) {
+            self.reconcile_all();
+        }
+    }
+
+    fn delete(&mut self, ns: String, name: String) {
+        let _span = info_span!("delete", %ns, %name).entered();
+
+        if self.reference_grants.delete(ns, &name) {
+            self.reconcile_all();
+        }
+    }
+}
+
+// === impl Namespace ===
+
+impl Namespace {
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.pods.is_empty()
+            && self.external_workloads.is_empty()
+            && self.servers.is_empty()
+            && self.http_routes.is_empty()
+            && self.authorization_policies.is_empty()
+            && self.meshtls_authentications.is_empty()
+            && self.network_authentications.is_empty()
+    }
+
+    /// Finds all pod and external workload ports that are selected by multiple
+    /// servers, indexed by the name of each server that does not take
+    /// precedence.
+    fn server_conflicts(&self) -> HashMap<&str, Vec<ServerConflict<'_>>> {
+        let mut servers = self.servers.iter().collect::<Vec<_>>();
+        servers.sort_by(|(a_name, a), (b_name, b)| {
+            server::cmp_precedence(
+                (a_name, a.created_at.as_ref()),
+                (b_name, b.created_at.as_ref()),
+            )
+        });
+
+        let mut conflicts = HashMap::<&str, Vec<ServerConflict<'_>>>::default();
+        let workloads = self.pods.iter().map(|(name, w)| ("pod", name, w)).chain(
+            self.external_workloads
+                .iter()
+                .map(|(name, w)| ("external workload", name, w)),
+        );
+        for (kind, workload_name, workload) in workloads {
+            let mut selected = pod::PortMap::<&str>::default();
+            for (srv_name, srv) in servers.iter() {
+                if !srv.pod_selector.matches(&workload.labels) {
+                    continue;
+                }
+                for port in server::select_ports(&srv.port_ref, &workload.port_names).into_iter() {
+                    match selected.entry(port) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(srv_name);
+                        }
+                        Entry::Occupied(entry) => {
+                            conflicts.entry(srv_name).or_default().push(ServerConflict {
+                                kind,
+                                workload: workload_name,
+                                port,
+                                winner: *entry.get(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+// === impl HttpRoute ===
+
+impl HttpRoute {
+    fn new(
+        generation: Option<i64>,
+        ns: Option<&str>,
+        parent_refs: Option<Vec<k8s_gateway_api::ParentReference>>,
+        rules: Result<(), String>,
+        statuses: Option<Vec<k8s_gateway_api::RouteParentStatus>>,
+    ) -> Self {
+        let parents = parent_refs
+            .into_iter()
+            .flatten()
+            .filter(|p| InboundParentRef::from_parent_ref(ns, p.clone()).is_some())
+            .collect();
+        Self {
+            generation,
+            parents,
+            rules,
+            statuses: statuses.unwrap_or_default(),
+        }
+    }
+
+    /// Builds a condition, preserving the observed transition time if the
+    /// condition's status has not changed.
+    fn condition(
+        &self,
+        type_: &str,
+        (status, reason, message): (bool, &str, String),
+        parent_ref: &k8s_gateway_api::ParentReference,
+    ) -> Condition {
+        let observed = self
+            .statuses
+            .iter()
+            .filter(|s| s.controller_name == POLICY_CONTROLLER_NAME && s.parent_ref == *parent_ref)
+            .flat_map(|s| s.conditions.iter());
+        mk_condition(observed, self.generation, type_, (status, reason, message))
+    }
+}
+
+// === impl Server ===
+
+impl Server {
+    /// The maximum number of conflicts described in a condition message.
+    const MAX_CONFLICTS: usize = 10;
+
+    /// Returns a status patch for the server if its desired conditions differ
+    /// from its observed conditions.
+    fn patch(&self, conflicts: &[ServerConflict<'_>]) -> Option<serde_json::Value> {
+        let conflicted = if conflicts.is_empty() {
+            (false, reasons::NO_CONFLICTS, String::new())
+        } else {
+            // Sort the conflicts so that the message is stable.
+            let mut descriptions = conflicts
+                .iter()
+                .map(
+                    |ServerConflict {
+                         kind,
+                         workload,
+                         port,
+                         winner,
+                     }| {
+                        format!(
+                            "port {} on {} {} is already selected by Server {}",
+                            port, kind, workload, winner
+                        )
+                    },
+                )
+                .collect::<Vec<_>>();
+            descriptions.sort();
+            let mut message = descriptions
+                .iter()
+                .take(Self::MAX_CONFLICTS)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("; ");
+            if descriptions.len() > Self::MAX_CONFLICTS {
+                message = format!(
+                    "{}; and {} more",
+                    message,
+                    descriptions.len() - Self::MAX_CONFLICTS
+                );
+            }
+            (true, reasons::PORT_CONFLICT, message)
+        };
+
+        let desired = vec![mk_condition(
+            self.conditions.iter(),
+            self.generation,
+            conditions::CONFLICTED,
+            conflicted,
+        )];
+        if desired == self.conditions {
+            tracing::trace!("Status is up-to-date");
+            return None;
+        }
+
+        let status = k8s::policy::ServerStatus {
+            conditions: desired,
+        };
+        Some(serde_json::json!({ "status": status }))
+    }
+}
+
+// === impl AuthorizationPolicy ===
+
+impl AuthorizationPolicy {
+    /// Builds a condition, preserving the observed transition time if the
+    /// condition's status has not changed.
+    fn condition(&self, type_: &str, (status, reason, message): (bool, &str, String)) -> Condition {
+        mk_condition(
+            self.conditions.iter(),
+            self.generation,
+            type_,
+            (status, reason, message),
+        )
+    }
+}
+
+fn mk_condition<'c>(
+    observed: impl IntoIterator<Item = &'c Condition>,
+    generation: Option<i64>,
+    type_: &str,
+    (status, reason, message): (bool, &str, String),
+) -> Condition {
+    let status = if status { "True" } else { "False" };
+    let last_transition_time = observed
+        .into_iter()
+        .find(|c| c.type_ == type_ && c.status == status)
+        .map(|c| c.last_transition_time.clone())
+        .unwrap_or_else(|| Time(Utc::now()));
+
+    Condition {
+        last_transition_time,
+        message,
+        observed_generation: generation,
+        reason: reason.to_string(),
+        status: status.to_string(),
+        type_: type_.to_string(),
+    }
+}
@@ -1,14 +1,20 @@
 mod annotation;
 mod authorization_policy;
+mod cluster_networks;
+mod external_workload;
+mod grpc_routes;
 mod http_routes;
+mod metrics;
+mod outbound;
 mod server_authorization;
+mod status;
 
 use crate::{defaults::DefaultPolicy, index::*, server_authorization::ServerSelector, ClusterInfo};
 use ahash::AHashMap as HashMap;
 use kubert::index::IndexNamespacedResource;
 use linkerd_policy_controller_core::{
-    AuthorizationRef, ClientAuthentication, ClientAuthorization, IdentityMatch, InboundServer,
-    IpNet, Ipv4Net, Ipv6Net, NetworkMatch, ProxyProtocol, ServerRef,
+    AuthorizationAction, AuthorizationRef, ClientAuthentication, ClientAuthorization,
+    IdentityMatch, InboundServer, IpNet, Ipv4Net, Ipv6Net, NetworkMatch, ProxyProtocol, ServerRef,
 };
 use linkerd_policy_controller_k8s_api::{
     self as k8s,
@@ -28,6 +34,169 @@ fn pod_must_exist_for_lookup() {
         .expect_err("pod-0.ns-0 must not exist");
 }
 
+#[test]
+fn pod_server_lookup() {
+    let test = TestConfig::default();
+    assert_eq!(
+        test.index
+            .read()
+            .pod_server("ns-0", "pod-0", 8080.try_into().unwrap()),
+        None,
+        "pod-0.ns-0 must not exist",
+    );
+
+    test.index
+        .write()
+        .apply(mk_pod("ns-0", "pod-0", Some(("container-0", None))));
+    assert_eq!(
+        test.index
+            .read()
+            .pod_server("ns-0", "pod-0", 8080.try_into().unwrap()),
+        Some(test.default_server()),
+    );
+
+    let srv = mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        None,
+        None,
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    );
+    test.index.write().apply(srv);
+    let server = test
+        .index
+        .read()
+        .pod_server("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert_eq!(server.reference, ServerRef::Server("srv-8080".to_string()));
+    assert_eq!(
+        Some(server),
+        test.index
+            .write()
+            .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+            .ok()
+            .map(|rx| rx.borrow().clone()),
+    );
+}
+
+#[test]
+fn conflicting_servers_use_precedence() {
+    // The oldest server wins, regardless of the order in which servers are
+    // indexed.
+    let t0 = k8s::chrono::Utc::now();
+    let t1 = t0 + k8s::chrono::Duration::seconds(1);
+    let mk_srv = |name: &str, created_at| {
+        let mut srv = mk_server(
+            "ns-0",
+            name,
+            Port::Number(8080.try_into().unwrap()),
+            None,
+            Some(("app", "app-0")),
+            Some(k8s::policy::server::ProxyProtocol::Http1),
+        );
+        srv.metadata.creation_timestamp = Some(k8s::Time(created_at));
+        srv
+    };
+
+    for (first, second) in [("srv-a", "srv-b"), ("srv-b", "srv-a")] {
+        let test = TestConfig::default();
+
+        let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+        pod.labels_mut()
+            .insert("app".to_string(), "app-0".to_string());
+        test.index.write().apply(pod);
+
+        let rx = test
+            .index
+            .write()
+            .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+            .expect("pod-0.ns-0 should exist");
+
+        let created_at = |name| if name == "srv-b" { t0 } else { t1 };
+        test.index.write().apply(mk_srv(first, created_at(first)));
+        test.index.write().apply(mk_srv(second, created_at(second)));
+        assert_eq!(
+            rx.borrow().reference,
+            ServerRef::Server("srv-b".to_string()),
+            "{} applied before {}",
+            first,
+            second,
+        );
+    }
+}
+
+#[test]
+fn conflicting_servers_with_same_timestamp_use_name() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+
+    for name in ["srv-c", "srv-a", "srv-b"] {
+        test.index.write().apply(mk_server(
+            "ns-0",
+            name,
+            Port::Number(8080.try_into().unwrap()),
+            None,
+            Some(("app", "app-0")),
+            Some(k8s::policy::server::ProxyProtocol::Http1),
+        ));
+    }
+    assert_eq!(
+        rx.borrow().reference,
+        ServerRef::Server("srv-a".to_string())
+    );
+}
+
+#[test]
+fn server_selects_port_range() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-range",
+        Port::Range("9000-9019".parse().unwrap()),
+        None,
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Opaque),
+    ));
+
+    for port in [9000, 9010, 9019] {
+        let rx = test
+            .index
+            .write()
+            .pod_server_rx("ns-0", "pod-0", port.try_into().unwrap())
+            .expect("pod-0.ns-0 should exist");
+        assert_eq!(
+            rx.borrow().reference,
+            ServerRef::Server("srv-range".to_string()),
+            "port {}",
+            port,
+        );
+    }
+
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 9020.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert_eq!(*rx.borrow(), test.default_server());
+}
+
 struct TestConfig {
     index: SharedIndex,
     detect_timeout: time::Duration,
@@ -36,8 +205,9 @@ struct TestConfig {
     _tracing: tracing::subscriber::DefaultGuard,
 }
 
-const DEFAULTS: [DefaultPolicy; 5] = [
+const DEFAULTS: [DefaultPolicy; 6] = [
     DefaultPolicy::Deny,
+    DefaultPolicy::Audit,
     DefaultPolicy::Allow {
         authenticated_only: true,
         cluster_only: false,
@@ -82,6 +252,16 @@ fn mk_pod(
     }
 }
 
+fn mk_namespace(name: impl ToString) -> k8s::Namespace {
+    k8s::Namespace {
+        metadata: k8s::ObjectMeta {
+            name: Some(name.to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
 fn mk_server(
     ns: impl ToString,
     name: impl ToString,
@@ -107,6 +287,7 @@ fn mk_server(
             pod_selector: pod_labels.into_iter().collect(),
             proxy_protocol,
         },
+        status: None,
     }
 }
 
@@ -122,12 +303,21 @@ fn mk_default_policy(
 
     match da {
         DefaultPolicy::Deny => None,
+        DefaultPolicy::Audit => Some((
+            AuthorizationRef::Default("audit".to_string()),
+            ClientAuthorization {
+                action: AuthorizationAction::Audit,
+                authentication: ClientAuthentication::Unauthenticated,
+                networks: all_nets,
+            },
+        )),
         DefaultPolicy::Allow {
             authenticated_only: true,
             cluster_only: false,
         } => Some((
             AuthorizationRef::Default("all-authenticated".to_string()),
             ClientAuthorization {
+                action: AuthorizationAction::Allow,
                 authentication: authed,
                 networks: all_nets,
             },
@@ -138,6 +328,7 @@ fn mk_default_policy(
         } => Some((
             AuthorizationRef::Default("all-unauthenticated".to_string()),
             ClientAuthorization {
+                action: AuthorizationAction::Allow,
                 authentication: ClientAuthentication::Unauthenticated,
                 networks: all_nets,
             },
@@ -148,6 +339,7 @@ fn mk_default_policy(
         } => Some((
             AuthorizationRef::Default("cluster-authenticated".to_string()),
             ClientAuthorization {
+                action: AuthorizationAction::Allow,
                 authentication: authed,
                 networks: cluster_nets,
             },
@@ -158,6 +350,7 @@ fn mk_default_policy(
         } => Some((
             AuthorizationRef::Default("cluster-unauthenticated".to_string()),
             ClientAuthorization {
+                action: AuthorizationAction::Allow,
                 authentication: ClientAuthentication::Unauthenticated,
                 networks: cluster_nets,
             },
@@ -174,8 +367,10 @@ impl TestConfig {
         let detect_timeout = time::Duration::from_secs(1);
         let cluster = ClusterInfo {
             networks: vec![cluster_net],
+            service_networks: vec![],
             control_plane_ns: "linkerd".to_string(),
             identity_domain: "cluster.example.com".into(),
+            dns_domain: "cluster.example.com".into(),
             default_policy,
             default_detect_timeout: detect_timeout,
         };
@@ -197,6 +392,7 @@ impl TestConfig {
                 timeout: self.detect_timeout,
             },
             http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
         }
     }
 
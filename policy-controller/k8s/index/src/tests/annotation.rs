@@ -112,6 +112,7 @@ fn authenticated_annotated() {
                     authenticated_only: true,
                 },
                 DefaultPolicy::Deny => DefaultPolicy::Deny,
+                DefaultPolicy::Audit => DefaultPolicy::Audit,
             };
             InboundServer {
                 reference: ServerRef::Default(policy.to_string()),
@@ -120,6 +121,7 @@ fn authenticated_annotated() {
                     timeout: test.detect_timeout,
                 },
                 http_routes: HashMap::default(),
+                grpc_routes: HashMap::default(),
             }
         };
 
@@ -131,3 +133,132 @@ fn authenticated_annotated() {
         assert_eq!(*rx.borrow(), config);
     }
 }
+
+/// Tests that a namespace's default policy annotation applies to pods that are
+/// not annotated, and that pod annotations take precedence.
+#[test]
+fn default_policy_namespace_annotated() {
+    for default in &DEFAULTS {
+        let test = TestConfig::from_default_policy(match *default {
+            // Invert default to ensure override applies.
+            DefaultPolicy::Deny => DefaultPolicy::Allow {
+                authenticated_only: false,
+                cluster_only: false,
+            },
+            _ => DefaultPolicy::Deny,
+        });
+
+        let pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+        test.index.write().apply(pod.clone());
+
+        let mut rx = test
+            .index
+            .write()
+            .pod_server_rx("ns-0", "pod-0", 2222.try_into().unwrap())
+            .expect("pod-0.ns-0 should exist");
+        assert_eq!(
+            rx.borrow_and_update().reference,
+            ServerRef::Default(test.default_policy.to_string()),
+        );
+
+        // Annotate the namespace and check that the pod's watch is updated
+        // with the namespace's default.
+        let mut ns = mk_namespace("ns-0");
+        ns.annotations_mut().insert(
+            "config.linkerd.io/default-inbound-policy".into(),
+            default.to_string(),
+        );
+        kubert::index::IndexClusterResource::apply(&mut *test.index.write(), ns.clone());
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(
+            rx.borrow_and_update().reference,
+            ServerRef::Default(default.to_string())
+        );
+
+        // Annotate the pod with the cluster default and check that it takes
+        // precedence over the namespace's annotation.
+        let mut pod = pod;
+        pod.annotations_mut().insert(
+            "config.linkerd.io/default-inbound-policy".into(),
+            test.default_policy.to_string(),
+        );
+        test.index.write().apply(pod);
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(
+            rx.borrow_and_update().reference,
+            ServerRef::Default(test.default_policy.to_string())
+        );
+
+        // Changes to the namespace no longer affect the pod.
+        ns.annotations_mut().clear();
+        kubert::index::IndexClusterResource::apply(&mut *test.index.write(), ns);
+        assert!(!rx.has_changed().unwrap());
+    }
+}
+
+/// Tests that removing a namespace's annotations restores the cluster default.
+#[test]
+fn default_policy_namespace_deleted() {
+    let test = TestConfig::from_default_policy(DefaultPolicy::Deny);
+
+    let mut ns = mk_namespace("ns-0");
+    ns.annotations_mut().insert(
+        "config.linkerd.io/default-inbound-policy".into(),
+        "all-unauthenticated".into(),
+    );
+    kubert::index::IndexClusterResource::apply(&mut *test.index.write(), ns);
+
+    let pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    test.index.write().apply(pod);
+
+    let mut rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 2222.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert_eq!(
+        rx.borrow_and_update().reference,
+        ServerRef::Default("all-unauthenticated".to_string()),
+    );
+
+    kubert::index::IndexClusterResource::<k8s::Namespace>::delete(
+        &mut *test.index.write(),
+        "ns-0".to_string(),
+    );
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(*rx.borrow(), test.default_server());
+}
+
+#[test]
+fn opaque_namespace_annotated() {
+    let test = TestConfig::default();
+
+    let mut ns = mk_namespace("ns-0");
+    ns.annotations_mut()
+        .insert("config.linkerd.io/opaque-ports".into(), "2222".into());
+    kubert::index::IndexClusterResource::apply(&mut *test.index.write(), ns);
+
+    let p = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    test.index.write().reset(vec![p], Default::default());
+
+    let mut server = test.default_server();
+    server.protocol = ProxyProtocol::Opaque;
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 2222.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert_eq!(*rx.borrow(), server);
+
+    // Pod annotations override the namespace's opaque ports.
+    let mut p = mk_pod("ns-0", "pod-1", Some(("container-0", None)));
+    p.annotations_mut()
+        .insert("config.linkerd.io/opaque-ports".into(), "3333".into());
+    test.index.write().apply(p);
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-1", 2222.try_into().unwrap())
+        .expect("pod-1.ns-0 should exist");
+    assert_eq!(*rx.borrow(), test.default_server());
+}
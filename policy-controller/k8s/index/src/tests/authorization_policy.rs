@@ -1,4 +1,5 @@
 use super::*;
+use linkerd_policy_controller_core::evaluate;
 
 #[test]
 fn links_authorization_policy_with_mtls_name() {
@@ -32,10 +33,12 @@ fn links_authorization_policy_with_mtls_name() {
             authorizations: Default::default(),
             protocol: ProxyProtocol::Http1,
             http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
         },
     );
 
     let authz = ClientAuthorization {
+        action: AuthorizationAction::Allow,
         networks: vec!["10.0.0.0/8".parse::<IpNet>().unwrap().into()],
         authentication: ClientAuthentication::TlsAuthenticated(vec![IdentityMatch::Exact(
             "foo.bar".to_string(),
@@ -86,6 +89,7 @@ fn links_authorization_policy_with_mtls_name() {
             .collect(),
             protocol: ProxyProtocol::Http1,
             http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
         },
     );
 }
@@ -122,10 +126,12 @@ fn authorization_targets_namespace() {
             authorizations: Default::default(),
             protocol: ProxyProtocol::Http1,
             http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
         },
     );
 
     let authz = ClientAuthorization {
+        action: AuthorizationAction::Allow,
         networks: vec!["10.0.0.0/8".parse::<IpNet>().unwrap().into()],
         authentication: ClientAuthentication::TlsAuthenticated(vec![IdentityMatch::Exact(
             "foo.bar".to_string(),
@@ -176,6 +182,7 @@ fn authorization_targets_namespace() {
             .collect(),
             protocol: ProxyProtocol::Http1,
             http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
         },
     );
 }
@@ -212,10 +219,12 @@ fn links_authorization_policy_with_service_account() {
             authorizations: Default::default(),
             protocol: ProxyProtocol::Http1,
             http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
         },
     );
 
     let authz = ClientAuthorization {
+        action: AuthorizationAction::Allow,
         networks: vec!["10.0.0.0/8".parse::<IpNet>().unwrap().into()],
         authentication: ClientAuthentication::TlsAuthenticated(vec![IdentityMatch::Exact(
             "foo.ns-0.serviceaccount.identity.linkerd.cluster.example.com".to_string(),
@@ -260,11 +269,374 @@ fn links_authorization_policy_with_service_account() {
             .collect(),
             protocol: ProxyProtocol::Http1,
             http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
         },
     );
 }
 
-fn mk_authorization_policy(
+#[test]
+fn authorization_policy_selects_servers_by_label() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-admin",
+        Port::Number(9990.try_into().unwrap()),
+        Some(("tier", "admin")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-app",
+        Port::Number(8080.try_into().unwrap()),
+        Some(("tier", "app")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+
+    let mut policy = mk_authorization_policy(
+        "ns-0",
+        "authz-admin",
+        None::<String>,
+        Some(NamespacedTargetRef {
+            group: None,
+            kind: "ServiceAccount".to_string(),
+            namespace: Some("ns-0".to_string()),
+            name: "foo".to_string(),
+        }),
+    );
+    policy.spec.target_ref = None;
+    policy.spec.target_selector = Some(Some(("tier", "admin")).into_iter().collect());
+    test.index.write().apply(policy);
+
+    let authz = AuthorizationRef::AuthorizationPolicy("authz-admin".to_string());
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 9990.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert_eq!(
+        rx.borrow().reference,
+        ServerRef::Server("srv-admin".to_string())
+    );
+    assert!(rx.borrow().authorizations.contains_key(&authz));
+
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert_eq!(
+        rx.borrow().reference,
+        ServerRef::Server("srv-app".to_string())
+    );
+    assert!(!rx.borrow().authorizations.contains_key(&authz));
+}
+
+#[test]
+fn authorization_policy_with_deny_action() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        None,
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+
+    // Deny policies may refuse clients by identity.
+    let mut policy = mk_authorization_policy(
+        "ns-0",
+        "deny-foo",
+        Some("srv-8080"),
+        Some(NamespacedTargetRef {
+            group: None,
+            kind: "ServiceAccount".to_string(),
+            namespace: Some("ns-0".to_string()),
+            name: "foo".to_string(),
+        }),
+    );
+    policy.spec.action = Some(k8s::policy::AuthorizationAction::Deny);
+    test.index.write().apply(policy);
+    let authz = ClientAuthorization {
+        action: AuthorizationAction::Deny,
+        networks: vec![
+            NetworkMatch {
+                net: Ipv4Net::default().into(),
+                except: vec![],
+            },
+            NetworkMatch {
+                net: Ipv6Net::default().into(),
+                except: vec![],
+            },
+        ],
+        authentication: ClientAuthentication::TlsAuthenticated(vec![IdentityMatch::Exact(
+            "foo.ns-0.serviceaccount.identity.linkerd.cluster.example.com".to_string(),
+        )]),
+    };
+    assert_eq!(
+        rx.borrow()
+            .authorizations
+            .get(&AuthorizationRef::AuthorizationPolicy(
+                "deny-foo".to_string()
+            )),
+        Some(&authz),
+    );
+
+    let mut policy = mk_authorization_policy(
+        "ns-0",
+        "deny-foo",
+        Some("srv-8080"),
+        Some(NamespacedTargetRef {
+            group: Some("policy.linkerd.io".to_string()),
+            kind: "NetworkAuthentication".to_string(),
+            namespace: None,
+            name: "net-foo".to_string(),
+        }),
+    );
+    policy.spec.action = Some(k8s::policy::AuthorizationAction::Deny);
+    test.index.write().apply(policy);
+    test.index.write().apply(mk_network_authentication(
+        "ns-0".to_string(),
+        "net-foo".to_string(),
+        vec![k8s::policy::network_authentication::Network {
+            cidr: "10.0.0.0/8".parse().unwrap(),
+            except: Some(vec!["10.1.0.0/16".parse().unwrap()]),
+        }],
+    ));
+
+    let authz = ClientAuthorization {
+        action: AuthorizationAction::Deny,
+        networks: vec![NetworkMatch {
+            net: "10.0.0.0/8".parse().unwrap(),
+            except: vec!["10.1.0.0/16".parse().unwrap()],
+        }],
+        authentication: ClientAuthentication::Unauthenticated,
+    };
+    assert_eq!(
+        rx.borrow()
+            .authorizations
+            .get(&AuthorizationRef::AuthorizationPolicy(
+                "deny-foo".to_string()
+            )),
+        Some(&authz),
+    );
+}
+
+#[test]
+fn authorization_policy_in_audit_mode() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        None,
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+
+    // An allow policy in audit mode permits all other clients on its behalf,
+    // since no other policy is enforced.
+    let mut allow = mk_authorization_policy(
+        "ns-0",
+        "allow-foo",
+        Some("srv-8080"),
+        Some(NamespacedTargetRef {
+            group: None,
+            kind: "ServiceAccount".to_string(),
+            namespace: Some("ns-0".to_string()),
+            name: "foo".to_string(),
+        }),
+    );
+    allow.spec.mode = Some(k8s::policy::AuthorizationMode::Audit);
+    test.index.write().apply(allow);
+
+    let audit = AuthorizationRef::AuditedPolicy("allow-foo".to_string());
+    assert_eq!(
+        rx.borrow()
+            .authorizations
+            .get(&AuthorizationRef::AuthorizationPolicy(
+                "allow-foo".to_string()
+            ))
+            .map(|authz| authz.action),
+        Some(AuthorizationAction::Allow),
+    );
+    assert_eq!(
+        rx.borrow().authorizations.get(&audit),
+        Some(&ClientAuthorization {
+            action: AuthorizationAction::Audit,
+            networks: vec![
+                "0.0.0.0/0".parse::<IpNet>().unwrap().into(),
+                "::/0".parse::<IpNet>().unwrap().into(),
+            ],
+            authentication: ClientAuthentication::Unauthenticated,
+        }),
+    );
+
+    // A deny policy in audit mode flags the clients it would refuse.
+    let mut deny = mk_authorization_policy(
+        "ns-0",
+        "deny-bar",
+        Some("srv-8080"),
+        Some(NamespacedTargetRef {
+            group: Some("policy.linkerd.io".to_string()),
+            kind: "NetworkAuthentication".to_string(),
+            namespace: None,
+            name: "net-bar".to_string(),
+        }),
+    );
+    deny.spec.action = Some(k8s::policy::AuthorizationAction::Deny);
+    deny.spec.mode = Some(k8s::policy::AuthorizationMode::Audit);
+    test.index.write().apply(deny);
+    test.index.write().apply(mk_network_authentication(
+        "ns-0".to_string(),
+        "net-bar".to_string(),
+        vec![k8s::policy::network_authentication::Network {
+            cidr: "10.0.0.0/8".parse().unwrap(),
+            except: None,
+        }],
+    ));
+
+    assert_eq!(
+        rx.borrow()
+            .authorizations
+            .get(&AuthorizationRef::AuthorizationPolicy(
+                "deny-bar".to_string()
+            ))
+            .map(|authz| authz.action),
+        Some(AuthorizationAction::AuditDeny),
+    );
+
+    // Once the allow policy is enforced, other clients are no longer audited.
+    test.index.write().apply(mk_authorization_policy(
+        "ns-0",
+        "allow-foo",
+        Some("srv-8080"),
+        Some(NamespacedTargetRef {
+            group: None,
+            kind: "ServiceAccount".to_string(),
+            namespace: Some("ns-0".to_string()),
+            name: "foo".to_string(),
+        }),
+    ));
+    assert!(!rx.borrow().authorizations.contains_key(&audit));
+}
+
+#[test]
+fn audited_policy_does_not_permit_clients_refused_by_enforced_policy() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        None,
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+
+    test.index.write().apply(mk_authorization_policy(
+        "ns-0",
+        "allow-foo",
+        Some("srv-8080"),
+        Some(NamespacedTargetRef {
+            group: None,
+            kind: "ServiceAccount".to_string(),
+            namespace: Some("ns-0".to_string()),
+            name: "foo".to_string(),
+        }),
+    ));
+    let mut audited = mk_authorization_policy(
+        "ns-0",
+        "allow-bar",
+        Some("srv-8080"),
+        Some(NamespacedTargetRef {
+            group: None,
+            kind: "ServiceAccount".to_string(),
+            namespace: Some("ns-0".to_string()),
+            name: "bar".to_string(),
+        }),
+    );
+    audited.spec.mode = Some(k8s::policy::AuthorizationMode::Audit);
+    test.index.write().apply(audited);
+
+    // Both policies permit their own clients, and all other clients are still
+    // refused by the enforced policy.
+    let server = rx.borrow();
+    let mut actions = server
+        .authorizations
+        .iter()
+        .map(|(reference, authz)| (reference.clone(), authz.action))
+        .collect::<Vec<_>>();
+    actions.sort_by(|(a, _), (b, _)| a.cmp(b));
+    assert_eq!(
+        actions,
+        vec![
+            (
+                AuthorizationRef::AuthorizationPolicy("allow-bar".to_string()),
+                AuthorizationAction::Allow,
+            ),
+            (
+                AuthorizationRef::AuthorizationPolicy("allow-foo".to_string()),
+                AuthorizationAction::Allow,
+            ),
+        ],
+    );
+
+    let nobody = evaluate::Client {
+        addr: "10.1.1.1".parse().unwrap(),
+        tls: true,
+        identity: Some(
+            "nobody.ns-0.serviceaccount.identity.linkerd.cluster.example.com".to_string(),
+        ),
+    };
+    let decision = evaluate::evaluate(&server, &["10.0.0.0/8".parse().unwrap()], &nobody, None);
+    assert_eq!(decision.outcome, evaluate::Outcome::Unauthorized,);
+}
+
+pub(super) fn mk_authorization_policy(
     ns: impl ToString,
     name: impl ToString,
     server: Option<impl ToString>,
@@ -277,7 +649,7 @@ fn mk_authorization_policy(
             ..Default::default()
         },
         spec: k8s::policy::AuthorizationPolicySpec {
-            target_ref: match server {
+            target_ref: Some(match server {
                 Some(server) => LocalTargetRef {
                     group: Some("policy.linkerd.io".to_string()),
                     kind: "Server".to_string(),
@@ -288,13 +660,17 @@ fn mk_authorization_policy(
                     kind: "Namespace".to_string(),
                     name: ns.to_string(),
                 },
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: authns.into_iter().collect(),
+            action: None,
+            mode: None,
         },
+        status: None,
     }
 }
 
-fn mk_meshtls_authentication(
+pub(super) fn mk_meshtls_authentication(
     ns: impl ToString,
     name: impl ToString,
     identities: impl IntoIterator<Item = String>,
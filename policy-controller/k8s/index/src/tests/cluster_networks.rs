@@ -0,0 +1,128 @@
+use super::*;
+
+#[test]
+fn default_server_uses_node_networks() {
+    let default_policy = DefaultPolicy::Allow {
+        authenticated_only: false,
+        cluster_only: true,
+    };
+    let test = TestConfig::from_default_policy(default_policy);
+    let mut networks_rx = test.index.read().cluster_networks_rx();
+
+    let pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    test.index.write().apply(pod);
+    let mut rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 2222.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert_eq!(*rx.borrow_and_update(), test.default_server());
+
+    // When a node reports its pod networks, the default server is updated to
+    // use them instead of the configured networks.
+    let node_net = "10.1.0.0/24".parse::<IpNet>().unwrap();
+    kubert::index::IndexClusterResource::apply(
+        &mut *test.index.write(),
+        mk_node("node-0", vec![node_net]),
+    );
+    assert!(networks_rx.has_changed().unwrap());
+    assert_eq!(**networks_rx.borrow_and_update(), [node_net]);
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(
+        rx.borrow_and_update().authorizations,
+        mk_default_policy(default_policy, vec![node_net]),
+    );
+
+    // Reapplying an unchanged node does not update the networks.
+    kubert::index::IndexClusterResource::apply(
+        &mut *test.index.write(),
+        mk_node("node-0", vec![node_net]),
+    );
+    assert!(!networks_rx.has_changed().unwrap());
+    assert!(!rx.has_changed().unwrap());
+
+    // When no nodes report pod networks, the configured networks are used.
+    kubert::index::IndexClusterResource::<k8s::Node>::delete(
+        &mut *test.index.write(),
+        "node-0".to_string(),
+    );
+    assert!(networks_rx.has_changed().unwrap());
+    assert_eq!(**networks_rx.borrow_and_update(), test.cluster.networks[..]);
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(*rx.borrow(), test.default_server());
+}
+
+#[test]
+fn node_networks_include_service_networks() {
+    let _tracing = TestConfig::init_tracing();
+    let service_net = "10.96.0.0/12".parse::<IpNet>().unwrap();
+    let index = Index::shared(ClusterInfo {
+        networks: vec!["192.0.2.0/24".parse().unwrap()],
+        service_networks: vec![service_net],
+        control_plane_ns: "linkerd".to_string(),
+        identity_domain: "cluster.example.com".into(),
+        dns_domain: "cluster.example.com".into(),
+        default_policy: DefaultPolicy::Deny,
+        default_detect_timeout: time::Duration::from_secs(1),
+    });
+    let mut networks_rx = index.read().cluster_networks_rx();
+
+    let net0 = "10.1.0.0/24".parse::<IpNet>().unwrap();
+    let net1 = "fd00:1::/64".parse::<IpNet>().unwrap();
+    kubert::index::IndexClusterResource::reset(
+        &mut *index.write(),
+        vec![mk_node("node-0", vec![net0]), mk_node("node-1", vec![net1])],
+        Default::default(),
+    );
+    assert!(networks_rx.has_changed().unwrap());
+    assert_eq!(**networks_rx.borrow_and_update(), [net0, service_net, net1]);
+}
+
+#[test]
+fn node_networks_are_aggregated() {
+    let _tracing = TestConfig::init_tracing();
+    let index = Index::shared(ClusterInfo {
+        networks: vec!["192.0.2.0/24".parse().unwrap()],
+        service_networks: vec!["10.96.0.0/12".parse().unwrap()],
+        control_plane_ns: "linkerd".to_string(),
+        identity_domain: "cluster.example.com".into(),
+        dns_domain: "cluster.example.com".into(),
+        default_policy: DefaultPolicy::Deny,
+        default_detect_timeout: time::Duration::from_secs(1),
+    });
+    let mut networks_rx = index.read().cluster_networks_rx();
+
+    // Adjacent node networks are merged, and node networks within the service
+    // networks are subsumed by them.
+    kubert::index::IndexClusterResource::reset(
+        &mut *index.write(),
+        vec![
+            mk_node("node-0", vec!["10.1.0.0/24".parse().unwrap()]),
+            mk_node("node-1", vec!["10.1.1.0/24".parse().unwrap()]),
+            mk_node("node-2", vec!["10.100.0.0/24".parse().unwrap()]),
+        ],
+        Default::default(),
+    );
+    assert!(networks_rx.has_changed().unwrap());
+    assert_eq!(
+        **networks_rx.borrow_and_update(),
+        [
+            "10.1.0.0/23".parse::<IpNet>().unwrap(),
+            "10.96.0.0/12".parse().unwrap(),
+        ]
+    );
+}
+
+fn mk_node(name: impl ToString, pod_networks: Vec<IpNet>) -> k8s::Node {
+    k8s::Node {
+        metadata: k8s::ObjectMeta {
+            name: Some(name.to_string()),
+            ..Default::default()
+        },
+        spec: Some(k8s::NodeSpec {
+            pod_cidrs: Some(pod_networks.iter().map(ToString::to_string).collect()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
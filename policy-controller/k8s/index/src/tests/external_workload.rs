@@ -0,0 +1,91 @@
+use super::*;
+
+#[test]
+fn server_selects_external_workload() {
+    let test = TestConfig::default();
+
+    test.index
+        .write()
+        .apply(mk_external_workload("ns-0", "vm-0", Some(("app", "app-0"))));
+
+    let mut rx = test
+        .index
+        .write()
+        .external_workload_server_rx("ns-0", "vm-0", 8080.try_into().unwrap())
+        .expect("vm-0.ns-0 should exist");
+    assert_eq!(*rx.borrow_and_update(), test.default_server());
+
+    // A pod with the same name is distinct from the external workload.
+    test.index
+        .write()
+        .pod_server_rx("ns-0", "vm-0", 8080.try_into().unwrap())
+        .expect_err("pod vm-0.ns-0 must not exist");
+
+    // The server selects the workload's named port.
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-admin-http",
+        Port::Name("admin-http".to_string()),
+        None,
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(
+        rx.borrow_and_update().reference,
+        ServerRef::Server("srv-admin-http".to_string()),
+    );
+
+    // Relabeling the workload so that it is no longer selected reverts the
+    // port to the default server.
+    test.index
+        .write()
+        .apply(mk_external_workload("ns-0", "vm-0", Some(("app", "app-1"))));
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(*rx.borrow_and_update(), test.default_server());
+
+    <Index as IndexNamespacedResource<k8s::workload::ExternalWorkload>>::delete(
+        &mut test.index.write(),
+        "ns-0".to_string(),
+        "vm-0".to_string(),
+    );
+    test.index
+        .write()
+        .external_workload_server_rx("ns-0", "vm-0", 8080.try_into().unwrap())
+        .expect_err("vm-0.ns-0 must not exist");
+}
+
+pub(super) fn mk_external_workload(
+    ns: impl ToString,
+    name: impl ToString,
+    labels: impl IntoIterator<Item = (&'static str, &'static str)>,
+) -> k8s::workload::ExternalWorkload {
+    use k8s::workload::external_workload::*;
+    ExternalWorkload {
+        metadata: k8s::ObjectMeta {
+            namespace: Some(ns.to_string()),
+            name: Some(name.to_string()),
+            labels: Some(
+                labels
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            ..Default::default()
+        },
+        spec: ExternalWorkloadSpec {
+            mesh_tls: MeshTls {
+                identity: format!("spiffe://cluster.example.com/{}", name.to_string()),
+                server_name: format!("{}.cluster.example.com", name.to_string()),
+            },
+            ports: Some(vec![PortSpec {
+                name: Some("admin-http".to_string()),
+                port: 8080.try_into().unwrap(),
+                protocol: None,
+            }]),
+            workload_ips: Some(vec![WorkloadIp {
+                ip: "192.0.2.10".to_string(),
+            }]),
+        },
+    }
+}
@@ -0,0 +1,215 @@
+use super::*;
+use linkerd_policy_controller_core::grpc_route::{GrpcMethodMatch, GrpcRouteMatch};
+use linkerd_policy_controller_k8s_api::gateway::grpcroute as api;
+
+#[test]
+fn route_attaches_to_grpc_server() {
+    let test = TestConfig::default();
+
+    // Create pod.
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+
+    let mut rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert_eq!(*rx.borrow_and_update(), test.default_server());
+
+    // Create a gRPC server. The gRPC protocol is preserved.
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        Some(("app", "app-0")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Grpc),
+    ));
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(
+        *rx.borrow_and_update(),
+        InboundServer {
+            reference: ServerRef::Server("srv-8080".to_string()),
+            authorizations: Default::default(),
+            protocol: ProxyProtocol::Grpc,
+            http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
+        },
+    );
+
+    // Create route.
+    test.index
+        .write()
+        .apply(mk_grpc_route("ns-0", "route-foo", "srv-8080"));
+    assert!(rx.has_changed().unwrap());
+    {
+        let server = rx.borrow_and_update();
+        assert!(server.http_routes.is_empty());
+        assert_eq!(
+            server.grpc_routes["route-foo"].rules[0].matches,
+            vec![GrpcRouteMatch {
+                method: Some(GrpcMethodMatch {
+                    service: Some("foo.Bar".to_string()),
+                    method: Some("Baz".to_string()),
+                }),
+                headers: vec![],
+            }],
+        );
+    }
+
+    // Create authz policy.
+    test.index.write().apply(k8s::policy::AuthorizationPolicy {
+        metadata: k8s::ObjectMeta {
+            namespace: Some("ns-0".to_string()),
+            name: Some("authz-foo".to_string()),
+            ..Default::default()
+        },
+        spec: k8s::policy::AuthorizationPolicySpec {
+            target_ref: Some(LocalTargetRef {
+                group: Some("gateway.networking.k8s.io".to_string()),
+                kind: "GRPCRoute".to_string(),
+                name: "route-foo".to_string(),
+            }),
+            target_selector: None,
+            required_authentication_refs: vec![NamespacedTargetRef {
+                group: None,
+                kind: "ServiceAccount".to_string(),
+                namespace: Some("ns-0".to_string()),
+                name: "foo".to_string(),
+            }],
+            action: None,
+            mode: None,
+        },
+        status: None,
+    });
+    assert!(rx.has_changed().unwrap());
+    {
+        let server = rx.borrow_and_update();
+        assert!(server.authorizations.is_empty());
+        assert!(server.grpc_routes["route-foo"].authorizations.contains_key(
+            &AuthorizationRef::AuthorizationPolicy("authz-foo".to_string())
+        ));
+    }
+
+    // Deleting the route removes it from the server.
+    <Index as IndexNamespacedResource<k8s::gateway::GrpcRoute>>::delete(
+        &mut test.index.write(),
+        "ns-0".to_string(),
+        "route-foo".to_string(),
+    );
+    assert!(rx.has_changed().unwrap());
+    assert!(rx.borrow_and_update().grpc_routes.is_empty());
+}
+
+#[test]
+fn routes_ranked_by_age_and_name() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        Some(("app", "app-0")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Grpc),
+    ));
+
+    // The older route is ranked first, regardless of name.
+    let t0 = k8s::chrono::Utc::now();
+    for (name, created_at) in [
+        ("route-a", t0 + k8s::chrono::Duration::seconds(1)),
+        ("route-b", t0),
+    ] {
+        let mut route = mk_grpc_route("ns-0", name, "srv-8080");
+        route.metadata.creation_timestamp = Some(k8s::Time(created_at));
+        test.index.write().apply(route);
+    }
+
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    let server = rx.borrow();
+    assert_eq!(server.grpc_routes["route-b"].rank, 0);
+    assert_eq!(server.grpc_routes["route-a"].rank, 1);
+}
+
+#[test]
+fn cross_namespace_route_is_not_indexed() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        Some(("app", "app-0")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Grpc),
+    ));
+
+    let mut rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    rx.borrow_and_update();
+
+    // Only HTTPRoutes may reference a Server in another namespace.
+    let mut route = mk_grpc_route("ns-1", "route-foo", "srv-8080");
+    route.spec.inner.parent_refs.as_mut().unwrap()[0].namespace = Some("ns-0".to_string());
+    test.index.write().apply(route);
+    assert!(!rx.has_changed().unwrap());
+    assert!(rx.borrow().grpc_routes.is_empty());
+}
+
+fn mk_grpc_route(
+    ns: impl ToString,
+    name: impl ToString,
+    server: impl ToString,
+) -> k8s::gateway::GrpcRoute {
+    k8s::gateway::GrpcRoute {
+        metadata: k8s::ObjectMeta {
+            namespace: Some(ns.to_string()),
+            name: Some(name.to_string()),
+            ..Default::default()
+        },
+        spec: api::GrpcRouteSpec {
+            inner: k8s_gateway_api::CommonRouteSpec {
+                parent_refs: Some(vec![k8s_gateway_api::ParentReference {
+                    group: Some("policy.linkerd.io".to_string()),
+                    kind: Some("Server".to_string()),
+                    namespace: None,
+                    name: server.to_string(),
+                    section_name: None,
+                    port: None,
+                }]),
+            },
+            hostnames: None,
+            rules: Some(vec![api::GrpcRouteRule {
+                matches: Some(vec![api::GrpcRouteMatch {
+                    method: Some(api::GrpcMethodMatch {
+                        r#type: Some(api::GrpcMethodMatchType::Exact),
+                        service: Some("foo.Bar".to_string()),
+                        method: Some("Baz".to_string()),
+                    }),
+                    headers: None,
+                }]),
+                filters: None,
+                backend_refs: None,
+            }]),
+        },
+        status: None,
+    }
+}
@@ -43,6 +43,7 @@ fn test_route_attaches_to_server(test: TestConfig, apply_route: impl FnOnce(&mut
             authorizations: Default::default(),
             protocol: ProxyProtocol::Http1,
             http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
         },
     );
 
@@ -83,6 +84,390 @@ fn test_route_attaches_to_server(test: TestConfig, apply_route: impl FnOnce(&mut
         )));
 }
 
+#[test]
+fn server_deny_policies_apply_to_routes() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        Some(("app", "app-0")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+    test.index.write().apply(
+        MkRoute {
+            ns: "ns-0".to_string(),
+            name: "route-foo".to_string(),
+            server: "srv-8080".to_string(),
+        }
+        .gateway_api(),
+    );
+
+    let server_target = LocalTargetRef {
+        group: Some("policy.linkerd.io".to_string()),
+        kind: "Server".to_string(),
+        name: "srv-8080".to_string(),
+    };
+    let sa_foo = NamespacedTargetRef {
+        group: None,
+        kind: "ServiceAccount".to_string(),
+        namespace: Some("ns-0".to_string()),
+        name: "foo".to_string(),
+    };
+
+    let mut allow = mk_authorization_policy("ns-0", "allow-foo", "srv-8080", vec![sa_foo.clone()]);
+    allow.spec.target_ref = Some(server_target.clone());
+    test.index.write().apply(allow);
+
+    let mut deny = mk_authorization_policy("ns-0", "deny-foo", "srv-8080", vec![sa_foo]);
+    deny.spec.target_ref = Some(server_target);
+    deny.spec.action = Some(k8s::policy::AuthorizationAction::Deny);
+    test.index.write().apply(deny);
+
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    let route_authzs = rx.borrow().http_routes["route-foo"].authorizations.clone();
+
+    // Allow policies that target the server do not apply to its routes, but
+    // deny policies do.
+    assert!(
+        !route_authzs.contains_key(&AuthorizationRef::AuthorizationPolicy(
+            "allow-foo".to_string()
+        ))
+    );
+    assert_eq!(
+        route_authzs.get(&AuthorizationRef::AuthorizationPolicy(
+            "deny-foo".to_string()
+        )),
+        rx.borrow()
+            .authorizations
+            .get(&AuthorizationRef::AuthorizationPolicy(
+                "deny-foo".to_string()
+            )),
+    );
+    assert!(
+        route_authzs.contains_key(&AuthorizationRef::AuthorizationPolicy(
+            "deny-foo".to_string()
+        ))
+    );
+}
+
+#[test]
+fn gateway_route_with_url_rewrite_is_not_indexed() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        Some(("app", "app-0")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+
+    let mut route = MkRoute {
+        ns: "ns-0".to_string(),
+        name: "route-foo".to_string(),
+        server: "srv-8080".to_string(),
+    }
+    .gateway_api();
+    route.spec.rules.as_mut().unwrap()[0].filters =
+        Some(vec![k8s_gateway_api::HttpRouteFilter::URLRewrite {
+            url_rewrite: k8s_gateway_api::HttpUrlRewriteFilter {
+                hostname: Some("bar.example.com".to_string()),
+                path: Some(k8s_gateway_api::HttpPathModifier::ReplacePrefixMatch(
+                    "/bar".to_string(),
+                )),
+            },
+        }]);
+    test.index.write().apply(route);
+
+    // Inbound proxies cannot rewrite requests, so the route is not served.
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert!(rx.borrow().http_routes.is_empty());
+}
+
+#[test]
+fn gateway_route_with_request_mirror_is_not_indexed() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        Some(("app", "app-0")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+
+    let mut route = MkRoute {
+        ns: "ns-0".to_string(),
+        name: "route-foo".to_string(),
+        server: "srv-8080".to_string(),
+    }
+    .gateway_api();
+    route.spec.rules.as_mut().unwrap()[0].filters =
+        Some(vec![k8s_gateway_api::HttpRouteFilter::RequestMirror {
+            request_mirror: k8s_gateway_api::HttpRequestMirrorFilter {
+                backend_ref: k8s_gateway_api::BackendObjectReference {
+                    group: None,
+                    kind: None,
+                    name: "shadow".to_string(),
+                    namespace: None,
+                    port: Some(8888),
+                },
+            },
+        }]);
+    test.index.write().apply(route);
+
+    // Inbound proxies cannot mirror requests, so the route is not served.
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert!(rx.borrow().http_routes.is_empty());
+}
+
+#[test]
+fn routes_ranked_by_age_and_name() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        Some(("app", "app-0")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+
+    // The newest route is ranked last, regardless of name. Routes of the same
+    // age are ranked by name.
+    let t0 = k8s::chrono::Utc::now();
+    for (name, created_at) in [
+        ("route-a", t0 + k8s::chrono::Duration::seconds(1)),
+        ("route-c", t0),
+        ("route-b", t0),
+    ] {
+        let mut route = MkRoute {
+            ns: "ns-0".to_string(),
+            name: name.to_string(),
+            server: "srv-8080".to_string(),
+        }
+        .gateway_api();
+        route.metadata.creation_timestamp = Some(k8s::Time(created_at));
+        test.index.write().apply(route);
+    }
+
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    let server = rx.borrow();
+    assert_eq!(server.http_routes["route-b"].rank, 0);
+    assert_eq!(server.http_routes["route-c"].rank, 1);
+    assert_eq!(server.http_routes["route-a"].rank, 2);
+}
+
+#[test]
+fn route_attaches_to_parent_port() {
+    let test = TestConfig::default();
+
+    // Create a pod with two ports of the same name, both selected by a
+    // single server.
+    let mk_port = |port: i32| ContainerPort {
+        name: Some("http".to_string()),
+        container_port: port,
+        protocol: Some("TCP".to_string()),
+        ..ContainerPort::default()
+    };
+    let mut pod = mk_pod(
+        "ns-0",
+        "pod-0",
+        vec![
+            ("container-0", Some(mk_port(8080))),
+            ("container-1", Some(mk_port(9090))),
+        ],
+    );
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-http",
+        Port::Name("http".to_string()),
+        Some(("app", "app-0")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+
+    let mk_route = |name: &str, port: Option<u16>, section_name: Option<&str>| {
+        let mut route = MkRoute {
+            ns: "ns-0".to_string(),
+            name: name.to_string(),
+            server: "srv-http".to_string(),
+        }
+        .gateway_api();
+        let parent = &mut route.spec.inner.parent_refs.as_mut().unwrap()[0];
+        parent.port = port;
+        parent.section_name = section_name.map(ToString::to_string);
+        route
+    };
+    test.index
+        .write()
+        .apply(mk_route("route-port", Some(9090), None));
+    test.index
+        .write()
+        .apply(mk_route("route-section", None, Some("http")));
+    test.index
+        .write()
+        .apply(mk_route("route-other-section", None, Some("admin-http")));
+
+    let rx_8080 = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    let rx_9090 = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 9090.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+
+    let server_8080 = rx_8080.borrow();
+    let routes_8080 = &server_8080.http_routes;
+    assert!(!routes_8080.contains_key("route-port"));
+    assert!(routes_8080.contains_key("route-section"));
+    assert!(!routes_8080.contains_key("route-other-section"));
+
+    let server_9090 = rx_9090.borrow();
+    let routes_9090 = &server_9090.http_routes;
+    assert!(routes_9090.contains_key("route-port"));
+    assert!(routes_9090.contains_key("route-section"));
+    assert!(!routes_9090.contains_key("route-other-section"));
+}
+
+#[test]
+fn cross_namespace_route_requires_reference_grant() {
+    let test = TestConfig::default();
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    test.index.write().apply(pod);
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        Some(("app", "app-0")),
+        Some(("app", "app-0")),
+        Some(k8s::policy::server::ProxyProtocol::Http1),
+    ));
+
+    // A route in ns-1 references the server in ns-0.
+    let mut route = MkRoute {
+        ns: "ns-1".to_string(),
+        name: "route-foo".to_string(),
+        server: "srv-8080".to_string(),
+    }
+    .gateway_api();
+    route.spec.inner.parent_refs.as_mut().unwrap()[0].namespace = Some("ns-0".to_string());
+    test.index.write().apply(route);
+
+    let mut rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 8080.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+    assert!(
+        !rx.borrow_and_update()
+            .http_routes
+            .contains_key("ns-1/route-foo"),
+        "route must not attach without a ReferenceGrant"
+    );
+
+    // Permit HTTPRoutes in ns-1 to reference Servers in ns-0.
+    let grant = k8s::gateway::ReferenceGrant {
+        metadata: k8s::ObjectMeta {
+            namespace: Some("ns-0".to_string()),
+            name: Some("grant-0".to_string()),
+            ..Default::default()
+        },
+        spec: k8s::gateway::ReferenceGrantSpec {
+            from: vec![k8s::gateway::referencegrant::ReferenceGrantFrom {
+                group: "gateway.networking.k8s.io".to_string(),
+                kind: "HTTPRoute".to_string(),
+                namespace: "ns-1".to_string(),
+            }],
+            to: vec![k8s::gateway::referencegrant::ReferenceGrantTo {
+                group: "policy.linkerd.io".to_string(),
+                kind: "Server".to_string(),
+                name: None,
+            }],
+        },
+    };
+    test.index.write().apply(grant.clone());
+    assert!(rx.has_changed().unwrap());
+    assert!(rx
+        .borrow_and_update()
+        .http_routes
+        .contains_key("ns-1/route-foo"));
+
+    // Deleting the grant detaches the route.
+    <Index as IndexNamespacedResource<k8s::gateway::ReferenceGrant>>::delete(
+        &mut test.index.write(),
+        "ns-0".to_string(),
+        "grant-0".to_string(),
+    );
+    assert!(rx.has_changed().unwrap());
+    assert!(!rx
+        .borrow_and_update()
+        .http_routes
+        .contains_key("ns-1/route-foo"));
+
+    // Deleting the route detaches it, even when permitted.
+    test.index.write().apply(grant);
+    assert!(rx.has_changed().unwrap());
+    assert!(rx
+        .borrow_and_update()
+        .http_routes
+        .contains_key("ns-1/route-foo"));
+    <Index as IndexNamespacedResource<k8s_gateway_api::HttpRoute>>::delete(
+        &mut test.index.write(),
+        "ns-1".to_string(),
+        "route-foo".to_string(),
+    );
+    assert!(rx.has_changed().unwrap());
+    assert!(!rx.borrow().http_routes.contains_key("ns-1/route-foo"));
+}
+
 struct MkRoute {
     ns: String,
     name: String,
@@ -162,6 +547,8 @@ impl MkRoute {
                         method: Some("GET".to_string()),
                     }]),
                     filters: None,
+                    backend_refs: None,
+                    timeouts: None,
                 }]),
             },
             status: None,
@@ -182,12 +569,16 @@ fn mk_authorization_policy(
             ..Default::default()
         },
         spec: k8s::policy::AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: Some("gateway.networking.k8s.io".to_string()),
                 kind: "HttpRoute".to_string(),
                 name: route.to_string(),
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: authns.into_iter().collect(),
+            action: None,
+            mode: None,
         },
+        status: None,
     }
 }
@@ -0,0 +1,79 @@
+use super::*;
+use prometheus_client::registry::Registry;
+
+#[test]
+fn reports_index_state() {
+    let test = TestConfig::default();
+    let mut registry = Registry::default();
+    test.index.read().register_metrics(&mut registry);
+
+    test.index
+        .write()
+        .apply(mk_pod("ns-0", "pod-0", Some(("container-0", None))));
+    test.index.write().apply(mk_server(
+        "ns-0",
+        "srv-0",
+        Port::Number(2222.try_into().unwrap()),
+        None,
+        None,
+        None,
+    ));
+    let rx = test
+        .index
+        .write()
+        .pod_server_rx("ns-0", "pod-0", 2222.try_into().unwrap())
+        .expect("pod-0.ns-0 should exist");
+
+    let metrics = encode(&test.index, &registry);
+    assert!(metrics.contains("\nresources{kind=\"Pod\",namespace=\"ns-0\"} 1\n"));
+    assert!(metrics.contains("\nresources{kind=\"Server\",namespace=\"ns-0\"} 1\n"));
+    assert!(metrics.contains("\npod_port_watches 1\n"));
+    assert!(metrics.contains("\nnamespace_reindex_duration_seconds_count 1\n"));
+
+    // Gauges are reset when resources are removed.
+    <Index as IndexNamespacedResource<k8s::policy::Server>>::delete(
+        &mut test.index.write(),
+        "ns-0".to_string(),
+        "srv-0".to_string(),
+    );
+    drop(rx);
+    let metrics = encode(&test.index, &registry);
+    assert!(metrics.contains("\nresources{kind=\"Server\",namespace=\"ns-0\"} 0\n"));
+    assert!(metrics.contains("\npod_port_watches 0\n"));
+}
+
+#[test]
+fn counts_rejected_updates() {
+    let test = TestConfig::default();
+    let mut registry = Registry::default();
+    test.index.read().register_metrics(&mut registry);
+
+    test.index
+        .write()
+        .apply(mk_pod("ns-0", "pod-0", Some(("container-0", None))));
+    // A pod's ports may not change.
+    test.index.write().apply(mk_pod(
+        "ns-0",
+        "pod-0",
+        Some((
+            "container-0",
+            Some(ContainerPort {
+                name: Some("admin-http".to_string()),
+                container_port: 9990,
+                ..Default::default()
+            }),
+        )),
+    ));
+
+    let metrics = encode(&test.index, &registry);
+    assert!(
+        metrics.contains("\nrejected_resources_total{kind=\"Pod\",reason=\"ports_changed\"} 1\n")
+    );
+}
+
+fn encode(index: &SharedIndex, registry: &Registry) -> String {
+    index.read().refresh_metrics();
+    let mut buf = Vec::new();
+    prometheus_client::encoding::text::encode(&mut buf, registry).unwrap();
+    String::from_utf8(buf).unwrap()
+}
@@ -0,0 +1,254 @@
+use super::*;
+use crate::outbound;
+use linkerd_policy_controller_core::outbound::{
+    Backend, OutboundHttpRouteRule, OutboundPolicy, WeightedService,
+};
+use linkerd_policy_controller_k8s_api::policy::httproute::HttpRouteTimeouts;
+
+#[test]
+fn service_must_exist_for_lookup() {
+    let test = TestConfig::default();
+    let index = outbound::Index::shared(test.cluster);
+    index
+        .write()
+        .service_policy_rx("ns-0", "svc-0", 8080.try_into().unwrap())
+        .expect_err("svc-0.ns-0 must not exist");
+}
+
+#[test]
+fn gateway_route_resolves_backends() {
+    let test = TestConfig::default();
+    let index = outbound::Index::shared(test.cluster);
+    index.write().apply(mk_service("ns-0", "svc-0", 8080));
+
+    let mut rx = index
+        .write()
+        .service_policy_rx("ns-0", "svc-0", 8080.try_into().unwrap())
+        .expect("svc-0.ns-0 should exist");
+    {
+        let policy = rx.borrow_and_update();
+        assert_eq!(policy.authority, "svc-0.ns-0.svc.cluster.example.com:8080");
+        assert!(policy.http_routes.is_empty());
+    }
+
+    // Route to a backend Service that does not (yet) exist.
+    index.write().apply(mk_gateway_route(
+        "ns-0",
+        "route-0",
+        "svc-0",
+        Some(vec![k8s_gateway_api::HttpBackendRef {
+            backend_ref: Some(k8s_gateway_api::BackendRef {
+                weight: Some(3),
+                name: "svc-1".to_string(),
+                port: 8080,
+            }),
+            filters: None,
+        }]),
+    ));
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(
+        route_backends(&rx.borrow_and_update(), "route-0"),
+        vec![Backend::Invalid {
+            weight: 3,
+            message: "Service svc-1.ns-0 not found".to_string(),
+        }],
+    );
+
+    // Once the backend Service is created, the backend is resolved.
+    index.write().apply(mk_service("ns-0", "svc-1", 8080));
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(
+        route_backends(&rx.borrow_and_update(), "route-0"),
+        vec![Backend::Service(WeightedService {
+            weight: 3,
+            authority: "svc-1.ns-0.svc.cluster.example.com:8080".to_string(),
+            name: "svc-1".to_string(),
+            namespace: "ns-0".to_string(),
+            port: 8080.try_into().unwrap(),
+            filters: vec![],
+        })],
+    );
+
+    // Deleting the route removes it from the policy.
+    <outbound::Index as IndexNamespacedResource<k8s_gateway_api::HttpRoute>>::delete(
+        &mut index.write(),
+        "ns-0".to_string(),
+        "route-0".to_string(),
+    );
+    assert!(rx.has_changed().unwrap());
+    assert!(rx.borrow_and_update().http_routes.is_empty());
+}
+
+#[test]
+fn linkerd_route_defaults_to_parent_service() {
+    let test = TestConfig::default();
+    let index = outbound::Index::shared(test.cluster);
+    index.write().apply(mk_service("ns-0", "svc-0", 8080));
+
+    let mut route = mk_linkerd_route("ns-0", "route-0", "svc-0");
+    route.spec.rules.as_mut().unwrap()[0].timeouts = Some(HttpRouteTimeouts {
+        request: Some("1m30s".to_string()),
+        backend_request: Some("500ms".to_string()),
+    });
+    index.write().apply(route);
+
+    let rx = index
+        .write()
+        .service_policy_rx("ns-0", "svc-0", 8080.try_into().unwrap())
+        .expect("svc-0.ns-0 should exist");
+    let policy = rx.borrow();
+    let rule = &policy.http_routes["route-0"].rules[0];
+    assert_eq!(
+        rule.backends,
+        vec![Backend::Service(WeightedService {
+            weight: 1,
+            authority: "svc-0.ns-0.svc.cluster.example.com:8080".to_string(),
+            name: "svc-0".to_string(),
+            namespace: "ns-0".to_string(),
+            port: 8080.try_into().unwrap(),
+            filters: vec![],
+        })],
+    );
+    assert_eq!(rule.request_timeout, Some(time::Duration::from_secs(90)));
+    assert_eq!(
+        rule.backend_request_timeout,
+        Some(time::Duration::from_millis(500))
+    );
+
+    // The route only applies to the port it references.
+    let other = index
+        .write()
+        .service_policy_rx("ns-0", "svc-0", 9090.try_into().unwrap())
+        .expect("svc-0.ns-0 should exist");
+    assert!(other.borrow().http_routes.is_empty());
+}
+
+#[test]
+fn routes_are_ranked_by_age_and_name() {
+    let test = TestConfig::default();
+    let index = outbound::Index::shared(test.cluster);
+    index.write().apply(mk_service("ns-0", "svc-0", 8080));
+
+    // The newest route is ranked last, regardless of name. Routes of the same
+    // age are ranked by name.
+    let t0 = k8s::chrono::Utc::now();
+    for (name, created_at) in [
+        ("route-a", t0 + k8s::chrono::Duration::seconds(1)),
+        ("route-c", t0),
+        ("route-b", t0),
+    ] {
+        let mut route = mk_gateway_route("ns-0", name, "svc-0", None);
+        route.metadata.creation_timestamp = Some(k8s::Time(created_at));
+        index.write().apply(route);
+    }
+
+    let rx = index
+        .write()
+        .service_policy_rx("ns-0", "svc-0", 8080.try_into().unwrap())
+        .expect("svc-0.ns-0 should exist");
+    let policy = rx.borrow();
+    assert_eq!(policy.http_routes["route-b"].rank, 0);
+    assert_eq!(policy.http_routes["route-c"].rank, 1);
+    assert_eq!(policy.http_routes["route-a"].rank, 2);
+}
+
+fn route_backends(policy: &OutboundPolicy, route: &str) -> Vec<Backend> {
+    let OutboundHttpRouteRule { backends, .. } = &policy.http_routes[route].rules[0];
+    backends.clone()
+}
+
+fn mk_service(ns: impl ToString, name: impl ToString, port: i32) -> k8s::Service {
+    k8s::Service {
+        metadata: k8s::ObjectMeta {
+            namespace: Some(ns.to_string()),
+            name: Some(name.to_string()),
+            ..Default::default()
+        },
+        spec: Some(k8s::ServiceSpec {
+            ports: Some(vec![k8s::ServicePort {
+                port,
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn mk_gateway_route(
+    ns: impl ToString,
+    name: impl ToString,
+    service: impl ToString,
+    backend_refs: Option<Vec<k8s_gateway_api::HttpBackendRef>>,
+) -> k8s_gateway_api::HttpRoute {
+    k8s_gateway_api::HttpRoute {
+        metadata: k8s::ObjectMeta {
+            namespace: Some(ns.to_string()),
+            name: Some(name.to_string()),
+            ..Default::default()
+        },
+        spec: k8s_gateway_api::HttpRouteSpec {
+            inner: k8s_gateway_api::CommonRouteSpec {
+                parent_refs: Some(vec![k8s_gateway_api::ParentReference {
+                    group: Some("core".to_string()),
+                    kind: Some("Service".to_string()),
+                    namespace: None,
+                    name: service.to_string(),
+                    section_name: None,
+                    port: Some(8080),
+                }]),
+            },
+            hostnames: None,
+            rules: Some(vec![k8s_gateway_api::HttpRouteRule {
+                matches: Some(vec![k8s_gateway_api::HttpRouteMatch {
+                    path: Some(k8s_gateway_api::HttpPathMatch::PathPrefix {
+                        value: "/foo".to_string(),
+                    }),
+                    ..Default::default()
+                }]),
+                filters: None,
+                backend_refs,
+            }]),
+        },
+        status: None,
+    }
+}
+
+fn mk_linkerd_route(
+    ns: impl ToString,
+    name: impl ToString,
+    service: impl ToString,
+) -> k8s::policy::HttpRoute {
+    k8s::policy::HttpRoute {
+        metadata: k8s::ObjectMeta {
+            namespace: Some(ns.to_string()),
+            name: Some(name.to_string()),
+            ..Default::default()
+        },
+        spec: k8s::policy::HttpRouteSpec {
+            inner: k8s_gateway_api::CommonRouteSpec {
+                parent_refs: Some(vec![k8s_gateway_api::ParentReference {
+                    group: Some("core".to_string()),
+                    kind: Some("Service".to_string()),
+                    namespace: None,
+                    name: service.to_string(),
+                    section_name: None,
+                    port: Some(8080),
+                }]),
+            },
+            hostnames: None,
+            rules: Some(vec![k8s::policy::httproute::HttpRouteRule {
+                matches: Some(vec![k8s_gateway_api::HttpRouteMatch {
+                    path: Some(k8s_gateway_api::HttpPathMatch::PathPrefix {
+                        value: "/foo".to_string(),
+                    }),
+                    ..Default::default()
+                }]),
+                filters: None,
+                backend_refs: None,
+                timeouts: None,
+            }]),
+        },
+        status: None,
+    }
+}
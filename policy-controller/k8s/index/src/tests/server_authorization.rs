@@ -44,6 +44,7 @@ fn link_server_authz(selector: ServerSelector) {
             authorizations: Default::default(),
             protocol: ProxyProtocol::Http1,
             http_routes: HashMap::default(),
+            grpc_routes: HashMap::default(),
         },
     );
     test.index.write().apply(mk_server_authz(
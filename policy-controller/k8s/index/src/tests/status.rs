@@ -0,0 +1,443 @@
+use super::{
+    authorization_policy::{mk_authorization_policy, mk_meshtls_authentication},
+    external_workload::mk_external_workload,
+    *,
+};
+use crate::status::{self, conditions, reasons, Id, Kind, Update};
+use tokio::sync::mpsc;
+
+#[test]
+fn route_accepted_when_server_exists() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let index = status::Index::shared(tx);
+
+    // A route that references a missing server is not accepted.
+    index
+        .write()
+        .apply(mk_route("ns-0", "route-foo", "srv-8080", None));
+    let Update { id, patch } = rx.try_recv().expect("route status must be published");
+    assert_eq!(
+        id,
+        Id {
+            kind: Kind::GatewayHttpRoute,
+            namespace: "ns-0".to_string(),
+            name: "route-foo".to_string(),
+        }
+    );
+    let parents = patch_parents(patch);
+    assert_eq!(parents.len(), 1);
+    assert_condition(
+        &parents[0].conditions,
+        conditions::ACCEPTED,
+        "False",
+        reasons::NO_MATCHING_PARENT,
+    );
+
+    // Creating the server causes the route to be accepted.
+    index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        None,
+        None,
+        None,
+    ));
+    let Update { id, patch } = rx.try_recv().expect("route status must be published");
+    assert_eq!(id.name, "route-foo");
+    let parents = patch_parents(patch);
+    assert_eq!(parents.len(), 1);
+    assert_condition(
+        &parents[0].conditions,
+        conditions::ACCEPTED,
+        "True",
+        reasons::ACCEPTED,
+    );
+    assert_condition(
+        &parents[0].conditions,
+        conditions::RESOLVED_REFS,
+        "True",
+        reasons::RESOLVED_REFS,
+    );
+
+    // Once the status has been written, no further updates are published.
+    index.write().apply(mk_route(
+        "ns-0",
+        "route-foo",
+        "srv-8080",
+        Some(k8s_gateway_api::RouteStatus { parents }),
+    ));
+    assert!(rx.try_recv().is_err());
+
+    // Deleting the route clears any pending update.
+    IndexNamespacedResource::<k8s_gateway_api::HttpRoute>::delete(
+        &mut *index.write(),
+        "ns-0".to_string(),
+        "route-foo".to_string(),
+    );
+    let Update { id, patch } = rx.try_recv().expect("route deletion must be published");
+    assert_eq!(id.name, "route-foo");
+    assert_eq!(patch, None);
+}
+
+#[test]
+fn route_preserves_other_controller_statuses() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let index = status::Index::shared(tx);
+
+    let other = k8s_gateway_api::RouteParentStatus {
+        parent_ref: k8s_gateway_api::ParentReference {
+            group: None,
+            kind: Some("Gateway".to_string()),
+            namespace: None,
+            name: "gateway-0".to_string(),
+            section_name: None,
+            port: None,
+        },
+        controller_name: "example.com/gateway-controller".to_string(),
+        conditions: vec![],
+    };
+    index.write().apply(mk_route(
+        "ns-0",
+        "route-foo",
+        "srv-8080",
+        Some(k8s_gateway_api::RouteStatus {
+            parents: vec![other.clone()],
+        }),
+    ));
+
+    let Update { patch, .. } = rx.try_recv().expect("route status must be published");
+    let parents = patch_parents(patch);
+    assert_eq!(parents.len(), 2);
+    assert!(parents.contains(&other));
+}
+
+#[test]
+fn authorization_policy_resolves_refs() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let index = status::Index::shared(tx);
+
+    // A policy whose target and authentication do not exist is not accepted.
+    let mut policy = mk_authorization_policy(
+        "ns-0",
+        "authz-foo",
+        Some("srv-8080"),
+        Some(NamespacedTargetRef {
+            group: Some("policy.linkerd.io".to_string()),
+            kind: "MeshTLSAuthentication".to_string(),
+            namespace: None,
+            name: "mtls-clients".to_string(),
+        }),
+    );
+    policy.metadata.generation = Some(1);
+    index.write().apply(policy.clone());
+    let Update { id, patch } = rx.try_recv().expect("policy status must be published");
+    assert_eq!(
+        id,
+        Id {
+            kind: Kind::AuthorizationPolicy,
+            namespace: "ns-0".to_string(),
+            name: "authz-foo".to_string(),
+        }
+    );
+    let conds = patch_conditions(patch);
+    assert_condition(
+        &conds,
+        conditions::ACCEPTED,
+        "False",
+        reasons::REF_NOT_FOUND,
+    );
+    assert_condition(
+        &conds,
+        conditions::RESOLVED_REFS,
+        "False",
+        reasons::REF_NOT_FOUND,
+    );
+    assert_condition(
+        &conds,
+        conditions::TARGET_FOUND,
+        "False",
+        reasons::NO_MATCHING_TARGET,
+    );
+
+    // Creating the authentication resolves the policy's references.
+    index.write().apply(mk_meshtls_authentication(
+        "ns-0",
+        "mtls-clients",
+        Some("foo.ns-0.serviceaccount.identity.linkerd.cluster.local".to_string()),
+        None,
+    ));
+    let Update { id, patch } = rx.try_recv().expect("policy status must be published");
+    assert_eq!(id.name, "authz-foo");
+    let conds = patch_conditions(patch);
+    assert_condition(&conds, conditions::ACCEPTED, "True", reasons::ACCEPTED);
+    assert_condition(
+        &conds,
+        conditions::RESOLVED_REFS,
+        "True",
+        reasons::RESOLVED_REFS,
+    );
+    assert_condition(
+        &conds,
+        conditions::TARGET_FOUND,
+        "False",
+        reasons::NO_MATCHING_TARGET,
+    );
+
+    // Creating the server resolves the policy's target.
+    index.write().apply(mk_server(
+        "ns-0",
+        "srv-8080",
+        Port::Number(8080.try_into().unwrap()),
+        None,
+        None,
+        None,
+    ));
+    let Update { patch, .. } = rx.try_recv().expect("policy status must be published");
+    let conds = patch_conditions(patch);
+    assert_condition(
+        &conds,
+        conditions::TARGET_FOUND,
+        "True",
+        reasons::TARGET_FOUND,
+    );
+
+    // Once the status has been written, no further updates are published.
+    policy.status = Some(k8s::policy::AuthorizationPolicyStatus { conditions: conds });
+    index.write().apply(policy);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn server_conflicts() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let index = status::Index::shared(tx);
+
+    let t0 = k8s::chrono::Utc::now();
+    let mk_srv = |name: &str, created_at| {
+        let mut srv = mk_server(
+            "ns-0",
+            name,
+            Port::Number(8080.try_into().unwrap()),
+            None,
+            Some(("app", "app-0")),
+            None,
+        );
+        srv.metadata.generation = Some(1);
+        srv.metadata.creation_timestamp = Some(k8s::Time(created_at));
+        srv
+    };
+
+    // Server statuses are not published until pods have been indexed.
+    index.write().apply(mk_srv("srv-b", t0));
+    index
+        .write()
+        .apply(mk_srv("srv-a", t0 + k8s::chrono::Duration::seconds(1)));
+    assert!(rx.try_recv().is_err());
+
+    let mut pod = mk_pod("ns-0", "pod-0", Some(("container-0", None)));
+    pod.labels_mut()
+        .insert("app".to_string(), "app-0".to_string());
+    IndexNamespacedResource::<k8s::Pod>::reset(&mut *index.write(), vec![pod], Default::default());
+
+    let mut updates = HashMap::<String, Vec<k8s::Condition>>::default();
+    while let Ok(Update { id, patch }) = rx.try_recv() {
+        assert_eq!(id.kind, Kind::Server);
+        let mut patch = patch.expect("patch must be set");
+        let status: k8s::policy::ServerStatus =
+            serde_json::from_value(patch["status"].take()).expect("status must be a ServerStatus");
+        updates.insert(id.name, status.conditions);
+    }
+    assert_eq!(updates.len(), 2);
+
+    // The older server is not conflicted.
+    assert_condition(
+        &updates["srv-b"],
+        conditions::CONFLICTED,
+        "False",
+        reasons::NO_CONFLICTS,
+    );
+
+    // The newer server describes the conflict.
+    assert_condition(
+        &updates["srv-a"],
+        conditions::CONFLICTED,
+        "True",
+        reasons::PORT_CONFLICT,
+    );
+    assert_eq!(
+        updates["srv-a"][0].message,
+        "port 8080 on pod pod-0 is already selected by Server srv-b"
+    );
+
+    // Deleting the older server resolves the conflict.
+    IndexNamespacedResource::<k8s::policy::Server>::delete(
+        &mut *index.write(),
+        "ns-0".to_string(),
+        "srv-b".to_string(),
+    );
+    let mut updates = vec![];
+    while let Ok(update) = rx.try_recv() {
+        updates.push(update);
+    }
+    assert!(updates.contains(&Update {
+        id: Id {
+            kind: Kind::Server,
+            namespace: "ns-0".to_string(),
+            name: "srv-b".to_string(),
+        },
+        patch: None,
+    }));
+    let Update { patch, .. } = updates
+        .into_iter()
+        .find(|u| u.id.name == "srv-a")
+        .expect("srv-a status must be published");
+    let mut patch = patch.expect("patch must be set");
+    let status: k8s::policy::ServerStatus =
+        serde_json::from_value(patch["status"].take()).expect("status must be a ServerStatus");
+    assert_condition(
+        &status.conditions,
+        conditions::CONFLICTED,
+        "False",
+        reasons::NO_CONFLICTS,
+    );
+}
+
+#[test]
+fn server_conflicts_on_external_workloads() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let index = status::Index::shared(tx);
+
+    let t0 = k8s::chrono::Utc::now();
+    let mk_srv = |name: &str, created_at| {
+        let mut srv = mk_server(
+            "ns-0",
+            name,
+            Port::Name("admin-http".to_string()),
+            None,
+            Some(("app", "app-0")),
+            None,
+        );
+        srv.metadata.generation = Some(1);
+        srv.metadata.creation_timestamp = Some(k8s::Time(created_at));
+        srv
+    };
+    index.write().apply(mk_srv("srv-b", t0));
+    index
+        .write()
+        .apply(mk_srv("srv-a", t0 + k8s::chrono::Duration::seconds(1)));
+    IndexNamespacedResource::<k8s::Pod>::reset(&mut *index.write(), vec![], Default::default());
+    while rx.try_recv().is_ok() {}
+
+    // Both servers select the external workload's port, so the newer server
+    // is conflicted.
+    index
+        .write()
+        .apply(mk_external_workload("ns-0", "ew-0", vec![("app", "app-0")]));
+    let updates = server_updates(&mut rx);
+    assert_condition(
+        &updates["srv-a"],
+        conditions::CONFLICTED,
+        "True",
+        reasons::PORT_CONFLICT,
+    );
+    assert_eq!(
+        updates["srv-a"][0].message,
+        "port 8080 on external workload ew-0 is already selected by Server srv-b"
+    );
+
+    // Deleting the external workload resolves the conflict.
+    IndexNamespacedResource::<k8s::workload::ExternalWorkload>::delete(
+        &mut *index.write(),
+        "ns-0".to_string(),
+        "ew-0".to_string(),
+    );
+    let updates = server_updates(&mut rx);
+    assert_condition(
+        &updates["srv-a"],
+        conditions::CONFLICTED,
+        "False",
+        reasons::NO_CONFLICTS,
+    );
+}
+
+fn mk_route(
+    ns: impl ToString,
+    name: impl ToString,
+    server: impl ToString,
+    status: Option<k8s_gateway_api::RouteStatus>,
+) -> k8s_gateway_api::HttpRoute {
+    k8s_gateway_api::HttpRoute {
+        metadata: k8s::ObjectMeta {
+            namespace: Some(ns.to_string()),
+            name: Some(name.to_string()),
+            generation: Some(1),
+            ..Default::default()
+        },
+        spec: k8s_gateway_api::HttpRouteSpec {
+            inner: k8s_gateway_api::CommonRouteSpec {
+                parent_refs: Some(vec![k8s_gateway_api::ParentReference {
+                    group: Some("policy.linkerd.io".to_string()),
+                    kind: Some("Server".to_string()),
+                    namespace: None,
+                    name: server.to_string(),
+                    section_name: None,
+                    port: None,
+                }]),
+            },
+            hostnames: None,
+            rules: Some(vec![k8s_gateway_api::HttpRouteRule {
+                matches: Some(vec![k8s_gateway_api::HttpRouteMatch {
+                    path: Some(k8s_gateway_api::HttpPathMatch::PathPrefix {
+                        value: "/".to_string(),
+                    }),
+                    headers: None,
+                    query_params: None,
+                    method: None,
+                }]),
+                filters: None,
+                backend_refs: None,
+            }]),
+        },
+        status: status.map(|inner| k8s_gateway_api::HttpRouteStatus { inner }),
+    }
+}
+
+fn patch_parents(patch: Option<serde_json::Value>) -> Vec<k8s_gateway_api::RouteParentStatus> {
+    let mut patch = patch.expect("patch must be set");
+    let status: k8s_gateway_api::RouteStatus =
+        serde_json::from_value(patch["status"].take()).expect("status must be a RouteStatus");
+    status.parents
+}
+
+fn patch_conditions(patch: Option<serde_json::Value>) -> Vec<k8s::Condition> {
+    let mut patch = patch.expect("patch must be set");
+    let status: k8s::policy::AuthorizationPolicyStatus =
+        serde_json::from_value(patch["status"].take())
+            .expect("status must be an AuthorizationPolicyStatus");
+    status.conditions
+}
+
+fn server_updates(
+    rx: &mut mpsc::UnboundedReceiver<Update>,
+) -> HashMap<String, Vec<k8s::Condition>> {
+    let mut updates = HashMap::default();
+    while let Ok(Update { id, patch }) = rx.try_recv() {
+        assert_eq!(id.kind, Kind::Server);
+        let mut patch = patch.expect("patch must be set");
+        let status: k8s::policy::ServerStatus =
+            serde_json::from_value(patch["status"].take()).expect("status must be a ServerStatus");
+        updates.insert(id.name, status.conditions);
+    }
+    updates
+}
+
+#[track_caller]
+fn assert_condition(conditions: &[k8s::Condition], type_: &str, value: &str, reason: &str) {
+    let cond = conditions
+        .iter()
+        .find(|c| c.type_ == type_)
+        .unwrap_or_else(|| panic!("{} condition must be set", type_));
+    assert_eq!(cond.status, value);
+    assert_eq!(cond.reason, reason);
+    assert_eq!(cond.observed_generation, Some(1));
+}
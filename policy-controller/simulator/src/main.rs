@@ -0,0 +1,253 @@
+//! Simulates inbound policy decisions against a set of manifests, without a
+//! cluster.
+//!
+//! Manifests are loaded into the same index that the policy controller uses,
+//! and the resulting server for a workload port is evaluated for a client
+//! (and, optionally, an HTTP request). The process exits with a non-zero
+//! status if the client is not permitted, so that policy changes may be
+//! checked in CI:
+//!
+//! ```text
+//! linkerd-policy-simulator --manifests ./k8s \
+//!     --namespace emojivoto --pod web --port 8080 \
+//!     --client-addr 10.1.2.3 \
+//!     --client-identity vote-bot.emojivoto.serviceaccount.identity.linkerd.cluster.local \
+//!     --method GET --path /api/list
+//! ```
+
+#![deny(warnings, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+mod manifests;
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use linkerd_policy_controller_core::{
+    evaluate::{self, Client, Mismatch, Outcome, RouteMatch},
+    AuthorizationAction, AuthorizationRef, IpNet, ServerRef,
+};
+use linkerd_policy_controller_k8s_index::{ClusterInfo, DefaultPolicy, Index};
+use std::{net::IpAddr, num::NonZeroU16, path::PathBuf, time};
+
+#[derive(Debug, Parser)]
+#[clap(
+    name = "linkerd-policy-simulator",
+    about = "Simulates inbound policy decisions from manifests"
+)]
+struct Args {
+    /// Files or directories of YAML manifests to load.
+    #[clap(long, required = true, multiple_occurrences = true)]
+    manifests: Vec<PathBuf>,
+
+    /// The namespace of the workload.
+    #[clap(long, default_value = "default")]
+    namespace: String,
+
+    /// The name of the pod (or `Deployment`, `StatefulSet`, or `DaemonSet`).
+    #[clap(long, required_unless_present = "external_workload")]
+    pod: Option<String>,
+
+    /// The name of an `ExternalWorkload`.
+    #[clap(long, conflicts_with = "pod")]
+    external_workload: Option<String>,
+
+    /// The workload's port.
+    #[clap(long)]
+    port: NonZeroU16,
+
+    /// The client's IP address.
+    #[clap(long)]
+    client_addr: IpAddr,
+
+    /// The client's mesh identity. Implies that the client uses mutual TLS.
+    #[clap(long)]
+    client_identity: Option<String>,
+
+    /// Indicates that the client uses mutual TLS without an identity.
+    #[clap(long)]
+    client_tls: bool,
+
+    /// The HTTP request method. When set, the request is matched against the
+    /// server's routes.
+    #[clap(long)]
+    method: Option<http::Method>,
+
+    /// The HTTP request path, including any query string.
+    #[clap(long, default_value = "/")]
+    path: String,
+
+    /// The HTTP request authority.
+    #[clap(long)]
+    authority: Option<String>,
+
+    /// HTTP request headers, as `name:value`.
+    #[clap(long = "header", multiple_occurrences = true)]
+    headers: Vec<String>,
+
+    /// Network CIDRs of pod IPs.
+    #[clap(
+        long,
+        default_value = "10.0.0.0/8,100.64.0.0/10,172.16.0.0/12,192.168.0.0/16",
+        value_delimiter = ','
+    )]
+    cluster_networks: Vec<IpNet>,
+
+    #[clap(long, default_value = "all-unauthenticated")]
+    default_policy: DefaultPolicy,
+
+    #[clap(long, default_value = "cluster.local")]
+    identity_domain: String,
+
+    #[clap(long, default_value = "cluster.local")]
+    cluster_domain: String,
+
+    #[clap(long, default_value = "linkerd")]
+    control_plane_namespace: String,
+}
+
+fn main() -> Result<()> {
+    let Args {
+        manifests,
+        namespace,
+        pod,
+        external_workload,
+        port,
+        client_addr,
+        client_identity,
+        client_tls,
+        method,
+        path,
+        authority,
+        headers,
+        cluster_networks,
+        default_policy,
+        identity_domain,
+        cluster_domain,
+        control_plane_namespace,
+    } = Args::parse();
+
+    // Illegal resources are logged as warnings by the index.
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .with_writer(std::io::stderr)
+        .init();
+
+    let request = match method {
+        Some(method) => {
+            let mut req = http::Request::builder().method(method).uri(path);
+            if let Some(authority) = authority {
+                req = req.header(http::header::HOST, authority);
+            }
+            for header in headers {
+                let (name, value) = match header.split_once(':') {
+                    Some((name, value)) => (name.trim(), value.trim()),
+                    None => bail!("invalid header: {}", header),
+                };
+                req = req.header(name, value);
+            }
+            Some(req.body(()).context("invalid HTTP request")?)
+        }
+        None => None,
+    };
+
+    let index = Index::shared(ClusterInfo {
+        networks: cluster_networks.clone(),
+        service_networks: vec![],
+        control_plane_ns: control_plane_namespace,
+        identity_domain,
+        dns_domain: cluster_domain,
+        default_policy,
+        default_detect_timeout: time::Duration::from_secs(10),
+    });
+    manifests::load(&mut index.write(), &manifests)?;
+
+    let rx = match (pod, external_workload) {
+        (Some(pod), _) => index.write().pod_server_rx(&namespace, &pod, port),
+        (None, Some(name)) => index
+            .write()
+            .external_workload_server_rx(&namespace, &name, port),
+        (None, None) => unreachable!("clap requires a pod or external workload"),
+    }?;
+    let server = rx.borrow().clone();
+
+    let client = Client {
+        addr: client_addr,
+        tls: client_tls || client_identity.is_some(),
+        identity: client_identity,
+    };
+    let decision = evaluate::evaluate(&server, &cluster_networks, &client, request.as_ref());
+
+    match &server.reference {
+        ServerRef::Default(name) => println!("server: default ({})", name),
+        ServerRef::Server(name) => println!("server: Server {}", name),
+    }
+    println!("protocol: {:?}", server.protocol);
+    match decision.route {
+        Some(RouteMatch::Http { name, rule }) => {
+            println!("route: HTTPRoute {} (rule {})", name, rule)
+        }
+        Some(RouteMatch::Grpc { name, rule }) => {
+            println!("route: GRPCRoute {} (rule {})", name, rule)
+        }
+        None => {}
+    }
+    for (reference, mismatch) in decision.unmatched.iter() {
+        println!(
+            "skipped: {}: {}",
+            display_ref(reference),
+            display_mismatch(mismatch, &client)
+        );
+    }
+
+    match decision.outcome {
+        Outcome::Permitted {
+            authorization,
+            action,
+        } => {
+            let audit = if action == AuthorizationAction::Audit {
+                " (audit: would be refused if enforced)"
+            } else {
+                ""
+            };
+            println!("allowed: {}{}", display_ref(authorization), audit);
+            Ok(())
+        }
+        Outcome::Denied { authorization } => {
+            println!("denied: {}", display_ref(authorization));
+            std::process::exit(1);
+        }
+        Outcome::Unauthorized => {
+            println!("denied: no authorization permits the client");
+            std::process::exit(1);
+        }
+        Outcome::NoRoute => {
+            println!("denied: no route matches the request");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn display_mismatch(mismatch: &Mismatch, client: &Client) -> String {
+    match mismatch {
+        Mismatch::Network => format!(
+            "client address {} is not in a permitted network",
+            client.addr
+        ),
+        Mismatch::Tls => "client must use mutual TLS".to_string(),
+        Mismatch::Identity => match client.identity.as_deref() {
+            Some(id) => format!("client identity {} is not permitted", id),
+            None => "client must have a mesh identity".to_string(),
+        },
+    }
+}
+
+fn display_ref(reference: &AuthorizationRef) -> String {
+    match reference {
+        AuthorizationRef::Default(name) => format!("default {}", name),
+        AuthorizationRef::ServerAuthorization(name) => format!("ServerAuthorization {}", name),
+        AuthorizationRef::AuthorizationPolicy(name) => format!("AuthorizationPolicy {}", name),
+        AuthorizationRef::AuditedPolicy(name) => {
+            format!("AuthorizationPolicy {} (audit mode)", name)
+        }
+    }
+}
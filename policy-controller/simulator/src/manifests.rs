@@ -0,0 +1,187 @@
+//! Loads Kubernetes manifests into an `Index`.
+
+use anyhow::{bail, Context, Result};
+use kubert::index::{IndexClusterResource, IndexNamespacedResource};
+use linkerd_policy_controller_k8s_api::{
+    self as k8s, api::core::v1::PodTemplateSpec, Resource, ResourceExt,
+};
+use linkerd_policy_controller_k8s_index::Index;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::path::{Path, PathBuf};
+
+/// The namespace of resources that do not specify one, as with `kubectl apply`.
+const DEFAULT_NAMESPACE: &str = "default";
+
+/// Loads all YAML manifests in `paths` (recursing into directories) into the
+/// index.
+///
+/// Workloads (e.g. `Deployment`s) are indexed as a single pod, named after the
+/// workload, with the labels, annotations, and ports of its pod template.
+/// Resources that do not affect inbound policy are ignored.
+pub fn load(index: &mut Index, paths: &[PathBuf]) -> Result<()> {
+    for path in paths {
+        for file in manifest_files(path)? {
+            let data = std::fs::read_to_string(&file)
+                .with_context(|| format!("failed to read {}", file.display()))?;
+            for document in serde_yaml::Deserializer::from_str(&data) {
+                let value = serde_yaml::Value::deserialize(document)
+                    .with_context(|| format!("failed to parse {}", file.display()))?;
+                apply(index, value)
+                    .with_context(|| format!("invalid resource in {}", file.display()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn manifest_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(path)
+        .with_context(|| format!("failed to read directory {}", path.display()))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(manifest_files(&path)?);
+        } else if matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml" | "yml")
+        ) {
+            files.push(path);
+        }
+    }
+    // Apply files in a stable order so that results do not depend on the
+    // filesystem.
+    files.sort();
+    Ok(files)
+}
+
+fn apply(index: &mut Index, value: serde_yaml::Value) -> Result<()> {
+    // Empty documents (e.g. a trailing `---`) are ignored.
+    if value.is_null() {
+        return Ok(());
+    }
+
+    let api_version = value
+        .get("apiVersion")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let kind = value
+        .get("kind")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let group = match api_version.split_once('/') {
+        Some((group, _)) => group,
+        None => "",
+    };
+
+    match (group, kind.as_str()) {
+        ("", "List") => {
+            let items = match value.get("items").and_then(|i| i.as_sequence()) {
+                Some(items) => items.clone(),
+                None => return Ok(()),
+            };
+            for item in items {
+                apply(index, item)?;
+            }
+        }
+
+        ("", "Namespace") => {
+            let ns = serde_yaml::from_value::<k8s::Namespace>(value)?;
+            IndexClusterResource::apply(index, ns);
+        }
+        ("", "Pod") => apply_namespaced::<k8s::Pod>(index, value)?,
+
+        ("apps", "Deployment") => {
+            let deploy = serde_yaml::from_value::<k8s::api::apps::v1::Deployment>(value)?;
+            let template = deploy.spec.clone().map(|s| s.template);
+            apply_template(index, &deploy, template)
+        }
+        ("apps", "StatefulSet") => {
+            let sts = serde_yaml::from_value::<k8s::api::apps::v1::StatefulSet>(value)?;
+            let template = sts.spec.clone().map(|s| s.template);
+            apply_template(index, &sts, template)
+        }
+        ("apps", "DaemonSet") => {
+            let ds = serde_yaml::from_value::<k8s::api::apps::v1::DaemonSet>(value)?;
+            let template = ds.spec.clone().map(|s| s.template);
+            apply_template(index, &ds, template)
+        }
+
+        ("workload.linkerd.io", "ExternalWorkload") => {
+            apply_namespaced::<k8s::workload::ExternalWorkload>(index, value)?
+        }
+
+        ("policy.linkerd.io", "Server") => apply_namespaced::<k8s::policy::Server>(index, value)?,
+        ("policy.linkerd.io", "ServerAuthorization") => {
+            apply_namespaced::<k8s::policy::ServerAuthorization>(index, value)?
+        }
+        ("policy.linkerd.io", "AuthorizationPolicy") => {
+            apply_namespaced::<k8s::policy::AuthorizationPolicy>(index, value)?
+        }
+        ("policy.linkerd.io", "MeshTLSAuthentication") => {
+            apply_namespaced::<k8s::policy::MeshTLSAuthentication>(index, value)?
+        }
+        ("policy.linkerd.io", "NetworkAuthentication") => {
+            apply_namespaced::<k8s::policy::NetworkAuthentication>(index, value)?
+        }
+        ("policy.linkerd.io", "HTTPRoute") => {
+            apply_namespaced::<k8s::policy::HttpRoute>(index, value)?
+        }
+
+        ("gateway.networking.k8s.io", "HTTPRoute") => {
+            apply_namespaced::<k8s_gateway_api::HttpRoute>(index, value)?
+        }
+        ("gateway.networking.k8s.io", "GRPCRoute") => {
+            apply_namespaced::<k8s::gateway::GrpcRoute>(index, value)?
+        }
+        ("gateway.networking.k8s.io", "ReferenceGrant") => {
+            apply_namespaced::<k8s::gateway::ReferenceGrant>(index, value)?
+        }
+
+        ("", "") => bail!("resource must specify an apiVersion and kind"),
+        (_, kind) => tracing::debug!(%api_version, %kind, "Ignoring resource"),
+    }
+
+    Ok(())
+}
+
+fn apply_namespaced<T>(index: &mut Index, value: serde_yaml::Value) -> Result<()>
+where
+    T: DeserializeOwned + Resource,
+    Index: IndexNamespacedResource<T>,
+{
+    let mut resource = serde_yaml::from_value::<T>(value)?;
+    if resource.namespace().is_none() {
+        resource.meta_mut().namespace = Some(DEFAULT_NAMESPACE.to_string());
+    }
+    IndexNamespacedResource::apply(index, resource);
+    Ok(())
+}
+
+/// Indexes a workload's pod template as a pod named after the workload.
+fn apply_template<T: Resource>(index: &mut Index, workload: &T, template: Option<PodTemplateSpec>) {
+    let PodTemplateSpec { metadata, spec } = template.unwrap_or_default();
+    let metadata = metadata.unwrap_or_default();
+    let pod = k8s::Pod {
+        metadata: k8s::ObjectMeta {
+            name: Some(workload.name_unchecked()),
+            namespace: Some(
+                workload
+                    .namespace()
+                    .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string()),
+            ),
+            labels: metadata.labels,
+            annotations: metadata.annotations,
+            ..Default::default()
+        },
+        spec,
+        ..Default::default()
+    };
+    IndexNamespacedResource::apply(index, pod);
+}
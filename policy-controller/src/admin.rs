@@ -0,0 +1,418 @@
+//! Serves diagnostic endpoints that describe the policies published by the
+//! controller.
+//!
+//! - `GET /policy/inbound?ns=<namespace>&pod=<name>&port=<port>` returns the
+//!   inbound server for a pod's port, as it is served to proxies. An
+//!   `ExternalWorkload` may be looked up with `external=<name>` in place of
+//!   `pod`.
+//! - `GET /policy/watchers` lists the proxies that are watching inbound
+//!   policies, with the time at which each last received an update.
+//! - `GET /metrics` returns the controller's Prometheus metrics.
+
+use crate::{grpc, k8s::chrono, SharedIndex, Workload};
+use futures::prelude::*;
+use hyper::{http, Body, Request, Response};
+use linkerd_policy_controller_core::{
+    grpc_route::{self, GrpcRouteMatch, InboundGrpcRoute},
+    http_route::{
+        self, HeaderMatch, HostMatch, HttpRouteMatch, InboundFilter, InboundHttpRoute, PathMatch,
+        PathModifier, QueryParamMatch,
+    },
+    AuthorizationAction, AuthorizationRef, ClientAuthentication, ClientAuthorization,
+    IdentityMatch, InboundServer, ProxyProtocol, ServerRef,
+};
+use prometheus_client::registry::Registry;
+use serde_json::{json, Value};
+use std::{collections::HashMap, net::SocketAddr, num::NonZeroU16, sync::Arc, time};
+
+#[derive(Clone)]
+struct Admin {
+    index: SharedIndex,
+    watchers: grpc::Watchers,
+    metrics: Arc<Registry>,
+}
+
+/// Serves the admin endpoints on `addr` until `shutdown` completes.
+pub async fn serve(
+    addr: SocketAddr,
+    index: SharedIndex,
+    watchers: grpc::Watchers,
+    metrics: Arc<Registry>,
+    shutdown: impl Future<Output = ()>,
+) -> hyper::Result<()> {
+    let admin = Admin {
+        index,
+        watchers,
+        metrics,
+    };
+    hyper::Server::bind(&addr)
+        .serve(hyper::service::make_service_fn(move |_| {
+            let admin = admin.clone();
+            future::ok::<_, std::convert::Infallible>(hyper::service::service_fn(
+                move |req: Request<Body>| {
+                    let admin = admin.clone();
+                    async move { Ok::<_, std::convert::Infallible>(handle(&admin, req).await) }
+                },
+            ))
+        }))
+        .with_graceful_shutdown(shutdown)
+        .await
+}
+
+async fn handle(admin: &Admin, req: Request<Body>) -> Response<Body> {
+    if req.method() != http::Method::GET {
+        return rsp(http::StatusCode::METHOD_NOT_ALLOWED, "method not allowed\n");
+    }
+
+    match req.uri().path() {
+        "/policy/inbound" => inbound(&admin.index, req.uri().query().unwrap_or_default()),
+        "/policy/watchers" => json_rsp(&Value::Array(
+            admin.watchers.list().iter().map(watcher).collect(),
+        )),
+        "/metrics" => metrics(admin),
+        _ => rsp(http::StatusCode::NOT_FOUND, "not found\n"),
+    }
+}
+
+fn metrics(admin: &Admin) -> Response<Body> {
+    // Gauges that describe the index's state are computed on demand.
+    admin.index.read().refresh_metrics();
+
+    let mut body = Vec::new();
+    if let Err(error) = prometheus_client::encoding::text::encode(&mut body, &admin.metrics) {
+        return rsp(
+            http::StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to encode metrics: {}\n", error),
+        );
+    }
+    Response::builder()
+        .status(http::StatusCode::OK)
+        .header(
+            http::header::CONTENT_TYPE,
+            "application/openmetrics-text; version=1.0.0; charset=utf-8",
+        )
+        .body(body.into())
+        .expect("response must be valid")
+}
+
+fn inbound(index: &SharedIndex, query: &str) -> Response<Body> {
+    let (ns, workload, port) = match parse_inbound_query(query) {
+        Ok(target) => target,
+        Err(error) => return rsp(http::StatusCode::BAD_REQUEST, format!("{}\n", error)),
+    };
+
+    // Lookups must not index the port, since that would create servers for
+    // every port that is queried.
+    let index = index.read();
+    let server = match workload {
+        Workload::Pod(pod) => index.pod_server(&ns, &pod, port),
+        Workload::External(name) => index.external_workload_server(&ns, &name, port),
+    };
+    match server {
+        Some(server) => json_rsp(&inbound_server(&server)),
+        None => rsp(http::StatusCode::NOT_FOUND, "unknown workload\n"),
+    }
+}
+
+fn parse_inbound_query(query: &str) -> anyhow::Result<(String, Workload, NonZeroU16)> {
+    let params = form_urlencoded::parse(query.as_bytes()).collect::<HashMap<_, _>>();
+
+    let ns = match params.get("ns") {
+        Some(ns) if !ns.is_empty() => ns.to_string(),
+        _ => anyhow::bail!("the `ns` parameter is required"),
+    };
+    let workload = match (params.get("pod"), params.get("external")) {
+        (Some(pod), None) if !pod.is_empty() => Workload::Pod(pod.to_string()),
+        (None, Some(name)) if !name.is_empty() => Workload::External(name.to_string()),
+        _ => anyhow::bail!("exactly one of the `pod` or `external` parameters is required"),
+    };
+    let port = match params.get("port").map(|p| p.parse::<NonZeroU16>()) {
+        Some(Ok(port)) => port,
+        _ => anyhow::bail!("the `port` parameter must be a valid port number"),
+    };
+
+    Ok((ns, workload, port))
+}
+
+fn rsp(status: http::StatusCode, body: impl Into<Body>) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(body.into())
+        .expect("response must be valid")
+}
+
+fn json_rsp(value: &Value) -> Response<Body> {
+    let body = serde_json::to_vec_pretty(value).expect("JSON values must serialize");
+    Response::builder()
+        .status(http::StatusCode::OK)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(body.into())
+        .expect("response must be valid")
+}
+
+// === JSON representations ===
+
+fn watcher(w: &grpc::Watcher) -> Value {
+    let workload = match &w.workload {
+        Workload::Pod(name) => json!({ "kind": "Pod", "name": name }),
+        Workload::External(name) => json!({ "kind": "ExternalWorkload", "name": name }),
+    };
+    json!({
+        "namespace": w.namespace,
+        "workload": workload,
+        "port": w.port.get(),
+        "connectedSince": timestamp(w.connected_at),
+        "lastUpdate": w.last_update_at.map(timestamp),
+        "updates": w.updates,
+    })
+}
+
+fn timestamp(t: time::SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()
+}
+
+fn inbound_server(srv: &InboundServer) -> Value {
+    let server = match &srv.reference {
+        ServerRef::Default(name) => json!({ "kind": "default", "name": name }),
+        ServerRef::Server(name) => json!({ "kind": "Server", "name": name }),
+    };
+
+    let protocol = match srv.protocol {
+        ProxyProtocol::Detect { timeout } => {
+            json!({ "type": "detect", "timeout": format!("{:?}", timeout) })
+        }
+        ProxyProtocol::Http1 => json!({ "type": "HTTP/1" }),
+        ProxyProtocol::Http2 => json!({ "type": "HTTP/2" }),
+        ProxyProtocol::Grpc => json!({ "type": "gRPC" }),
+        ProxyProtocol::Opaque => json!({ "type": "opaque" }),
+        ProxyProtocol::Tls => json!({ "type": "TLS" }),
+    };
+
+    // Routes are rendered as the fragments that are served to proxies.
+    let http_routes = http_route::fragments_by_precedence(srv.http_routes.iter());
+    let grpc_routes = grpc_route::fragments_by_precedence(srv.grpc_routes.iter());
+
+    json!({
+        "server": server,
+        "protocol": protocol,
+        "authorizations": authorizations(&srv.authorizations),
+        "httpRoutes": http_routes
+            .into_iter()
+            .map(|(name, route)| http_route(name, &route))
+            .collect::<Vec<_>>(),
+        "grpcRoutes": grpc_routes
+            .into_iter()
+            .map(|(name, route)| grpc_route(name, &route))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn authorizations<'a>(
+    authzs: impl IntoIterator<Item = (&'a AuthorizationRef, &'a ClientAuthorization)>,
+) -> Vec<Value> {
+    let mut authzs = authzs
+        .into_iter()
+        .map(|(reference, authz)| {
+            let source = match reference {
+                AuthorizationRef::Default(name) => json!({ "kind": "default", "name": name }),
+                AuthorizationRef::ServerAuthorization(name) => {
+                    json!({ "kind": "ServerAuthorization", "name": name })
+                }
+                AuthorizationRef::AuthorizationPolicy(name) => {
+                    json!({ "kind": "AuthorizationPolicy", "name": name })
+                }
+                AuthorizationRef::AuditedPolicy(name) => {
+                    json!({ "kind": "AuthorizationPolicy", "name": name, "audited": true })
+                }
+            };
+            (source.to_string(), authorization(source, authz))
+        })
+        .collect::<Vec<_>>();
+    authzs.sort_by(|(a, _), (b, _)| a.cmp(b));
+    authzs.into_iter().map(|(_, authz)| authz).collect()
+}
+
+fn authorization(source: Value, authz: &ClientAuthorization) -> Value {
+    let action = match authz.action {
+        AuthorizationAction::Allow => "allow",
+        AuthorizationAction::Deny => "deny",
+        AuthorizationAction::Audit => "audit",
+        AuthorizationAction::AuditDeny => "auditDeny",
+    };
+
+    let authentication = match &authz.authentication {
+        ClientAuthentication::Unauthenticated => json!({ "type": "unauthenticated" }),
+        ClientAuthentication::TlsUnauthenticated => json!({ "type": "tlsUnauthenticated" }),
+        ClientAuthentication::TlsAuthenticated(ids) => json!({
+            "type": "tlsAuthenticated",
+            "identities": ids.iter().map(IdentityMatch::to_string).collect::<Vec<_>>(),
+        }),
+    };
+
+    let networks = authz
+        .networks
+        .iter()
+        .map(|n| {
+            json!({
+                "net": n.net.to_string(),
+                "except": n.except.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "source": source,
+        "action": action,
+        "networks": networks,
+        "authentication": authentication,
+    })
+}
+
+fn http_route(name: &str, route: &InboundHttpRoute) -> Value {
+    json!({
+        "name": name,
+        "rank": route.rank,
+        "hostnames": route.hostnames.iter().map(hostname).collect::<Vec<_>>(),
+        "rules": route
+            .rules
+            .iter()
+            .map(|rule| json!({
+                "matches": rule.matches.iter().map(http_route_match).collect::<Vec<_>>(),
+                "filters": rule.filters.iter().map(filter).collect::<Vec<_>>(),
+            }))
+            .collect::<Vec<_>>(),
+        "authorizations": authorizations(&route.authorizations),
+    })
+}
+
+fn grpc_route(name: &str, route: &InboundGrpcRoute) -> Value {
+    json!({
+        "name": name,
+        "rank": route.rank,
+        "hostnames": route.hostnames.iter().map(hostname).collect::<Vec<_>>(),
+        "rules": route
+            .rules
+            .iter()
+            .map(|rule| json!({
+                "matches": rule.matches.iter().map(grpc_route_match).collect::<Vec<_>>(),
+                "filters": rule.filters.iter().map(filter).collect::<Vec<_>>(),
+            }))
+            .collect::<Vec<_>>(),
+        "authorizations": authorizations(&route.authorizations),
+    })
+}
+
+fn hostname(host: &HostMatch) -> String {
+    match host {
+        HostMatch::Exact(host) => host.clone(),
+        HostMatch::Suffix { reverse_labels } => {
+            let labels = reverse_labels.iter().rev().cloned().collect::<Vec<_>>();
+            format!("*.{}", labels.join("."))
+        }
+    }
+}
+
+fn http_route_match(m: &HttpRouteMatch) -> Value {
+    let path = m.path.as_ref().map(|path| match path {
+        PathMatch::Exact(p) => json!({ "exact": p }),
+        PathMatch::Prefix(p) => json!({ "prefix": p }),
+        PathMatch::Regex(r) => json!({ "regex": r.as_str() }),
+    });
+    let query_params = m
+        .query_params
+        .iter()
+        .map(|q| match q {
+            QueryParamMatch::Exact(name, value) => json!({ "name": name, "exact": value }),
+            QueryParamMatch::Regex(name, r) => json!({ "name": name, "regex": r.as_str() }),
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "path": path,
+        "headers": m.headers.iter().map(header_match).collect::<Vec<_>>(),
+        "queryParams": query_params,
+        "method": m.method.as_ref().map(|m| m.as_str()),
+    })
+}
+
+fn grpc_route_match(m: &GrpcRouteMatch) -> Value {
+    let method = m.method.as_ref().map(|m| {
+        json!({
+            "service": m.service,
+            "method": m.method,
+        })
+    });
+    json!({
+        "method": method,
+        "headers": m.headers.iter().map(header_match).collect::<Vec<_>>(),
+    })
+}
+
+fn header_match(h: &HeaderMatch) -> Value {
+    match h {
+        HeaderMatch::Exact(name, value) => json!({
+            "name": name.as_str(),
+            "exact": String::from_utf8_lossy(value.as_bytes()),
+        }),
+        HeaderMatch::Regex(name, r) => json!({ "name": name.as_str(), "regex": r.as_str() }),
+    }
+}
+
+fn filter(filter: &InboundFilter) -> Value {
+    fn headers(
+        add: &[(http::HeaderName, http::HeaderValue)],
+        set: &[(http::HeaderName, http::HeaderValue)],
+        remove: &[http::HeaderName],
+    ) -> Value {
+        let pairs = |hs: &[(http::HeaderName, http::HeaderValue)]| {
+            hs.iter()
+                .map(|(n, v)| {
+                    json!({
+                        "name": n.as_str(),
+                        "value": String::from_utf8_lossy(v.as_bytes()),
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        json!({
+            "add": pairs(add),
+            "set": pairs(set),
+            "remove": remove.iter().map(|n| n.as_str()).collect::<Vec<_>>(),
+        })
+    }
+
+    fn path(path: &Option<PathModifier>) -> Value {
+        match path {
+            Some(PathModifier::Full(p)) => json!({ "replaceFullPath": p }),
+            Some(PathModifier::Prefix(p)) => json!({ "replacePrefixMatch": p }),
+            None => Value::Null,
+        }
+    }
+
+    match filter {
+        InboundFilter::RequestHeaderModifier(f) => json!({
+            "type": "RequestHeaderModifier",
+            "requestHeaderModifier": headers(&f.add, &f.set, &f.remove),
+        }),
+        InboundFilter::RequestRedirect(f) => json!({
+            "type": "RequestRedirect",
+            "requestRedirect": {
+                "scheme": f.scheme.as_ref().map(|s| s.as_str()),
+                "hostname": f.host,
+                "path": path(&f.path),
+                "port": f.port.map(NonZeroU16::get),
+                "statusCode": f.status.map(|s| s.as_u16()),
+            },
+        }),
+        InboundFilter::FailureInjector(f) => json!({
+            "type": "FailureInjector",
+            "failureInjector": {
+                "statusCode": f.status.as_u16(),
+                "message": f.message,
+                "ratio": {
+                    "numerator": f.ratio.numerator,
+                    "denominator": f.ratio.denominator,
+                },
+            },
+        }),
+    }
+}
@@ -1,10 +1,11 @@
 use crate::k8s::{
+    gateway::{grpcroute, GrpcRoute, GrpcRouteSpec},
     labels,
     policy::{
-        httproute, AuthorizationPolicy, AuthorizationPolicySpec, HttpRoute, HttpRouteSpec,
-        LocalTargetRef, MeshTLSAuthentication, MeshTLSAuthenticationSpec, NamespacedTargetRef,
-        NetworkAuthentication, NetworkAuthenticationSpec, Server, ServerAuthorization,
-        ServerAuthorizationSpec, ServerSpec,
+        httproute, server::Port, AuthorizationPolicy, AuthorizationPolicySpec, HttpRoute,
+        HttpRouteSpec, LocalTargetRef, MeshTLSAuthentication, MeshTLSAuthenticationSpec,
+        NamespacedTargetRef, NetworkAuthentication, NetworkAuthenticationSpec, Server,
+        ServerAuthorization, ServerAuthorizationSpec, ServerSpec,
     },
 };
 use anyhow::{anyhow, bail, Result};
@@ -127,6 +128,10 @@ impl Admission {
             return self.admit_spec::<gateway::HttpRouteSpec>(req).await;
         }
 
+        if is_kind::<GrpcRoute>(&req) {
+            return self.admit_spec::<GrpcRouteSpec>(req).await;
+        }
+
         AdmissionResponse::invalid(format_args!(
             "unsupported resource type: {}.{}.{}",
             req.kind.group, req.kind.version, req.kind.kind
@@ -213,6 +218,10 @@ fn validate_policy_target(ns: &str, tgt: &LocalTargetRef) -> Result<()> {
         return Ok(());
     }
 
+    if tgt.targets_kind::<GrpcRoute>() {
+        return Ok(());
+    }
+
     if tgt.targets_kind::<Namespace>() {
         if tgt.name != ns {
             bail!("cannot target another namespace: {}", tgt.name);
@@ -226,7 +235,12 @@ fn validate_policy_target(ns: &str, tgt: &LocalTargetRef) -> Result<()> {
 #[async_trait::async_trait]
 impl Validate<AuthorizationPolicySpec> for Admission {
     async fn validate(self, ns: &str, _name: &str, spec: AuthorizationPolicySpec) -> Result<()> {
-        validate_policy_target(ns, &spec.target_ref)?;
+        match (&spec.target_ref, &spec.target_selector) {
+            (Some(tgt), None) => validate_policy_target(ns, tgt)?,
+            (None, Some(_)) => {}
+            (Some(_), Some(_)) => bail!("targetRef and targetSelector may not both be set"),
+            (None, None) => bail!("one of targetRef or targetSelector must be set"),
+        }
 
         let mtls_authns_count = spec
             .required_authentication_refs
@@ -316,6 +330,14 @@ impl Validate<ServerSpec> for Admission {
     // TODO(ver) this isn't rigorous about detecting servers that select the same port if one port
     // specifies a numeric port and the other specifies the port's name.
     async fn validate(self, ns: &str, name: &str, spec: ServerSpec) -> Result<()> {
+        // Port names must contain at least one letter, so a name without any is most likely a
+        // malformed port range (e.g. `9019-9000`).
+        if let Port::Name(port) = &spec.port {
+            if !port.chars().any(|c| c.is_ascii_alphabetic()) {
+                bail!("invalid port name or range: {}", port);
+            }
+        }
+
         // Since we can't ensure that the local index is up-to-date with the API server (i.e.
         // updates may be delayed), we issue an API request to get the latest state of servers in
         // the namespace.
@@ -324,7 +346,7 @@ impl Validate<ServerSpec> for Admission {
             .await?;
         for server in servers.items.into_iter() {
             if server.name_unchecked() != name
-                && server.spec.port == spec.port
+                && server.spec.port.overlaps(&spec.port)
                 && Self::overlaps(&server.spec.pod_selector, &spec.pod_selector)
             {
                 bail!("identical server spec already exists");
@@ -428,21 +450,28 @@ impl Validate<HttpRouteSpec> for Admission {
     async fn validate(self, _ns: &str, _name: &str, spec: HttpRouteSpec) -> Result<()> {
         // The validation for the policy.linkerd.io HTTPRoute type is much
         // simpler than for the Gateway API version: the route must only target
-        // `Server` resources.
+        // `Server` or `Service` resources.
         //
         // We don't have to do any validation that unsupported filters aren't
         // present, because Linkerd's HTTPRoute CRD doesn't include those
         // filters at all.
-        let all_target_servers = spec
+        let all_target_supported = spec
             .inner
             .parent_refs
             .iter()
             .flatten()
-            .all(parent_ref_targets_server);
+            .all(|p| parent_ref_targets_server(p) || parent_ref_targets_service(p));
         anyhow::ensure!(
-            all_target_servers,
-            "policy.linkerd.io HTTPRoutes must target only Server resources"
+            all_target_supported,
+            "policy.linkerd.io HTTPRoutes must target only Server or Service resources"
         );
+
+        for rule in spec.rules.iter().flatten() {
+            if let Some(timeouts) = &rule.timeouts {
+                validate_http_route_timeouts(timeouts)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -450,7 +479,30 @@ impl Validate<HttpRouteSpec> for Admission {
 #[async_trait::async_trait]
 impl Validate<gateway::HttpRouteSpec> for Admission {
     async fn validate(self, _ns: &str, _name: &str, spec: gateway::HttpRouteSpec) -> Result<()> {
-        // Only validate HttpRoutes which have a Server as a parent_ref.
+        // Only validate HttpRoutes which have a Server or Service as a
+        // parent_ref.
+        let targets_linkerd = spec
+            .inner
+            .parent_refs
+            .iter()
+            .flatten()
+            .any(|p| parent_ref_targets_server(p) || parent_ref_targets_service(p));
+        if !targets_linkerd {
+            return Ok(());
+        }
+
+        for rule in spec.rules.iter().flatten() {
+            validate_gateway_http_route_rule(rule)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Validate<GrpcRouteSpec> for Admission {
+    async fn validate(self, ns: &str, _name: &str, spec: GrpcRouteSpec) -> Result<()> {
+        // Only validate GrpcRoutes which have a Server as a parent_ref.
         let targets_server = spec
             .inner
             .parent_refs
@@ -461,8 +513,23 @@ impl Validate<gateway::HttpRouteSpec> for Admission {
             return Ok(());
         }
 
+        // Only HTTPRoutes may reference a Server in another namespace.
+        if let Some(server_ns) = spec
+            .inner
+            .parent_refs
+            .iter()
+            .flatten()
+            .filter(|p| parent_ref_targets_server(p))
+            .find_map(|p| p.namespace.as_deref().filter(|server_ns| *server_ns != ns))
+        {
+            bail!(
+                "GRPCRoute may not reference a Server in another namespace ({})",
+                server_ns
+            );
+        }
+
         for rule in spec.rules.iter().flatten() {
-            validate_gateway_http_route_rule(rule)?;
+            validate_grpc_route_rule(rule)?;
         }
 
         Ok(())
@@ -478,6 +545,32 @@ fn parent_ref_targets_server(p: &httproute::ParentReference) -> bool {
     }
 }
 
+fn parent_ref_targets_service(p: &httproute::ParentReference) -> bool {
+    let is_core = p
+        .group
+        .as_deref()
+        .map_or(true, |g| g.is_empty() || g == "core");
+    let is_service = p
+        .kind
+        .as_deref()
+        .map_or(false, |k| k.eq_ignore_ascii_case("service"));
+    is_core && is_service
+}
+
+fn validate_http_route_timeouts(timeouts: &httproute::HttpRouteTimeouts) -> Result<()> {
+    let request = timeouts.request_timeout()?;
+    let backend_request = timeouts.backend_request_timeout()?;
+    if let (Some(request), Some(backend_request)) = (request, backend_request) {
+        anyhow::ensure!(
+            backend_request <= request,
+            "backendRequest timeout ({:?}) must not exceed request timeout ({:?})",
+            backend_request,
+            request
+        );
+    }
+    Ok(())
+}
+
 fn validate_gateway_http_route_rule(rule: &gateway::HttpRouteRule) -> Result<()> {
     if let Some(filters) = &rule.filters {
         validate_gateway_http_route_filters(filters)?;
@@ -509,3 +602,49 @@ fn validate_gateway_http_route_filters(filters: &[gateway::HttpRouteFilter]) ->
     }
     Ok(())
 }
+
+fn validate_grpc_route_rule(rule: &grpcroute::GrpcRouteRule) -> Result<()> {
+    for m in rule.matches.iter().flatten() {
+        if let Some(method) = &m.method {
+            validate_grpc_method_match(method)?;
+        }
+    }
+
+    if let Some(filters) = &rule.filters {
+        validate_grpc_route_filters(filters)?;
+    }
+
+    for backend_ref in rule.backend_refs.iter().flatten() {
+        if let Some(filters) = &backend_ref.filters {
+            validate_grpc_route_filters(filters)?;
+        }
+    }
+    Ok(())
+}
+
+fn validate_grpc_method_match(method: &grpcroute::GrpcMethodMatch) -> Result<()> {
+    if let Some(grpcroute::GrpcMethodMatchType::RegularExpression) = method.r#type {
+        bail!("RegularExpression method matches are not supported");
+    }
+
+    let is_set = |s: &Option<String>| s.as_deref().map_or(false, |s| !s.is_empty());
+    if !is_set(&method.service) && !is_set(&method.method) {
+        bail!("method matches must specify a service or a method");
+    }
+    Ok(())
+}
+
+fn validate_grpc_route_filters(filters: &[grpcroute::GrpcRouteFilter]) -> Result<()> {
+    for filter in filters.iter() {
+        match filter {
+            grpcroute::GrpcRouteFilter::ExtensionRef { .. } => {
+                bail!("ExtensionRef filters are not supported")
+            }
+            grpcroute::GrpcRouteFilter::RequestHeaderModifier { .. } => {}
+            grpcroute::GrpcRouteFilter::RequestMirror { .. } => {
+                bail!("RequestMirror filters are not supported")
+            }
+        }
+    }
+    Ok(())
+}
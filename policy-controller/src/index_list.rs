@@ -0,0 +1,64 @@
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
+use kubert::index::IndexNamespacedResource;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// A list of indexes for a single resource type.
+///
+/// An `IndexList` acts as an index for the resource type, fanning each update
+/// out to every index in the list so that a single watch may feed several
+/// indexes.
+#[derive(Debug)]
+pub struct IndexList<A, T = A> {
+    index: Arc<RwLock<A>>,
+    tail: Option<T>,
+}
+
+// === impl IndexList ===
+
+impl<A> IndexList<A> {
+    pub fn new(index: Arc<RwLock<A>>) -> Self {
+        Self { index, tail: None }
+    }
+}
+
+impl<A, T> IndexList<A, T> {
+    pub fn push<B>(self, index: Arc<RwLock<B>>) -> IndexList<B, Self> {
+        IndexList {
+            index,
+            tail: Some(self),
+        }
+    }
+
+    pub fn shared(self) -> Arc<RwLock<Self>> {
+        Arc::new(RwLock::new(self))
+    }
+}
+
+impl<A, T, R> IndexNamespacedResource<R> for IndexList<A, T>
+where
+    A: IndexNamespacedResource<R>,
+    T: IndexNamespacedResource<R>,
+    R: Clone,
+{
+    fn apply(&mut self, resource: R) {
+        if let Some(tail) = &mut self.tail {
+            tail.apply(resource.clone());
+        }
+        self.index.write().apply(resource);
+    }
+
+    fn delete(&mut self, namespace: String, name: String) {
+        if let Some(tail) = &mut self.tail {
+            tail.delete(namespace.clone(), name.clone());
+        }
+        self.index.write().delete(namespace, name);
+    }
+
+    fn reset(&mut self, resources: Vec<R>, removed: HashMap<String, HashSet<String>>) {
+        if let Some(tail) = &mut self.tail {
+            tail.reset(resources.clone(), removed.clone());
+        }
+        self.index.write().reset(resources, removed);
+    }
+}
@@ -0,0 +1,150 @@
+//! Coordinates leadership across controller replicas with a `coordination.k8s.io` Lease.
+//!
+//! Only the replica holding the lease should write to the Kubernetes API (e.g.
+//! resource statuses). All replicas continue to index resources and serve
+//! discovery.
+
+use anyhow::Result;
+use k8s_openapi::{
+    api::coordination::v1::{Lease, LeaseSpec},
+    apimachinery::pkg::apis::meta::v1::MicroTime,
+    chrono::{self, Utc},
+};
+use kube::api::{ObjectMeta, PostParams};
+use tokio::{sync::watch, time};
+use tracing::{debug, info, warn};
+
+/// Claims and renews a Lease, publishing whether this replica is the leader.
+#[derive(Clone)]
+pub struct Claimer {
+    api: kube::Api<Lease>,
+    name: String,
+    identity: String,
+    duration: time::Duration,
+    renew_interval: time::Duration,
+}
+
+// === impl Claimer ===
+
+impl Claimer {
+    pub fn new(
+        client: kube::Client,
+        namespace: &str,
+        name: impl ToString,
+        identity: impl ToString,
+        duration: time::Duration,
+    ) -> Self {
+        Self {
+            api: kube::Api::namespaced(client, namespace),
+            name: name.to_string(),
+            identity: identity.to_string(),
+            duration,
+            // Renew well before the lease expires so that a slow API server
+            // doesn't cause leadership to flap.
+            renew_interval: duration / 3,
+        }
+    }
+
+    /// Spawns a task that claims the lease, returning a receiver that is
+    /// updated whenever this replica gains or loses leadership.
+    pub fn spawn(self, drain: drain::Watch) -> watch::Receiver<bool> {
+        let (tx, rx) = watch::channel(false);
+        tokio::spawn(self.run(tx, drain));
+        rx
+    }
+
+    async fn run(self, tx: watch::Sender<bool>, drain: drain::Watch) {
+        let shutdown = drain.signaled();
+        tokio::pin!(shutdown);
+
+        loop {
+            let is_leader = match self.try_claim().await {
+                Ok(claimed) => claimed,
+                Err(error) => {
+                    warn!(%error, lease = %self.name, "Failed to claim lease");
+                    false
+                }
+            };
+            if *tx.borrow() != is_leader {
+                info!(lease = %self.name, identity = %self.identity, is_leader, "Leadership changed");
+                if tx.send(is_leader).is_err() {
+                    return;
+                }
+            }
+
+            tokio::select! {
+                _ = time::sleep(self.renew_interval) => {}
+                _ = (&mut shutdown) => return,
+            }
+        }
+    }
+
+    /// Attempts to claim (or renew) the lease, returning true if this replica
+    /// holds the lease.
+    ///
+    /// Updates are made with the lease's resource version so that concurrent
+    /// claims conflict instead of overwriting one another.
+    async fn try_claim(&self) -> Result<bool> {
+        let now = Utc::now();
+
+        let mut lease = match self.api.get_opt(&self.name).await? {
+            Some(lease) => lease,
+            None => {
+                debug!(lease = %self.name, "Creating lease");
+                let lease = Lease {
+                    metadata: ObjectMeta {
+                        name: Some(self.name.clone()),
+                        ..Default::default()
+                    },
+                    spec: Some(LeaseSpec {
+                        holder_identity: Some(self.identity.clone()),
+                        lease_duration_seconds: Some(self.duration.as_secs() as i32),
+                        acquire_time: Some(MicroTime(now)),
+                        renew_time: Some(MicroTime(now)),
+                        lease_transitions: Some(0),
+                    }),
+                };
+                return match self.api.create(&PostParams::default(), &lease).await {
+                    Ok(_) => Ok(true),
+                    Err(kube::Error::Api(e)) if e.code == 409 => Ok(false),
+                    Err(e) => Err(e.into()),
+                };
+            }
+        };
+
+        let spec = lease.spec.get_or_insert_with(Default::default);
+        if spec.holder_identity.as_deref() == Some(&*self.identity) {
+            spec.renew_time = Some(MicroTime(now));
+        } else {
+            let duration = spec
+                .lease_duration_seconds
+                .map(|s| chrono::Duration::seconds(s.into()))
+                .unwrap_or_else(|| chrono::Duration::from_std(self.duration).unwrap());
+            let expired = match spec.renew_time.as_ref() {
+                Some(MicroTime(renewed)) => *renewed + duration < now,
+                None => true,
+            };
+            if !expired {
+                debug!(lease = %self.name, holder = ?spec.holder_identity, "Lease is held by another replica");
+                return Ok(false);
+            }
+
+            debug!(lease = %self.name, holder = ?spec.holder_identity, "Claiming expired lease");
+            spec.holder_identity = Some(self.identity.clone());
+            spec.lease_duration_seconds = Some(self.duration.as_secs() as i32);
+            spec.acquire_time = Some(MicroTime(now));
+            spec.renew_time = Some(MicroTime(now));
+            spec.lease_transitions = Some(spec.lease_transitions.unwrap_or(0) + 1);
+        }
+
+        match self
+            .api
+            .replace(&self.name, &PostParams::default(), &lease)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(kube::Error::Api(e)) if e.code == 409 => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
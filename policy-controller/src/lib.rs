@@ -4,32 +4,55 @@
 use anyhow::Result;
 use std::num::NonZeroU16;
 
+pub mod admin;
 mod admission;
+pub mod index_list;
+pub mod lease;
+pub mod status;
 
 pub use self::admission::Admission;
 pub use linkerd_policy_controller_core::{
-    DiscoverInboundServer, InboundServer, InboundServerStream, IpNet,
+    DiscoverInboundServer, DiscoverOutboundPolicy, InboundServer, InboundServerStream, IpNet,
+    OutboundPolicy, OutboundPolicyStream, Workload,
 };
 pub use linkerd_policy_controller_grpc as grpc;
 pub use linkerd_policy_controller_k8s_api as k8s;
-pub use linkerd_policy_controller_k8s_index::{ClusterInfo, DefaultPolicy, Index, SharedIndex};
+pub use linkerd_policy_controller_k8s_index::{
+    outbound, ClusterInfo, DefaultPolicy, Index, SharedIndex,
+};
 
 #[derive(Clone, Debug)]
 pub struct IndexDiscover(SharedIndex);
 
+#[derive(Clone, Debug)]
+pub struct OutboundDiscover(outbound::SharedIndex);
+
 impl IndexDiscover {
     pub fn new(index: SharedIndex) -> Self {
         Self(index)
     }
+
+    fn server_rx(
+        &self,
+        namespace: &str,
+        workload: &Workload,
+        port: NonZeroU16,
+    ) -> Result<tokio::sync::watch::Receiver<InboundServer>> {
+        let mut index = self.0.write();
+        match workload {
+            Workload::Pod(pod) => index.pod_server_rx(namespace, pod, port),
+            Workload::External(name) => index.external_workload_server_rx(namespace, name, port),
+        }
+    }
 }
 
 #[async_trait::async_trait]
-impl DiscoverInboundServer<(String, String, NonZeroU16)> for IndexDiscover {
+impl DiscoverInboundServer<(String, Workload, NonZeroU16)> for IndexDiscover {
     async fn get_inbound_server(
         &self,
-        (namespace, pod, port): (String, String, NonZeroU16),
+        (namespace, workload, port): (String, Workload, NonZeroU16),
     ) -> Result<Option<InboundServer>> {
-        let rx = match self.0.write().pod_server_rx(&namespace, &pod, port) {
+        let rx = match self.server_rx(&namespace, &workload, port) {
             Ok(rx) => rx,
             Err(_) => return Ok(None),
         };
@@ -39,9 +62,40 @@ impl DiscoverInboundServer<(String, String, NonZeroU16)> for IndexDiscover {
 
     async fn watch_inbound_server(
         &self,
-        (namespace, pod, port): (String, String, NonZeroU16),
+        (namespace, workload, port): (String, Workload, NonZeroU16),
     ) -> Result<Option<InboundServerStream>> {
-        match self.0.write().pod_server_rx(&namespace, &pod, port) {
+        match self.server_rx(&namespace, &workload, port) {
+            Ok(rx) => Ok(Some(Box::pin(tokio_stream::wrappers::WatchStream::new(rx)))),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+impl OutboundDiscover {
+    pub fn new(index: outbound::SharedIndex) -> Self {
+        Self(index)
+    }
+}
+
+#[async_trait::async_trait]
+impl DiscoverOutboundPolicy<(String, String, NonZeroU16)> for OutboundDiscover {
+    async fn get_outbound_policy(
+        &self,
+        (namespace, service, port): (String, String, NonZeroU16),
+    ) -> Result<Option<OutboundPolicy>> {
+        let rx = match self.0.write().service_policy_rx(&namespace, &service, port) {
+            Ok(rx) => rx,
+            Err(_) => return Ok(None),
+        };
+        let policy = (*rx.borrow()).clone();
+        Ok(Some(policy))
+    }
+
+    async fn watch_outbound_policy(
+        &self,
+        (namespace, service, port): (String, String, NonZeroU16),
+    ) -> Result<Option<OutboundPolicyStream>> {
+        match self.0.write().service_policy_rx(&namespace, &service, port) {
             Ok(rx) => Ok(Some(Box::pin(tokio_stream::wrappers::WatchStream::new(rx)))),
             Err(_) => Ok(None),
         }
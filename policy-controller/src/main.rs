@@ -6,10 +6,13 @@ use clap::Parser;
 use futures::prelude::*;
 use kube::api::ListParams;
 use linkerd_policy_controller::{
-    grpc, k8s, Admission, ClusterInfo, DefaultPolicy, Index, IndexDiscover, IpNet, SharedIndex,
+    grpc, index_list::IndexList, k8s, lease, outbound, status, Admission, ClusterInfo,
+    DefaultPolicy, Index, IndexDiscover, IpNet, OutboundDiscover, SharedIndex,
 };
-use std::net::SocketAddr;
-use tokio::time;
+use linkerd_policy_controller_k8s_index as k8s_index;
+use prometheus_client::registry::Registry;
+use std::{net::SocketAddr, sync::Arc};
+use tokio::{sync::mpsc, time};
 use tracing::{info, info_span, instrument, Instrument};
 
 #[cfg(all(target_os = "linux", target_arch = "x86_64", target_env = "gnu"))]
@@ -18,6 +21,9 @@ static GLOBAL: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
 const DETECT_TIMEOUT: time::Duration = time::Duration::from_secs(10);
 
+const STATUS_LEASE_NAME: &str = "policy-controller-write";
+const STATUS_LEASE_DURATION: time::Duration = time::Duration::from_secs(30);
+
 #[derive(Debug, Parser)]
 #[clap(name = "policy", about = "A policy resource prototype")]
 struct Args {
@@ -48,6 +54,14 @@ struct Args {
     #[clap(long, default_value = "0.0.0.0:8090")]
     grpc_addr: SocketAddr,
 
+    /// The address of the server that describes published policies (e.g. `/policy/inbound`) and
+    /// serves metrics.
+    ///
+    /// It listens on the loopback interface by default, so it is only reachable from within the
+    /// pod (e.g. with `kubectl port-forward`).
+    #[clap(long, default_value = "127.0.0.1:9991")]
+    policy_admin_addr: SocketAddr,
+
     /// Network CIDRs of pod IPs.
     ///
     /// The default includes all private networks.
@@ -57,14 +71,35 @@ struct Args {
     )]
     cluster_networks: IpNets,
 
+    /// Discovers the cluster's networks from the pod CIDRs of `Node` resources.
+    ///
+    /// The `--cluster-networks` are used until a node reports a pod CIDR.
+    #[clap(long)]
+    discover_cluster_networks: bool,
+
+    /// Network CIDRs of Service cluster IPs.
+    ///
+    /// These are merged into the discovered cluster networks, since nodes do not report the
+    /// service CIDR. Only used with `--discover-cluster-networks`.
+    #[clap(long)]
+    service_networks: Option<IpNets>,
+
     #[clap(long, default_value = "cluster.local")]
     identity_domain: String,
 
+    /// The cluster's DNS domain, used to resolve Service authorities.
+    #[clap(long, default_value = "cluster.local")]
+    cluster_domain: String,
+
     #[clap(long, default_value = "all-unauthenticated")]
     default_policy: DefaultPolicy,
 
     #[clap(long, default_value = "linkerd")]
     control_plane_namespace: String,
+
+    /// The name of this controller's pod, used to claim the status lease.
+    #[clap(long, env = "LINKERD_POLICY_CONTROLLER_POD_NAME")]
+    controller_pod_name: String,
 }
 
 #[tokio::main]
@@ -76,11 +111,16 @@ async fn main() -> Result<()> {
         log_format,
         server,
         grpc_addr,
+        policy_admin_addr,
         admission_controller_disabled,
         identity_domain,
+        cluster_domain,
         cluster_networks: IpNets(cluster_networks),
+        discover_cluster_networks,
+        service_networks,
         default_policy,
         control_plane_namespace,
+        controller_pod_name,
     } = Args::parse();
 
     let server = if admission_controller_disabled {
@@ -99,23 +139,72 @@ async fn main() -> Result<()> {
 
     // Build the index data structure, which will be used to process events from all watches
     // The lookup handle is used by the gRPC server.
-    let index = Index::shared(ClusterInfo {
-        networks: cluster_networks.clone(),
+    let cluster_info = ClusterInfo {
+        networks: cluster_networks,
+        service_networks: service_networks
+            .map(|IpNets(nets)| nets)
+            .unwrap_or_default(),
         identity_domain,
-        control_plane_ns: control_plane_namespace,
+        dns_domain: cluster_domain.clone(),
+        control_plane_ns: control_plane_namespace.clone(),
         default_policy,
         default_detect_timeout: DETECT_TIMEOUT,
-    });
+    };
+    let index = Index::shared(cluster_info.clone());
+
+    // Build a separate index that computes resource statuses. It is fed by the same watches as the
+    // policy index. Statuses are only written to the API by the replica that holds the status
+    // lease.
+    let (status_tx, status_rx) = mpsc::unbounded_channel();
+    let status_index = k8s_index::status::Index::shared(status_tx);
+
+    // Build a separate index that serves outbound policies for Service ports. It shares route
+    // watches with the policy index.
+    let outbound_index = outbound::Index::shared(cluster_info);
+
+    // Metrics are served by the policy admin server.
+    let mut metrics = Registry::default();
+    index
+        .read()
+        .register_metrics(metrics.sub_registry_with_prefix("inbound_index"));
+    let grpc_metrics =
+        grpc::InboundMetrics::register(metrics.sub_registry_with_prefix("inbound_grpc"));
 
     // Spawn resource indexers that update the index and publish lookups for the gRPC server.
 
+    let namespaces = runtime.watch_all::<k8s::Namespace>(ListParams::default());
+    tokio::spawn(
+        kubert::index::cluster(index.clone(), namespaces).instrument(info_span!("namespaces")),
+    );
+
+    if discover_cluster_networks {
+        let nodes = runtime.watch_all::<k8s::Node>(ListParams::default());
+        tokio::spawn(kubert::index::cluster(index.clone(), nodes).instrument(info_span!("nodes")));
+    }
+
     let pods =
         runtime.watch_all::<k8s::Pod>(ListParams::default().labels("linkerd.io/control-plane-ns"));
-    tokio::spawn(kubert::index::namespaced(index.clone(), pods).instrument(info_span!("pods")));
+    let pods_indexes = IndexList::new(index.clone())
+        .push(status_index.clone())
+        .shared();
+    tokio::spawn(kubert::index::namespaced(pods_indexes, pods).instrument(info_span!("pods")));
+
+    let external_workloads =
+        runtime.watch_all::<k8s::workload::ExternalWorkload>(ListParams::default());
+    let external_workloads_indexes = IndexList::new(index.clone())
+        .push(status_index.clone())
+        .shared();
+    tokio::spawn(
+        kubert::index::namespaced(external_workloads_indexes, external_workloads)
+            .instrument(info_span!("externalworkloads")),
+    );
 
     let servers = runtime.watch_all::<k8s::policy::Server>(ListParams::default());
+    let servers_indexes = IndexList::new(index.clone())
+        .push(status_index.clone())
+        .shared();
     tokio::spawn(
-        kubert::index::namespaced(index.clone(), servers).instrument(info_span!("servers")),
+        kubert::index::namespaced(servers_indexes, servers).instrument(info_span!("servers")),
     );
 
     let server_authzs =
@@ -127,43 +216,112 @@ async fn main() -> Result<()> {
 
     let authz_policies =
         runtime.watch_all::<k8s::policy::AuthorizationPolicy>(ListParams::default());
+    let authz_policies_indexes = IndexList::new(index.clone())
+        .push(status_index.clone())
+        .shared();
     tokio::spawn(
-        kubert::index::namespaced(index.clone(), authz_policies)
+        kubert::index::namespaced(authz_policies_indexes, authz_policies)
             .instrument(info_span!("authorizationpolicies")),
     );
 
     let mtls_authns =
         runtime.watch_all::<k8s::policy::MeshTLSAuthentication>(ListParams::default());
+    let mtls_authns_indexes = IndexList::new(index.clone())
+        .push(status_index.clone())
+        .shared();
     tokio::spawn(
-        kubert::index::namespaced(index.clone(), mtls_authns)
+        kubert::index::namespaced(mtls_authns_indexes, mtls_authns)
             .instrument(info_span!("meshtlsauthentications")),
     );
 
     let network_authns =
         runtime.watch_all::<k8s::policy::NetworkAuthentication>(ListParams::default());
+    let network_authns_indexes = IndexList::new(index.clone())
+        .push(status_index.clone())
+        .shared();
     tokio::spawn(
-        kubert::index::namespaced(index.clone(), network_authns)
+        kubert::index::namespaced(network_authns_indexes, network_authns)
             .instrument(info_span!("networkauthentications")),
     );
 
     let gateway_http_routes =
         runtime.watch_all::<k8s_gateway_api::HttpRoute>(ListParams::default());
+    let gateway_http_routes_indexes = IndexList::new(index.clone())
+        .push(status_index.clone())
+        .push(outbound_index.clone())
+        .shared();
     tokio::spawn(
-        kubert::index::namespaced(index.clone(), gateway_http_routes)
-            .instrument(info_span!("httproutes", group = "networking.k8s.io")),
+        kubert::index::namespaced(gateway_http_routes_indexes, gateway_http_routes).instrument(
+            info_span!("httproutes", group = "gateway.networking.k8s.io"),
+        ),
     );
 
     let linkerd_http_routes = runtime.watch_all::<k8s::policy::HttpRoute>(ListParams::default());
+    let linkerd_http_routes_indexes = IndexList::new(index.clone())
+        .push(status_index.clone())
+        .push(outbound_index.clone())
+        .shared();
     tokio::spawn(
-        kubert::index::namespaced(index.clone(), linkerd_http_routes)
+        kubert::index::namespaced(linkerd_http_routes_indexes, linkerd_http_routes)
             .instrument(info_span!("httproutes", group = "policy.linkerd.io")),
     );
 
+    let grpc_routes = runtime.watch_all::<k8s::gateway::GrpcRoute>(ListParams::default());
+    let grpc_routes_indexes = IndexList::new(index.clone())
+        .push(status_index.clone())
+        .shared();
+    tokio::spawn(
+        kubert::index::namespaced(grpc_routes_indexes, grpc_routes).instrument(info_span!(
+            "grpcroutes",
+            group = "gateway.networking.k8s.io"
+        )),
+    );
+
+    let reference_grants = runtime.watch_all::<k8s::gateway::ReferenceGrant>(ListParams::default());
+    let reference_grants_indexes = IndexList::new(index.clone())
+        .push(status_index.clone())
+        .shared();
+    tokio::spawn(
+        kubert::index::namespaced(reference_grants_indexes, reference_grants)
+            .instrument(info_span!("referencegrants")),
+    );
+
+    let services = runtime.watch_all::<k8s::Service>(ListParams::default());
+    tokio::spawn(
+        kubert::index::namespaced(outbound_index.clone(), services)
+            .instrument(info_span!("services")),
+    );
+
+    let leader = lease::Claimer::new(
+        runtime.client(),
+        &control_plane_namespace,
+        STATUS_LEASE_NAME,
+        controller_pod_name,
+        STATUS_LEASE_DURATION,
+    )
+    .spawn(runtime.shutdown_handle());
+    tokio::spawn(
+        status::Controller::new(runtime.client(), status_rx, leader)
+            .run()
+            .instrument(info_span!("status")),
+    );
+
+    // Serve descriptions of the policies in the index for debugging.
+    tokio::spawn(admin(
+        policy_admin_addr,
+        index.clone(),
+        grpc_metrics.watchers(),
+        Arc::new(metrics),
+        runtime.shutdown_handle(),
+    ));
+
     // Run the gRPC server, serving results by looking up against the index handle.
     tokio::spawn(grpc(
         grpc_addr,
-        cluster_networks,
+        cluster_domain,
         index,
+        outbound_index,
+        grpc_metrics,
         runtime.shutdown_handle(),
     ));
 
@@ -195,15 +353,27 @@ impl std::str::FromStr for IpNets {
 #[instrument(skip_all, fields(port = %addr.port()))]
 async fn grpc(
     addr: SocketAddr,
-    cluster_networks: Vec<IpNet>,
+    cluster_domain: String,
     index: SharedIndex,
+    outbound_index: outbound::SharedIndex,
+    metrics: grpc::InboundMetrics,
     drain: drain::Watch,
 ) -> Result<()> {
-    let discover = IndexDiscover::new(index);
-    let server = grpc::Server::new(discover, cluster_networks, drain.clone());
+    let cluster_networks = index.read().cluster_networks_rx();
+    let inbound = grpc::Server::new(
+        IndexDiscover::new(index),
+        cluster_networks,
+        metrics,
+        drain.clone(),
+    );
+    let outbound = grpc::outbound::Server::new(
+        OutboundDiscover::new(outbound_index),
+        cluster_domain,
+        drain.clone(),
+    );
     let (close_tx, close_rx) = tokio::sync::oneshot::channel();
     tokio::pin! {
-        let srv = server.serve(addr, close_rx.map(|_| {}));
+        let srv = grpc::serve(addr, inbound, outbound, close_rx.map(|_| {}));
     }
     info!(%addr, "gRPC server listening");
     tokio::select! {
@@ -215,3 +385,32 @@ async fn grpc(
     }
     Ok(())
 }
+
+#[instrument(skip_all, fields(port = %addr.port()))]
+async fn admin(
+    addr: SocketAddr,
+    index: SharedIndex,
+    watchers: grpc::Watchers,
+    metrics: Arc<Registry>,
+    drain: drain::Watch,
+) -> Result<()> {
+    let (close_tx, close_rx) = tokio::sync::oneshot::channel();
+    tokio::pin! {
+        let srv = linkerd_policy_controller::admin::serve(
+            addr,
+            index,
+            watchers,
+            metrics,
+            close_rx.map(|_| {}),
+        );
+    }
+    info!(%addr, "policy admin server listening");
+    tokio::select! {
+        res = (&mut srv) => res?,
+        handle = drain.signaled() => {
+            let _ = close_tx.send(());
+            handle.release_after(srv).await?
+        }
+    }
+    Ok(())
+}
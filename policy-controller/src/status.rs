@@ -0,0 +1,181 @@
+//! Writes resource statuses computed by the status index to the Kubernetes API.
+
+use crate::k8s;
+use kube::api::{Patch, PatchParams};
+use linkerd_policy_controller_k8s_index::status::{Id, Kind, Update};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use tokio::{
+    sync::{mpsc, watch},
+    time,
+};
+use tracing::{debug, info, warn};
+
+/// The delay before a failed patch is first retried. The delay doubles with
+/// each subsequent failure, up to `MAX_BACKOFF`.
+const MIN_BACKOFF: time::Duration = time::Duration::from_millis(100);
+const MAX_BACKOFF: time::Duration = time::Duration::from_secs(60);
+
+/// Applies status updates when this replica holds the status lease.
+pub struct Controller {
+    client: kube::Client,
+    updates: mpsc::UnboundedReceiver<Update>,
+    leader: watch::Receiver<bool>,
+
+    /// Patches that have not yet been applied, by resource. A patch is
+    /// removed once it has been applied or when its resource is deleted.
+    pending: HashMap<Id, Pending>,
+}
+
+#[derive(Debug)]
+struct Pending {
+    patch: serde_json::Value,
+
+    /// The number of times the patch has failed to apply.
+    failures: u32,
+
+    /// The time at which the patch should be retried. Unset until the patch
+    /// has failed to apply.
+    retry_at: Option<time::Instant>,
+}
+
+// === impl Controller ===
+
+impl Controller {
+    pub fn new(
+        client: kube::Client,
+        updates: mpsc::UnboundedReceiver<Update>,
+        leader: watch::Receiver<bool>,
+    ) -> Self {
+        Self {
+            client,
+            updates,
+            leader,
+            pending: HashMap::default(),
+        }
+    }
+
+    pub async fn run(mut self) {
+        loop {
+            // Failed patches are only retried while this replica is the leader.
+            let retry_at = if *self.leader.borrow() {
+                self.pending.values().filter_map(|p| p.retry_at).min()
+            } else {
+                None
+            };
+
+            tokio::select! {
+                res = self.leader.changed() => {
+                    if res.is_err() {
+                        return;
+                    }
+                    if *self.leader.borrow_and_update() {
+                        info!(patches = self.pending.len(), "Became leader; writing statuses");
+                        let ids = self.pending.keys().cloned().collect::<Vec<_>>();
+                        for id in ids {
+                            self.apply(id).await;
+                        }
+                    }
+                }
+
+                update = self.updates.recv() => match update {
+                    None => return,
+                    Some(Update { id, patch: None }) => {
+                        self.pending.remove(&id);
+                    }
+                    Some(Update { id, patch: Some(patch) }) => {
+                        self.pending.insert(id.clone(), Pending {
+                            patch,
+                            failures: 0,
+                            retry_at: None,
+                        });
+                        if *self.leader.borrow() {
+                            self.apply(id).await;
+                        }
+                    }
+                },
+
+                () = time::sleep_until(retry_at.unwrap_or_else(time::Instant::now)), if retry_at.is_some() => {
+                    let now = time::Instant::now();
+                    let ids = self
+                        .pending
+                        .iter()
+                        .filter(|(_, p)| p.retry_at.map_or(false, |at| at <= now))
+                        .map(|(id, _)| id.clone())
+                        .collect::<Vec<_>>();
+                    for id in ids {
+                        self.apply(id).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Applies the pending patch for the given resource, scheduling a retry
+    /// if it fails.
+    async fn apply(&mut self, id: Id) {
+        let patch = match self.pending.get(&id) {
+            Some(pending) => &pending.patch,
+            None => return,
+        };
+
+        match self.patch(&id, patch).await {
+            Ok(()) => {
+                debug!(ns = %id.namespace, name = %id.name, kind = ?id.kind, "Patched status");
+                self.pending.remove(&id);
+            }
+            // The resource has been deleted, so there is nothing to retry.
+            Err(kube::Error::Api(error)) if error.code == 404 => {
+                debug!(ns = %id.namespace, name = %id.name, kind = ?id.kind, "Resource not found");
+                self.pending.remove(&id);
+            }
+            Err(error) => {
+                let pending = self.pending.get_mut(&id).expect("pending patch must exist");
+                let backoff = MIN_BACKOFF
+                    .saturating_mul(2u32.saturating_pow(pending.failures))
+                    .min(MAX_BACKOFF);
+                pending.failures += 1;
+                pending.retry_at = Some(time::Instant::now() + backoff);
+                warn!(
+                    ns = %id.namespace,
+                    name = %id.name,
+                    kind = ?id.kind,
+                    failures = pending.failures,
+                    ?backoff,
+                    %error,
+                    "Failed to patch status",
+                );
+            }
+        }
+    }
+
+    async fn patch(&self, id: &Id, patch: &serde_json::Value) -> Result<(), kube::Error> {
+        match id.kind {
+            Kind::GatewayHttpRoute => {
+                self.patch_status::<k8s_gateway_api::HttpRoute>(id, patch)
+                    .await
+            }
+            Kind::LinkerdHttpRoute => self.patch_status::<k8s::policy::HttpRoute>(id, patch).await,
+            Kind::GrpcRoute => {
+                self.patch_status::<k8s::gateway::GrpcRoute>(id, patch)
+                    .await
+            }
+            Kind::AuthorizationPolicy => {
+                self.patch_status::<k8s::policy::AuthorizationPolicy>(id, patch)
+                    .await
+            }
+            Kind::Server => self.patch_status::<k8s::policy::Server>(id, patch).await,
+        }
+    }
+
+    async fn patch_status<T>(&self, id: &Id, patch: &serde_json::Value) -> Result<(), kube::Error>
+    where
+        T: kube::Resource<DynamicType = ()> + Clone + DeserializeOwned + std::fmt::Debug,
+    {
+        let api = kube::Api::<T>::namespaced(self.client.clone(), &id.namespace);
+        let params = PatchParams::default();
+        api.patch_status(&id.name, &params, &Patch::Merge(patch))
+            .await?;
+        Ok(())
+    }
+}
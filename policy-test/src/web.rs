@@ -45,6 +45,7 @@ pub fn server(ns: &str) -> k8s::policy::Server {
             port: k8s::policy::server::Port::Name("http".to_string()),
             proxy_protocol: Some(k8s::policy::server::ProxyProtocol::Http1),
         },
+        status: None,
     }
 }
 
@@ -1,6 +1,9 @@
 use linkerd_policy_controller_k8s_api::{
     self as api,
-    policy::{AuthorizationPolicy, AuthorizationPolicySpec, LocalTargetRef, NamespacedTargetRef},
+    policy::{
+        AuthorizationAction, AuthorizationPolicy, AuthorizationPolicySpec, LocalTargetRef,
+        NamespacedTargetRef,
+    },
 };
 use linkerd_policy_test::admission;
 
@@ -13,11 +16,12 @@ async fn accepts_valid() {
             ..Default::default()
         },
         spec: AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: Some("policy.linkerd.io".to_string()),
                 kind: "Server".to_string(),
                 name: "api".to_string(),
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: vec![
                 NamespacedTargetRef {
                     group: Some("policy.linkerd.io".to_string()),
@@ -32,7 +36,176 @@ async fn accepts_valid() {
                     namespace: Some("linkerd".to_string()),
                 },
             ],
+            action: None,
+            mode: None,
+        },
+        status: None,
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn accepts_target_selector() {
+    admission::accepts(|ns| AuthorizationPolicy {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: AuthorizationPolicySpec {
+            target_ref: None,
+            target_selector: Some(api::labels::Selector::from_iter(Some(("tier", "admin")))),
+            required_authentication_refs: vec![NamespacedTargetRef {
+                group: Some("policy.linkerd.io".to_string()),
+                kind: "MeshTLSAuthentication".to_string(),
+                name: "mtls-clients".to_string(),
+                namespace: None,
+            }],
+            action: None,
+            mode: None,
+        },
+        status: None,
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_target_ref_and_selector() {
+    admission::rejects(|ns| AuthorizationPolicy {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: AuthorizationPolicySpec {
+            target_ref: Some(LocalTargetRef {
+                group: Some("policy.linkerd.io".to_string()),
+                kind: "Server".to_string(),
+                name: "api".to_string(),
+            }),
+            target_selector: Some(api::labels::Selector::from_iter(Some(("tier", "admin")))),
+            required_authentication_refs: vec![NamespacedTargetRef {
+                group: Some("policy.linkerd.io".to_string()),
+                kind: "MeshTLSAuthentication".to_string(),
+                name: "mtls-clients".to_string(),
+                namespace: None,
+            }],
+            action: None,
+            mode: None,
+        },
+        status: None,
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_missing_target() {
+    admission::rejects(|ns| AuthorizationPolicy {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: AuthorizationPolicySpec {
+            target_ref: None,
+            target_selector: None,
+            required_authentication_refs: vec![NamespacedTargetRef {
+                group: Some("policy.linkerd.io".to_string()),
+                kind: "MeshTLSAuthentication".to_string(),
+                name: "mtls-clients".to_string(),
+                namespace: None,
+            }],
+            action: None,
+            mode: None,
+        },
+        status: None,
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn accepts_deny() {
+    admission::accepts(|ns| AuthorizationPolicy {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: AuthorizationPolicySpec {
+            target_ref: Some(LocalTargetRef {
+                group: Some("policy.linkerd.io".to_string()),
+                kind: "Server".to_string(),
+                name: "api".to_string(),
+            }),
+            target_selector: None,
+            required_authentication_refs: vec![NamespacedTargetRef {
+                group: Some("policy.linkerd.io".to_string()),
+                kind: "NetworkAuthentication".to_string(),
+                name: "cluster-nets".to_string(),
+                namespace: Some("linkerd".to_string()),
+            }],
+            action: Some(AuthorizationAction::Deny),
+            mode: None,
+        },
+        status: None,
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn accepts_deny_with_identity() {
+    admission::accepts(|ns| AuthorizationPolicy {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: AuthorizationPolicySpec {
+            target_ref: Some(LocalTargetRef {
+                group: Some("policy.linkerd.io".to_string()),
+                kind: "Server".to_string(),
+                name: "api".to_string(),
+            }),
+            target_selector: None,
+            required_authentication_refs: vec![NamespacedTargetRef {
+                group: Some("policy.linkerd.io".to_string()),
+                kind: "MeshTLSAuthentication".to_string(),
+                name: "mtls-clients".to_string(),
+                namespace: None,
+            }],
+            action: Some(AuthorizationAction::Deny),
+            mode: None,
+        },
+        status: None,
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn accepts_deny_with_service_account() {
+    admission::accepts(|ns| AuthorizationPolicy {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: AuthorizationPolicySpec {
+            target_ref: Some(LocalTargetRef {
+                group: Some("policy.linkerd.io".to_string()),
+                kind: "Server".to_string(),
+                name: "api".to_string(),
+            }),
+            target_selector: None,
+            required_authentication_refs: vec![NamespacedTargetRef {
+                group: None,
+                kind: "ServiceAccount".to_string(),
+                name: "default".to_string(),
+                namespace: None,
+            }],
+            action: Some(AuthorizationAction::Deny),
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
@@ -46,11 +219,12 @@ async fn accepts_targets_namespace() {
             ..Default::default()
         },
         spec: AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: None,
                 kind: "Namespace".to_string(),
                 name: ns,
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: vec![
                 NamespacedTargetRef {
                     group: Some("policy.linkerd.io".to_string()),
@@ -65,7 +239,10 @@ async fn accepts_targets_namespace() {
                     namespace: Some("linkerd".to_string()),
                 },
             ],
+            action: None,
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
@@ -79,11 +256,12 @@ async fn rejects_targets_other_namespace() {
             ..Default::default()
         },
         spec: AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: None,
                 kind: "Namespace".to_string(),
                 name: "foobar".to_string(),
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: vec![
                 NamespacedTargetRef {
                     group: Some("policy.linkerd.io".to_string()),
@@ -98,7 +276,10 @@ async fn rejects_targets_other_namespace() {
                     namespace: Some("linkerd".to_string()),
                 },
             ],
+            action: None,
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
@@ -112,11 +293,12 @@ async fn accepts_targets_route() {
             ..Default::default()
         },
         spec: AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: Some("gateway.networking.k8s.io".to_string()),
                 kind: "HttpRoute".to_string(),
                 name: "route-foo".to_string(),
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: vec![
                 NamespacedTargetRef {
                     group: Some("policy.linkerd.io".to_string()),
@@ -131,7 +313,10 @@ async fn accepts_targets_route() {
                     namespace: Some("linkerd".to_string()),
                 },
             ],
+            action: None,
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
@@ -145,18 +330,22 @@ async fn accepts_valid_with_only_meshtls() {
             ..Default::default()
         },
         spec: AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: Some("policy.linkerd.io".to_string()),
                 kind: "Server".to_string(),
                 name: "api".to_string(),
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: vec![NamespacedTargetRef {
                 group: Some("policy.linkerd.io".to_string()),
                 kind: "MeshTLSAuthentication".to_string(),
                 name: "mtls-clients".to_string(),
                 namespace: None,
             }],
+            action: None,
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
@@ -170,18 +359,22 @@ async fn accepts_valid_with_only_network() {
             ..Default::default()
         },
         spec: AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: Some("policy.linkerd.io".to_string()),
                 kind: "Server".to_string(),
                 name: "api".to_string(),
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: vec![NamespacedTargetRef {
                 group: Some("policy.linkerd.io".to_string()),
                 kind: "NetworkAuthentication".to_string(),
                 name: "cluster-nets".to_string(),
                 namespace: Some("linkerd".to_string()),
             }],
+            action: None,
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
@@ -195,13 +388,17 @@ async fn accepts_empty_required_authentications() {
             ..Default::default()
         },
         spec: AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: Some("policy.linkerd.io".to_string()),
                 kind: "Server".to_string(),
                 name: "deny".to_string(),
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: vec![],
+            action: None,
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
@@ -241,7 +438,10 @@ async fn rejects_missing_required_authentications() {
                 name: "deny".to_string(),
             },
             required_authentication_refs: None,
+            action: None,
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
@@ -255,18 +455,22 @@ async fn rejects_target_ref_deployment() {
             ..Default::default()
         },
         spec: AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: Some("apps".to_string()),
                 kind: "Deployment".to_string(),
                 name: "someapp".to_string(),
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: vec![NamespacedTargetRef {
                 group: Some("policy.linkerd.io".to_string()),
                 kind: "NetworkAuthentication".to_string(),
                 namespace: Some("linkerd".to_string()),
                 name: "cluster-nets".to_string(),
             }],
+            action: None,
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
@@ -280,11 +484,12 @@ async fn rejects_duplicate_mtls_authns() {
             ..Default::default()
         },
         spec: AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: Some("policy.linkerd.io".to_string()),
                 kind: "Server".to_string(),
                 name: "some-srv".to_string(),
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: vec![
                 NamespacedTargetRef {
                     group: Some("policy.linkerd.io".to_string()),
@@ -299,7 +504,10 @@ async fn rejects_duplicate_mtls_authns() {
                     name: "other-ids".to_string(),
                 },
             ],
+            action: None,
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
@@ -313,11 +521,12 @@ async fn rejects_duplicate_network_authns() {
             ..Default::default()
         },
         spec: AuthorizationPolicySpec {
-            target_ref: LocalTargetRef {
+            target_ref: Some(LocalTargetRef {
                 group: Some("policy.linkerd.io".to_string()),
                 kind: "Server".to_string(),
                 name: "some-srv".to_string(),
-            },
+            }),
+            target_selector: None,
             required_authentication_refs: vec![
                 NamespacedTargetRef {
                     group: Some("policy.linkerd.io".to_string()),
@@ -332,7 +541,10 @@ async fn rejects_duplicate_network_authns() {
                     name: "other-nets".to_string(),
                 },
             ],
+            action: None,
+            mode: None,
         },
+        status: None,
     })
     .await;
 }
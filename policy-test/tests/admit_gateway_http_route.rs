@@ -1,7 +1,7 @@
 use k8s_gateway_api::{
     BackendObjectReference, BackendRef, CommonRouteSpec, HttpBackendRef, HttpPathMatch,
-    HttpRequestMirrorFilter, HttpRoute, HttpRouteFilter, HttpRouteMatch, HttpRouteRule,
-    HttpRouteSpec, ParentReference,
+    HttpPathModifier, HttpRequestMirrorFilter, HttpRoute, HttpRouteFilter, HttpRouteMatch,
+    HttpRouteRule, HttpRouteSpec, HttpUrlRewriteFilter, LocalObjectReference, ParentReference,
 };
 use linkerd_policy_controller_k8s_api::{self as api};
 use linkerd_policy_test::admission;
@@ -53,7 +53,7 @@ async fn accepts_valid() {
 async fn skips_validation_for_external_parent_ref() {
     // We test that HttpRoutes which do not have a Server as a parent_ref are
     // not validated by creating an HttpRoute with an unsupported filter
-    // (RequestMirror) and ensuring that it is accepted anyway.
+    // (ExtensionRef) and ensuring that it is accepted anyway.
     admission::accepts(|ns| HttpRoute {
         metadata: api::ObjectMeta {
             namespace: Some(ns.clone()),
@@ -79,15 +79,11 @@ async fn skips_validation_for_external_parent_ref() {
                     }),
                     ..HttpRouteMatch::default()
                 }]),
-                filters: Some(vec![HttpRouteFilter::RequestMirror {
-                    request_mirror: HttpRequestMirrorFilter {
-                        backend_ref: BackendObjectReference {
-                            group: None,
-                            kind: Some("Service".to_string()),
-                            name: "my-backend".to_string(),
-                            namespace: None,
-                            port: Some(8888),
-                        },
+                filters: Some(vec![HttpRouteFilter::ExtensionRef {
+                    extension_ref: LocalObjectReference {
+                        group: "example.com".to_string(),
+                        kind: "Foo".to_string(),
+                        name: "my-extension".to_string(),
                     },
                 }]),
                 backend_refs: None,
@@ -125,15 +121,11 @@ async fn rejects_unsupported_filter() {
                     }),
                     ..HttpRouteMatch::default()
                 }]),
-                filters: Some(vec![HttpRouteFilter::RequestMirror {
-                    request_mirror: HttpRequestMirrorFilter {
-                        backend_ref: BackendObjectReference {
-                            group: None,
-                            kind: Some("Service".to_string()),
-                            name: "my-backend".to_string(),
-                            namespace: None,
-                            port: Some(8888),
-                        },
+                filters: Some(vec![HttpRouteFilter::ExtensionRef {
+                    extension_ref: LocalObjectReference {
+                        group: "example.com".to_string(),
+                        kind: "Foo".to_string(),
+                        name: "my-extension".to_string(),
                     },
                 }]),
                 backend_refs: None,
@@ -178,15 +170,11 @@ async fn rejects_unsupported_backend_filter() {
                         name: "my-backend".to_string(),
                         port: 8888,
                     }),
-                    filters: Some(vec![HttpRouteFilter::RequestMirror {
-                        request_mirror: HttpRequestMirrorFilter {
-                            backend_ref: BackendObjectReference {
-                                group: None,
-                                kind: Some("Service".to_string()),
-                                name: "my-backend".to_string(),
-                                namespace: None,
-                                port: Some(8888),
-                            },
+                    filters: Some(vec![HttpRouteFilter::ExtensionRef {
+                        extension_ref: LocalObjectReference {
+                            group: "example.com".to_string(),
+                            kind: "Foo".to_string(),
+                            name: "my-extension".to_string(),
                         },
                     }]),
                 }]),
@@ -196,3 +184,104 @@ async fn rejects_unsupported_backend_filter() {
     })
     .await;
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_request_mirror() {
+    // Inbound proxies cannot mirror requests.
+    admission::rejects(|ns| {
+        mirror_route(
+            ns,
+            BackendObjectReference {
+                group: None,
+                kind: Some("Service".to_string()),
+                name: "my-backend".to_string(),
+                namespace: None,
+                port: Some(8888),
+            },
+        )
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_url_rewrite() {
+    // Inbound proxies cannot rewrite requests.
+    admission::rejects(|ns| {
+        url_rewrite_route(
+            ns,
+            HttpPathMatch::PathPrefix {
+                value: "/foo".to_string(),
+            },
+            HttpPathModifier::ReplacePrefixMatch("/bar".to_string()),
+        )
+    })
+    .await;
+}
+
+fn mirror_route(ns: String, backend_ref: BackendObjectReference) -> HttpRoute {
+    HttpRoute {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns.clone()),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: HttpRouteSpec {
+            inner: CommonRouteSpec {
+                parent_refs: Some(vec![ParentReference {
+                    group: Some("policy.linkerd.io".to_string()),
+                    kind: Some("Server".to_string()),
+                    namespace: Some(ns),
+                    name: "my-server".to_string(),
+                    section_name: None,
+                    port: None,
+                }]),
+            },
+            hostnames: None,
+            rules: Some(vec![HttpRouteRule {
+                matches: None,
+                filters: Some(vec![HttpRouteFilter::RequestMirror {
+                    request_mirror: HttpRequestMirrorFilter { backend_ref },
+                }]),
+                backend_refs: None,
+            }]),
+        },
+        status: None,
+    }
+}
+
+fn url_rewrite_route(ns: String, path: HttpPathMatch, modifier: HttpPathModifier) -> HttpRoute {
+    HttpRoute {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns.clone()),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: HttpRouteSpec {
+            inner: CommonRouteSpec {
+                parent_refs: Some(vec![ParentReference {
+                    group: Some("policy.linkerd.io".to_string()),
+                    kind: Some("Server".to_string()),
+                    namespace: Some(ns),
+                    name: "my-server".to_string(),
+                    section_name: None,
+                    port: None,
+                }]),
+            },
+            hostnames: None,
+            rules: Some(vec![HttpRouteRule {
+                matches: Some(vec![HttpRouteMatch {
+                    path: Some(path),
+                    ..HttpRouteMatch::default()
+                }]),
+                filters: Some(vec![HttpRouteFilter::URLRewrite {
+                    url_rewrite: HttpUrlRewriteFilter {
+                        hostname: None,
+                        path: Some(modifier),
+                    },
+                }]),
+                backend_refs: None,
+            }]),
+        },
+        status: None,
+    }
+}
@@ -0,0 +1,158 @@
+use k8s_gateway_api::{BackendObjectReference, CommonRouteSpec, ParentReference};
+use linkerd_policy_controller_k8s_api::{
+    self as api,
+    gateway::grpcroute::{
+        GrpcMethodMatch, GrpcMethodMatchType, GrpcRoute, GrpcRouteFilter, GrpcRouteMatch,
+        GrpcRouteRule, GrpcRouteSpec, HttpRequestMirrorFilter,
+    },
+};
+use linkerd_policy_test::admission;
+
+#[tokio::test(flavor = "current_thread")]
+async fn accepts_valid() {
+    admission::accepts(|ns| {
+        mk_route(
+            ns,
+            server_parent_ref(),
+            Some(GrpcMethodMatch {
+                r#type: Some(GrpcMethodMatchType::Exact),
+                service: Some("foo.Bar".to_string()),
+                method: Some("Baz".to_string()),
+            }),
+            None,
+        )
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn skips_validation_for_external_parent_ref() {
+    // GrpcRoutes which do not have a Server as a parent_ref are not validated,
+    // so an unsupported filter (RequestMirror) is accepted.
+    admission::accepts(|ns| {
+        mk_route(
+            ns,
+            ParentReference {
+                group: Some("foo.bar.bas".to_string()),
+                kind: Some("Gateway".to_string()),
+                namespace: None,
+                name: "my-gateway".to_string(),
+                section_name: None,
+                port: None,
+            },
+            None,
+            Some(mirror_filter()),
+        )
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_unsupported_filter() {
+    admission::rejects(|ns| mk_route(ns, server_parent_ref(), None, Some(mirror_filter()))).await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_cross_namespace_server_parent_ref() {
+    admission::rejects(|ns| {
+        mk_route(
+            ns,
+            ParentReference {
+                namespace: Some("other-ns".to_string()),
+                ..server_parent_ref()
+            },
+            None,
+            None,
+        )
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_empty_method_match() {
+    admission::rejects(|ns| {
+        mk_route(
+            ns,
+            server_parent_ref(),
+            Some(GrpcMethodMatch {
+                r#type: Some(GrpcMethodMatchType::Exact),
+                service: None,
+                method: Some("".to_string()),
+            }),
+            None,
+        )
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_regex_method_match() {
+    admission::rejects(|ns| {
+        mk_route(
+            ns,
+            server_parent_ref(),
+            Some(GrpcMethodMatch {
+                r#type: Some(GrpcMethodMatchType::RegularExpression),
+                service: Some("foo\\..*".to_string()),
+                method: None,
+            }),
+            None,
+        )
+    })
+    .await;
+}
+
+fn mk_route(
+    ns: String,
+    parent: ParentReference,
+    method: Option<GrpcMethodMatch>,
+    filter: Option<GrpcRouteFilter>,
+) -> GrpcRoute {
+    GrpcRoute {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: GrpcRouteSpec {
+            inner: CommonRouteSpec {
+                parent_refs: Some(vec![parent]),
+            },
+            hostnames: None,
+            rules: Some(vec![GrpcRouteRule {
+                matches: Some(vec![GrpcRouteMatch {
+                    method,
+                    headers: None,
+                }]),
+                filters: filter.map(|f| vec![f]),
+                backend_refs: None,
+            }]),
+        },
+        status: None,
+    }
+}
+
+fn server_parent_ref() -> ParentReference {
+    ParentReference {
+        group: Some("policy.linkerd.io".to_string()),
+        kind: Some("Server".to_string()),
+        namespace: None,
+        name: "my-server".to_string(),
+        section_name: None,
+        port: None,
+    }
+}
+
+fn mirror_filter() -> GrpcRouteFilter {
+    GrpcRouteFilter::RequestMirror {
+        request_mirror: HttpRequestMirrorFilter {
+            backend_ref: BackendObjectReference {
+                group: None,
+                kind: Some("Service".to_string()),
+                name: "my-backend".to_string(),
+                namespace: None,
+                port: Some(8888),
+            },
+        },
+    }
+}
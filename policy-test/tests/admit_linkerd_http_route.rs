@@ -66,6 +66,46 @@ async fn rejects_mixed_parent_ref() {
     .await;
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn accepts_service_parent_ref_with_timeouts() {
+    admission::accepts(|ns| HttpRoute {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns.clone()),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: HttpRouteSpec {
+            inner: CommonRouteSpec {
+                parent_refs: Some(vec![service_parent_ref(ns)]),
+            },
+            hostnames: None,
+            rules: Some(rules_with_timeouts("10s", "1s")),
+        },
+        status: None,
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_backend_request_timeout_exceeding_request_timeout() {
+    admission::rejects(|ns| HttpRoute {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns.clone()),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: HttpRouteSpec {
+            inner: CommonRouteSpec {
+                parent_refs: Some(vec![service_parent_ref(ns)]),
+            },
+            hostnames: None,
+            rules: Some(rules_with_timeouts("1s", "1m")),
+        },
+        status: None,
+    })
+    .await;
+}
+
 fn server_parent_ref(ns: String) -> ParentReference {
     ParentReference {
         group: Some("policy.linkerd.io".to_string()),
@@ -97,5 +137,27 @@ fn rules() -> Vec<HttpRouteRule> {
             ..HttpRouteMatch::default()
         }]),
         filters: None,
+        backend_refs: None,
+        timeouts: None,
     }]
 }
+
+fn service_parent_ref(ns: String) -> ParentReference {
+    ParentReference {
+        group: Some("core".to_string()),
+        kind: Some("Service".to_string()),
+        namespace: Some(ns),
+        name: "my-svc".to_string(),
+        section_name: None,
+        port: Some(8080),
+    }
+}
+
+fn rules_with_timeouts(request: &str, backend_request: &str) -> Vec<HttpRouteRule> {
+    let mut rules = rules();
+    rules[0].timeouts = Some(HttpRouteTimeouts {
+        request: Some(request.to_string()),
+        backend_request: Some(backend_request.to_string()),
+    });
+    rules
+}
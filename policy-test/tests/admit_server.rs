@@ -17,6 +17,7 @@ async fn accepts_valid() {
             port: Port::Number(80.try_into().unwrap()),
             proxy_protocol: None,
         },
+        status: None,
     })
     .await;
 }
@@ -35,6 +36,7 @@ async fn accepts_server_updates() {
                 port: Port::Number(80.try_into().unwrap()),
                 proxy_protocol: None,
             },
+            status: None,
         };
 
         let api = kube::Api::namespaced(client, &*ns);
@@ -71,6 +73,7 @@ async fn rejects_identitical_pod_selector() {
                 ..Default::default()
             },
             spec: spec.clone(),
+            status: None,
         };
         api.create(&kube::api::PostParams::default(), &test0)
             .await
@@ -83,6 +86,7 @@ async fn rejects_identitical_pod_selector() {
                 ..Default::default()
             },
             spec,
+            status: None,
         };
         api.create(&kube::api::PostParams::default(), &test1)
             .await
@@ -107,6 +111,7 @@ async fn rejects_all_pods_selected() {
                 port: Port::Number(80.try_into().unwrap()),
                 proxy_protocol: Some(ProxyProtocol::Http2),
             },
+            status: None,
         };
         api.create(&kube::api::PostParams::default(), &test0)
             .await
@@ -124,6 +129,7 @@ async fn rejects_all_pods_selected() {
                 // proxy protocol doesn't factor into the selection
                 proxy_protocol: Some(ProxyProtocol::Http1),
             },
+            status: None,
         };
         api.create(&kube::api::PostParams::default(), &test1)
             .await
@@ -178,3 +184,81 @@ async fn rejects_invalid_proxy_protocol() {
     })
     .await;
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn accepts_port_range() {
+    admission::accepts(|ns| Server {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: ServerSpec {
+            pod_selector: api::labels::Selector::default(),
+            port: Port::Range("9000-9019".parse().unwrap()),
+            proxy_protocol: Some(ProxyProtocol::Opaque),
+        },
+        status: None,
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_decreasing_port_range() {
+    admission::rejects(|ns| Server {
+        metadata: api::ObjectMeta {
+            namespace: Some(ns),
+            name: Some("test".to_string()),
+            ..Default::default()
+        },
+        spec: ServerSpec {
+            pod_selector: api::labels::Selector::default(),
+            port: Port::Name("9019-9000".to_string()),
+            proxy_protocol: None,
+        },
+        status: None,
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn rejects_port_within_existing_range() {
+    with_temp_ns(|client, ns| async move {
+        let api = kube::Api::namespaced(client, &*ns);
+
+        let test0 = Server {
+            metadata: api::ObjectMeta {
+                namespace: Some(ns.clone()),
+                name: Some("test0".to_string()),
+                ..Default::default()
+            },
+            spec: ServerSpec {
+                pod_selector: api::labels::Selector::from_iter(Some(("app", "test"))),
+                port: Port::Range("9000-9019".parse().unwrap()),
+                proxy_protocol: None,
+            },
+            status: None,
+        };
+        api.create(&kube::api::PostParams::default(), &test0)
+            .await
+            .expect("resource must apply");
+
+        let test1 = Server {
+            metadata: api::ObjectMeta {
+                namespace: Some(ns),
+                name: Some("test1".to_string()),
+                ..Default::default()
+            },
+            spec: ServerSpec {
+                pod_selector: api::labels::Selector::from_iter(Some(("app", "test"))),
+                port: Port::Number(9010.try_into().unwrap()),
+                proxy_protocol: None,
+            },
+            status: None,
+        };
+        api.create(&kube::api::PostParams::default(), &test1)
+            .await
+            .expect_err("resource must not apply");
+    })
+    .await;
+}
@@ -226,16 +226,20 @@ async fn server_with_authorization_policy() {
                     ..Default::default()
                 },
                 spec: k8s::policy::AuthorizationPolicySpec {
-                    target_ref: k8s::policy::LocalTargetRef::from_resource(&server),
+                    target_ref: Some(k8s::policy::LocalTargetRef::from_resource(&server)),
+                    target_selector: None,
                     required_authentication_refs: vec![
                         k8s::policy::NamespacedTargetRef::from_resource(&all_nets),
                     ],
+                    action: None,
+                    mode: None,
                 },
+                status: None,
             },
         )
         .await;
 
-        let config = time::timeout(time::Duration::from_secs(10), rx.next())
+        let config = time::timeout(Duration::from_secs(10), rx.next())
             .await
             .expect("watch must update within 10s")
             .expect("watch must not fail")
@@ -284,6 +288,108 @@ async fn server_with_authorization_policy() {
     .await;
 }
 
+/// Policies in audit mode are sent with metadata that distinguishes audited
+/// clients from authorized clients.
+#[tokio::test(flavor = "current_thread")]
+async fn server_with_audited_authorization_policy() {
+    with_temp_ns(|client, ns| async move {
+        let pod = create_ready_pod(&client, mk_pause(&ns, "pause")).await;
+
+        let mut rx = retry_watch_server(&client, &ns, &pod.name_unchecked()).await;
+        let config = rx
+            .next()
+            .await
+            .expect("watch must not fail")
+            .expect("watch must return an initial config");
+        tracing::trace!(?config);
+        assert_is_default_all_unauthenticated!(config);
+
+        let server = create(&client, mk_admin_server(&ns, "linkerd-admin")).await;
+        let config = rx
+            .next()
+            .await
+            .expect("watch must not fail")
+            .expect("watch must return an updated config");
+        tracing::trace!(?config);
+        assert_eq!(config.authorizations, vec![]);
+
+        let all_nets = create(
+            &client,
+            k8s::policy::NetworkAuthentication {
+                metadata: kube::api::ObjectMeta {
+                    namespace: Some(ns.clone()),
+                    name: Some("all-admin".to_string()),
+                    ..Default::default()
+                },
+                spec: k8s::policy::NetworkAuthenticationSpec {
+                    networks: vec![
+                        k8s::policy::network_authentication::Network {
+                            cidr: Ipv4Net::default().into(),
+                            except: None,
+                        },
+                        k8s::policy::network_authentication::Network {
+                            cidr: Ipv6Net::default().into(),
+                            except: None,
+                        },
+                    ],
+                },
+            },
+        )
+        .await;
+        let authz_policy = create(
+            &client,
+            k8s::policy::AuthorizationPolicy {
+                metadata: kube::api::ObjectMeta {
+                    namespace: Some(ns.clone()),
+                    name: Some("audit-admin".to_string()),
+                    ..Default::default()
+                },
+                spec: k8s::policy::AuthorizationPolicySpec {
+                    target_ref: Some(k8s::policy::LocalTargetRef::from_resource(&server)),
+                    target_selector: None,
+                    required_authentication_refs: vec![
+                        k8s::policy::NamespacedTargetRef::from_resource(&all_nets),
+                    ],
+                    action: None,
+                    mode: Some(k8s::policy::AuthorizationMode::Audit),
+                },
+                status: None,
+            },
+        )
+        .await;
+
+        let config = time::timeout(Duration::from_secs(10), rx.next())
+            .await
+            .expect("watch must update within 10s")
+            .expect("watch must not fail")
+            .expect("watch must return an updated config");
+        tracing::trace!(?config);
+        assert_eq!(config.authorizations.len(), 1);
+        let authz = config.authorizations.first().unwrap();
+        assert_eq!(
+            authz.metadata,
+            Some(grpc::meta::Metadata {
+                kind: Some(grpc::meta::metadata::Kind::Resource(grpc::meta::Resource {
+                    group: "policy.linkerd.io".to_string(),
+                    kind: "audit-authorizationpolicy".to_string(),
+                    name: authz_policy.name_unchecked(),
+                    ..Default::default()
+                }))
+            })
+        );
+        assert_eq!(
+            authz.labels,
+            convert_args!(hashmap!(
+                "group" => "policy.linkerd.io",
+                "kind" => "authorizationpolicy",
+                "name" => authz_policy.name_unchecked(),
+                "mode" => "audit",
+            ))
+        );
+    })
+    .await;
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn server_with_gateway_http_route() {
     with_temp_ns(|client, ns| async move {
@@ -362,6 +468,8 @@ async fn server_with_linkerd_http_route() {
                         method: Some("GET".to_string()),
                     }]),
                     filters: None,
+                    backend_refs: None,
+                    timeouts: None,
                 }]),
             },
             status: None,
@@ -458,6 +566,7 @@ where
                 group: "gateway.networking.k8s.io".to_string(),
                 kind: "HTTPRoute".to_string(),
                 name: "metrics-route".to_string(),
+                ..Default::default()
             }))
         }
     );
@@ -506,11 +615,15 @@ where
                 ..Default::default()
             },
             spec: k8s::policy::AuthorizationPolicySpec {
-                target_ref: k8s::policy::LocalTargetRef::from_resource(&route),
+                target_ref: Some(k8s::policy::LocalTargetRef::from_resource(&route)),
+                target_selector: None,
                 required_authentication_refs: vec![
                     k8s::policy::NamespacedTargetRef::from_resource(&all_nets),
                 ],
+                action: None,
+                mode: None,
             },
+            status: None,
         },
     )
     .await;
@@ -590,6 +703,7 @@ fn mk_admin_server(ns: &str, name: &str) -> k8s::policy::Server {
             port: k8s::policy::server::Port::Number(4191.try_into().unwrap()),
             proxy_protocol: Some(k8s::policy::server::ProxyProtocol::Http1),
         },
+        status: None,
     }
 }
 
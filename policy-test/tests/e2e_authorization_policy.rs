@@ -448,9 +448,13 @@ fn authz_policy(
             ..Default::default()
         },
         spec: k8s::policy::AuthorizationPolicySpec {
-            target_ref: target,
+            target_ref: Some(target),
+            target_selector: None,
             required_authentication_refs: authns.into_iter().collect(),
+            action: None,
+            mode: None,
         },
+        status: None,
     }
 }
 